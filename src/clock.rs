@@ -0,0 +1,45 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+// 学习计时使用的时钟；界面使用系统时钟，测试使用可手动拨动的时钟
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+// 手动时钟：只有调用 advance 时才走动，克隆出的时钟共享同一时间
+#[derive(Clone)]
+pub struct ManualClock {
+    start: Instant,
+    offset: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock { start: Instant::now(), offset: Rc::new(Cell::new(Duration::ZERO)) }
+    }
+
+    pub fn advance(&self, secs: f32) {
+        self.offset.set(self.offset.get() + Duration::from_secs_f32(secs));
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + self.offset.get()
+    }
+}
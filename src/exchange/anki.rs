@@ -0,0 +1,526 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use rusqlite::{params, Connection};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use super::ImportSheet;
+use crate::merge::{self, SaveMode};
+use crate::model::{FlashMemory, Word};
+use crate::storage::write_atomic;
+
+// Anki 牌组名中各层之间的分隔；18 版数据库中以 \x1f 保存
+const DECK_SEPARATOR: &str = "::";
+// 笔记各字段之间的分隔
+const FIELD_SEPARATOR: char = '\x1f';
+
+// 导出时笔记类型的字段，与表格编辑器的列相同（标签写入笔记的标签）
+const EXPORT_FIELDS: [&str; 7] = ["英文", "释义", "音标", "词性", "其他释义", "例句", "笔记"];
+
+// 一个牌组中可以导入的单词；path 为牌组名的各层，如 ["英语", "第一单元", "第一课"]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnkiDeck {
+    pub path: Vec<String>,
+    pub words: Vec<Word>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnkiPackage {
+    pub decks: Vec<AnkiDeck>,
+    pub skipped: usize, // 缺少英文或释义而跳过的笔记数
+}
+
+// 读取时解压到临时目录的数据库，用完删除
+struct TempFile(PathBuf);
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn temp_file() -> TempFile {
+    TempFile(std::env::temp_dir().join(format!("flash_memory-{}.anki", Uuid::new_v4())))
+}
+
+// 把字段中的 HTML 转为纯文本：换行标签与段落变为换行，去掉其他标签与 [sound:…]，解码常见实体
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(c) = rest.chars().next() {
+        if c == '<' {
+            let end = rest.find('>').map_or(rest.len(), |i| i + 1);
+            let tag = rest[1..end.saturating_sub(1).max(1)].trim().to_lowercase();
+            if tag.starts_with("br") || tag == "/div" || tag == "/p" || tag == "/li" {
+                text.push('\n');
+            }
+            rest = &rest[end..];
+        } else if c == '&' {
+            let entity = rest[1..].find(';').filter(|&i| i <= 8).map(|i| &rest[1..i + 1]);
+            let decoded = entity.and_then(|e| match e {
+                "nbsp" => Some(' '),
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => e.strip_prefix('#').and_then(|n| n.parse().ok()).and_then(char::from_u32),
+            });
+            match (entity, decoded) {
+                (Some(e), Some(d)) => {
+                    text.push(d);
+                    rest = &rest[e.len() + 2..];
+                }
+                _ => {
+                    text.push('&');
+                    rest = &rest[1..];
+                }
+            }
+        } else if rest.starts_with("[sound:") {
+            rest = &rest[rest.find(']').map_or(rest.len(), |i| i + 1)..];
+        } else {
+            text.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    text.lines().map(str::trim).filter(|l| !l.is_empty()).collect::<Vec<_>>().join("\n")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('\n', "<br>")
+}
+
+// 从 .apkg / .colpkg 中取出数据库：优先 18 版（zstd 压缩的 collection.anki21b），
+// 新版 Anki 导出的包中 collection.anki2 只是提示升级的占位
+fn extract_collection(path: &Path) -> Result<TempFile, Box<dyn Error>> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+    let file = temp_file();
+    let name = ["collection.anki21b", "collection.anki21", "collection.anki2"]
+        .into_iter()
+        .find(|n| archive.index_for_name(n).is_some())
+        .ok_or("不是 Anki 牌组文件：找不到 collection.anki2")?;
+    let mut entry = archive.by_name(name)?;
+    let mut bytes = Vec::new();
+    if name.ends_with('b') {
+        zstd::stream::copy_decode(&mut entry, &mut bytes)?;
+    } else {
+        entry.read_to_end(&mut bytes)?;
+    }
+    std::fs::write(&file.0, bytes)?;
+    Ok(file)
+}
+
+// 按标识查找的名称列表：笔记类型的各字段名，或牌组名的各层
+type NamesById = HashMap<i64, Vec<String>>;
+
+// 笔记类型的字段名与牌组名：11 版保存在 col 表的 JSON 中，18 版各占一张表
+fn read_layout(conn: &Connection) -> Result<(NamesById, NamesById), Box<dyn Error>> {
+    let mut fields = NamesById::new();
+    let mut decks = NamesById::new();
+    let version: i64 = conn.query_row("SELECT ver FROM col", [], |row| row.get(0))?;
+    if version >= 18 {
+        let mut stmt = conn.prepare("SELECT ntid, name FROM fields ORDER BY ntid, ord")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            fields.entry(row.get(0)?).or_default().push(row.get(1)?);
+        }
+        let mut stmt = conn.prepare("SELECT id, name FROM decks")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let name: String = row.get(1)?;
+            decks.insert(row.get(0)?, name.split(FIELD_SEPARATOR).map(str::to_string).collect());
+        }
+    } else {
+        let (models, deck_json): (String, String) = conn.query_row("SELECT models, decks FROM col", [], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        let models: HashMap<String, Value> = serde_json::from_str(&models)?;
+        for (id, model) in models {
+            let mut flds: Vec<&Value> = model["flds"].as_array().map(|a| a.iter().collect()).unwrap_or_default();
+            flds.sort_by_key(|f| f["ord"].as_i64());
+            fields.insert(id.parse()?, flds.iter().map(|f| f["name"].as_str().unwrap_or_default().to_string()).collect());
+        }
+        let deck_json: HashMap<String, Value> = serde_json::from_str(&deck_json)?;
+        for (id, deck) in deck_json {
+            let name = deck["name"].as_str().unwrap_or_default();
+            decks.insert(id.parse()?, name.split(DECK_SEPARATOR).map(str::to_string).collect());
+        }
+    }
+    Ok((fields, decks))
+}
+
+// 读取 .apkg / .colpkg 中的笔记：按字段名对应到单词的字段（与 CSV 表头相同的规则），
+// 笔记的标签作为单词的标签，按第一张卡片所在的牌组（筛选牌组中的卡片按原牌组）分组；媒体文件不导入
+pub fn read_package(path: &Path) -> Result<AnkiPackage, Box<dyn Error>> {
+    let file = extract_collection(path)?;
+    let conn = Connection::open(&file.0)?;
+    // 18 版数据库的名称列使用 Anki 自定义的排序规则
+    conn.create_collation("unicase", |a: &str, b: &str| a.to_lowercase().cmp(&b.to_lowercase()))?;
+    let (fields, deck_names) = read_layout(&conn)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT n.mid, n.tags, n.flds,
+                (SELECT CASE WHEN c.odid != 0 THEN c.odid ELSE c.did END FROM cards c WHERE c.nid = n.id ORDER BY c.ord LIMIT 1)
+         FROM notes n ORDER BY n.id",
+    )?;
+    let mut rows = stmt.query([])?;
+    let mut package = AnkiPackage::default();
+    let mut decks: BTreeMap<Vec<String>, Vec<Word>> = BTreeMap::new();
+    while let Some(row) = rows.next()? {
+        let (mid, tags, flds, did): (i64, String, String, Option<i64>) = (row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?);
+        let Some(deck) = did.and_then(|d| deck_names.get(&d)).filter(|d| !d[0].is_empty()) else {
+            package.skipped += 1;
+            continue;
+        };
+        let names = fields.get(&mid).cloned().unwrap_or_default();
+        let values: Vec<String> = flds.split(FIELD_SEPARATOR).map(html_to_text).collect();
+        let mut sheet = ImportSheet { rows: vec![names, values], has_header: true, mapping: Vec::new() };
+        sheet.guess_mapping();
+        let row = sheet.grid_rows().remove(0);
+        if !crate::grid::validate(std::slice::from_ref(&row)).is_empty() {
+            package.skipped += 1;
+            continue;
+        }
+        let mut word = row.to_word(&deck[0]);
+        for tag in tags.split_whitespace() {
+            if !word.tags.iter().any(|t| t == tag) {
+                word.tags.push(tag.to_string());
+            }
+        }
+        decks.entry(deck.clone()).or_default().push(word);
+    }
+    package.decks = decks.into_iter().map(|(path, words)| AnkiDeck { path, words }).collect();
+    Ok(package)
+}
+
+// 牌组导入后的位置：顶层牌组为分组，中间各层为文件夹，最后一层为单词表；只有一层时单词表与分组同名
+fn deck_location(path: &[String]) -> (&str, &[String], &str) {
+    let table = path.last().map(String::as_str).unwrap_or_default();
+    let folders = if path.len() > 2 { &path[1..path.len() - 1] } else { &[] };
+    (&path[0], folders, table)
+}
+
+// 牌组对应的单词表是否已存在
+pub fn deck_exists(memory: &FlashMemory, path: &[String]) -> bool {
+    let (group, folders, table) = deck_location(path);
+    let mut parent = None;
+    for name in folders {
+        match memory.child_folders(group, parent).iter().find(|f| f.name == *name) {
+            Some(folder) => parent = Some(folder.id),
+            None => return false,
+        }
+    }
+    memory.find_word_table(group, parent, table).is_some()
+}
+
+// 按牌组建立分组、文件夹与单词表并写入单词；已存在的同名单词表按 mode 合并。返回导入的单词数（新增与修改的单词，合并时重复的不计）。
+// 先在单词库的副本上导入，全部成功后才替换，中途出错时单词库保持不变
+pub fn import_decks(memory: &mut FlashMemory, decks: Vec<AnkiDeck>, mode: SaveMode) -> Result<usize, &'static str> {
+    let mut staged = memory.clone();
+    let mut count = 0;
+    for deck in decks {
+        let (group, folders, table) = deck_location(&deck.path);
+        staged.create_group_if_absent(group);
+        let mut parent = None;
+        for name in folders {
+            let existing = staged.child_folders(group, parent).iter().find(|f| f.name == *name).map(|f| f.id);
+            parent = Some(match existing {
+                Some(id) => id,
+                None => staged.create_folder(group, parent, name)?,
+            });
+        }
+        let (id, mode, old) = match staged.find_word_table(group, parent, table) {
+            Some(table) => (table.id, mode, table.words.clone()),
+            None => (staged.create_word_table(group, parent, table)?, SaveMode::Replace, Vec::new()),
+        };
+        let diff = merge::diff_words(&old, &merge::merge_words(&old, deck.words.clone(), mode));
+        count += diff.added.len() + diff.changed.len();
+        staged.save_words_to_table(group, id, deck.words, mode)?;
+    }
+    *memory = staged;
+    Ok(count)
+}
+
+// 11 版数据库结构，Anki 各版本都能导入
+const SCHEMA: &str = "
+CREATE TABLE col (id integer primary key, crt integer not null, mod integer not null, scm integer not null,
+    ver integer not null, dty integer not null, usn integer not null, ls integer not null, conf text not null,
+    models text not null, decks text not null, dconf text not null, tags text not null);
+CREATE TABLE notes (id integer primary key, guid text not null, mid integer not null, mod integer not null,
+    usn integer not null, tags text not null, flds text not null, sfld integer not null, csum integer not null,
+    flags integer not null, data text not null);
+CREATE TABLE cards (id integer primary key, nid integer not null, did integer not null, ord integer not null,
+    mod integer not null, usn integer not null, type integer not null, queue integer not null, due integer not null,
+    ivl integer not null, factor integer not null, reps integer not null, lapses integer not null, left integer not null,
+    odue integer not null, odid integer not null, flags integer not null, data text not null);
+CREATE TABLE revlog (id integer primary key, cid integer not null, usn integer not null, ease integer not null,
+    ivl integer not null, lastIvl integer not null, factor integer not null, time integer not null, type integer not null);
+CREATE TABLE graves (usn integer not null, oid integer not null, type integer not null);
+CREATE INDEX ix_notes_usn on notes (usn);
+CREATE INDEX ix_cards_usn on cards (usn);
+CREATE INDEX ix_revlog_usn on revlog (usn);
+CREATE INDEX ix_cards_nid on cards (nid);
+CREATE INDEX ix_cards_sched on cards (did, queue, due);
+CREATE INDEX ix_revlog_cid on revlog (cid);
+CREATE INDEX ix_notes_csum on notes (csum);
+";
+
+// 单词写成笔记的各字段（HTML）
+fn note_fields(word: &Word) -> Vec<String> {
+    let examples: Vec<String> = word
+        .examples
+        .iter()
+        .map(|e| if e.translation.is_empty() { e.sentence.clone() } else { format!("{} = {}", e.sentence, e.translation) })
+        .collect();
+    [
+        word.english.clone(),
+        word.chinese.clone(),
+        word.phonetic.clone(),
+        word.part_of_speech.clone(),
+        word.meanings.join("\n"),
+        examples.join("\n"),
+        word.notes.clone(),
+    ]
+    .iter()
+    .map(|f| escape_html(f))
+    .collect()
+}
+
+// Anki 用第一个字段纯文本的 SHA-1 前 8 位查重
+fn field_checksum(text: &str) -> i64 {
+    let digest = sha1_smol::Sha1::from(text).digest().bytes();
+    u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]) as i64
+}
+
+fn deck_json(id: i64, name: &str, now: i64) -> Value {
+    json!({
+        "id": id, "name": name, "mod": now, "usn": -1, "desc": "", "dyn": 0, "conf": 1, "collapsed": false,
+        "browserCollapsed": false, "extendNew": 0, "extendRev": 0,
+        "newToday": [0, 0], "revToday": [0, 0], "lrnToday": [0, 0], "timeToday": [0, 0],
+    })
+}
+
+fn model_json(id: i64, deck: i64, now: i64) -> Value {
+    let flds: Vec<Value> = EXPORT_FIELDS
+        .iter()
+        .enumerate()
+        .map(|(ord, name)| json!({"name": name, "ord": ord, "sticky": false, "rtl": false, "font": "Arial", "size": 20, "media": []}))
+        .collect();
+    let extras: String = EXPORT_FIELDS[2..].iter().map(|f| format!("{{{{#{f}}}}}<br>{{{{{f}}}}}{{{{/{f}}}}}")).collect();
+    json!({
+        "id": id, "name": "闪记单词", "type": 0, "mod": now, "usn": -1, "sortf": 0, "did": deck, "flds": flds,
+        "tmpls": [{
+            "name": "卡片 1", "ord": 0, "qfmt": "{{英文}}", "afmt": format!("{{{{FrontSide}}}}<hr id=answer>{{{{释义}}}}{}", extras),
+            "bqfmt": "", "bafmt": "", "did": null,
+        }],
+        "css": ".card { font-family: arial; font-size: 20px; text-align: center; color: black; background-color: white; }",
+        "latexPre": "\\documentclass[12pt]{article}\n\\special{papersize=3in,5in}\n\\usepackage[utf8]{inputenc}\n\\usepackage{amssymb,amsmath}\n\\pagestyle{empty}\n\\setlength{\\parindent}{0in}\n\\begin{document}\n",
+        "latexPost": "\\end{document}", "latexsvg": false, "req": [[0, "any", [0]]], "tags": [], "vers": [],
+    })
+}
+
+fn deck_config_json() -> Value {
+    json!({"1": {
+        "id": 1, "name": "Default", "mod": 0, "usn": 0, "maxTaken": 60, "autoplay": true, "timer": 0, "replayq": true, "dyn": false,
+        "new": {"bury": false, "delays": [1.0, 10.0], "initialFactor": 2500, "ints": [1, 4, 0], "order": 1, "perDay": 20},
+        "rev": {"bury": false, "ease4": 1.3, "ivlFct": 1.0, "maxIvl": 36500, "perDay": 200, "hardFactor": 1.2},
+        "lapse": {"delays": [10.0], "leechAction": 1, "leechFails": 8, "minInt": 1, "mult": 0.0},
+    }})
+}
+
+// 把分组导出为 Anki 可以导入的 .apkg：分组为顶层牌组，文件夹与单词表为子牌组，单词都作为新卡片。返回导出的单词数
+pub fn write_package(path: &Path, memory: &FlashMemory, group: &str) -> Result<usize, Box<dyn Error>> {
+    let now = Local::now();
+    let (secs, base) = (now.timestamp(), now.timestamp_millis());
+    let model_id = base;
+
+    // 各单词表的牌组名，上层的分组与文件夹也各是一个牌组
+    let mut deck_ids: BTreeMap<String, i64> = BTreeMap::new();
+    let mut tables = Vec::new();
+    for table in memory.tables_under(group, None) {
+        let mut path = vec![table.name.clone()];
+        let mut folder = table.folder;
+        while let Some(f) = folder.and_then(|id| memory.get_folder(group, id)) {
+            path.insert(0, f.name.clone());
+            folder = f.parent;
+        }
+        path.insert(0, group.to_string());
+        for depth in 1..=path.len() {
+            let name = path[..depth].join(DECK_SEPARATOR);
+            let next = base + deck_ids.len() as i64 + 1;
+            deck_ids.entry(name).or_insert(next);
+        }
+        tables.push((deck_ids[&path.join(DECK_SEPARATOR)], table));
+    }
+    if deck_ids.is_empty() {
+        deck_ids.insert(group.to_string(), base + 1);
+    }
+    let mut decks = serde_json::Map::new();
+    decks.insert("1".to_string(), deck_json(1, "Default", secs));
+    for (name, id) in &deck_ids {
+        decks.insert(id.to_string(), deck_json(*id, name, secs));
+    }
+    let top_deck = deck_ids[group];
+    let models = json!({ model_id.to_string(): model_json(model_id, top_deck, secs) });
+    let conf = json!({
+        "nextPos": 1, "estTimes": true, "activeDecks": [1], "sortType": "noteFld", "timeLim": 0, "sortBackwards": false,
+        "addToCur": true, "curDeck": 1, "newSpread": 0, "dueCounts": true, "curModel": model_id, "collapseTime": 1200,
+    });
+
+    let file = temp_file();
+    let conn = Connection::open(&file.0)?;
+    conn.execute_batch(SCHEMA)?;
+    let crt = now.date_naive().and_hms_opt(0, 0, 0).and_then(|t| t.and_local_timezone(Local).single()).map_or(secs, |t| t.timestamp());
+    conn.execute(
+        "INSERT INTO col VALUES (1, ?1, ?2, ?2, 11, 0, 0, 0, ?3, ?4, ?5, ?6, '{}')",
+        params![crt, base, conf.to_string(), models.to_string(), Value::Object(decks).to_string(), deck_config_json().to_string()],
+    )?;
+    let mut count = 0;
+    for (deck_id, table) in tables {
+        for word in &table.words {
+            let id = base + count as i64;
+            let fields = note_fields(word);
+            let tags = if word.tags.is_empty() { String::new() } else { format!(" {} ", word.tags.join(" ")) };
+            conn.execute(
+                "INSERT INTO notes VALUES (?1, ?2, ?3, ?4, -1, ?5, ?6, ?7, ?8, 0, '')",
+                params![
+                    id, word.id.to_string(), model_id, secs, tags, fields.join(&FIELD_SEPARATOR.to_string()), word.english,
+                    field_checksum(&word.english),
+                ],
+            )?;
+            conn.execute(
+                "INSERT INTO cards VALUES (?1, ?1, ?2, 0, ?3, -1, 0, 0, ?4, 0, 0, 0, 0, 0, 0, 0, 0, '')",
+                params![id, deck_id, secs, count as i64 + 1],
+            )?;
+            count += 1;
+        }
+    }
+    conn.close().map_err(|(_, e)| e)?;
+
+    let mut archive = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    archive.start_file("collection.anki2", options)?;
+    archive.write_all(&std::fs::read(&file.0)?)?;
+    archive.start_file("media", options)?;
+    archive.write_all(b"{}")?;
+    write_atomic(path, &archive.finish()?.into_inner())?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Example;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+    }
+
+    fn pairs(deck: &AnkiDeck) -> Vec<(&str, &str)> {
+        deck.words.iter().map(|w| (w.english.as_str(), w.chinese.as_str())).collect()
+    }
+
+    #[test]
+    fn html_fields_become_plain_text() {
+        assert_eq!(html_to_text("苹果<br>苹果树"), "苹果\n苹果树");
+        assert_eq!(html_to_text("<div><b>look</b>&nbsp;up</div><div>查阅</div>"), "look up\n查阅");
+        assert_eq!(html_to_text("ˈæpl [sound:apple.mp3]"), "ˈæpl");
+        assert_eq!(html_to_text("a &lt; b &amp;&#32;c & d"), "a < b & c & d");
+    }
+
+    #[test]
+    fn legacy_package_maps_decks_and_fields() {
+        let package = read_package(&fixture("anki_legacy.apkg")).unwrap();
+        let paths: Vec<String> = package.decks.iter().map(|d| d.path.join("::")).collect();
+        assert_eq!(paths, vec!["日语", "英语::Unit 1::Lesson 1", "英语::第一课"]);
+        assert_eq!(package.skipped, 1);
+
+        // 筛选牌组中的卡片归入原牌组
+        let lesson = &package.decks[2];
+        assert_eq!(pairs(lesson), vec![("apple", "苹果 | 苹果树"), ("look up", "查阅"), ("dog", "狗 & 犬")]);
+        assert_eq!(lesson.words[0].tags, vec!["水果", "初级"]);
+        assert!(lesson.words.iter().all(|w| w.group == "英语"));
+
+        // 自定义笔记类型按字段名对应
+        let ice = &package.decks[1].words[0];
+        assert_eq!((ice.english.as_str(), ice.chinese.as_str(), ice.phonetic.as_str()), ("ice cream", "冰淇淋", "ˈaɪs kriːm"));
+        assert_eq!(
+            ice.examples,
+            vec![
+                Example { sentence: "I like ice cream.".to_string(), translation: "我喜欢冰淇淋。".to_string() },
+                Example { sentence: "Ice cream melts.".to_string(), translation: String::new() },
+            ]
+        );
+    }
+
+    #[test]
+    fn modern_collection_is_preferred_over_the_placeholder() {
+        let package = read_package(&fixture("anki_modern.colpkg")).unwrap();
+        assert_eq!(package.decks.len(), 1);
+        assert_eq!(package.decks[0].path, vec!["法语", "基础"]);
+        assert_eq!(pairs(&package.decks[0]), vec![("bonjour", "你好"), ("merci", "谢谢")]);
+        assert_eq!(package.decks[0].words[0].tags, vec!["问候"]);
+
+        let err = read_package(&fixture("v6.json")).unwrap_err();
+        assert!(!err.to_string().is_empty());
+    }
+
+    #[test]
+    fn decks_become_groups_folders_and_tables() {
+        let package = read_package(&fixture("anki_legacy.apkg")).unwrap();
+        let mut memory = FlashMemory::new();
+        let lesson = memory.create_word_table("英语", None, "第一课").unwrap();
+        memory.add_words_to_table("英语", lesson, vec![Word::new("apple", "苹果", "英语")]).unwrap();
+        assert!(deck_exists(&memory, &package.decks[2].path));
+        assert!(!deck_exists(&memory, &package.decks[1].path));
+
+        // 已有的 apple 合并了释义，算作修改
+        assert_eq!(import_decks(&mut memory, package.decks.clone(), SaveMode::Merge).unwrap(), 5);
+        assert_eq!(memory.get_groups(), vec!["英语", "日语"]);
+        let words = &memory.get_word_table("英语", lesson).unwrap().words;
+        assert_eq!(words.len(), 3);
+        assert_eq!(words[0].chinese, "苹果 | 苹果树");
+        let unit = memory.child_folders("英语", None)[0].id;
+        assert_eq!(memory.get_folder("英语", unit).unwrap().name, "Unit 1");
+        assert_eq!(memory.find_word_table("英语", Some(unit), "Lesson 1").unwrap().words.len(), 1);
+        assert_eq!(memory.find_word_table("日语", None, "日语").unwrap().words[0].english, "ねこ");
+
+        // 再次导入时重复的单词不计
+        let before = memory.clone();
+        assert_eq!(import_decks(&mut memory, package.decks.clone(), SaveMode::Append).unwrap(), 0);
+        assert_eq!(import_decks(&mut memory, package.decks, SaveMode::Merge).unwrap(), 0);
+        assert_eq!(memory, before);
+    }
+
+    #[test]
+    fn exported_group_imports_back() {
+        let mut memory = FlashMemory::new();
+        let lesson = memory.create_word_table("英语", None, "第一课").unwrap();
+        let unit = memory.create_folder("英语", None, "第一单元").unwrap();
+        let nested = memory.create_word_table("英语", Some(unit), "第二课").unwrap();
+        let mut apple = Word::new("apple", "苹果 <红>", "英语");
+        apple.phonetic = "ˈæpl".to_string();
+        apple.meanings = vec!["苹果树".to_string(), "苹果公司".to_string()];
+        apple.examples = vec![Example { sentence: "I ate an apple.".to_string(), translation: "我吃了一个苹果。".to_string() }];
+        apple.notes = "可数名词\n常见水果".to_string();
+        apple.tags = vec!["水果".to_string(), "初级".to_string()];
+        memory.add_words_to_table("英语", lesson, vec![apple.clone()]).unwrap();
+        memory.add_words_to_table("英语", nested, vec![Word::new("cat", "猫", "英语")]).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("英语.apkg");
+        assert_eq!(write_package(&path, &memory, "英语").unwrap(), 2);
+        let package = read_package(&path).unwrap();
+        let paths: Vec<String> = package.decks.iter().map(|d| d.path.join("::")).collect();
+        assert_eq!(paths, vec!["英语::第一单元::第二课", "英语::第一课"]);
+        assert_eq!(package.decks[1].words, vec![Word { id: package.decks[1].words[0].id, ..apple }]);
+
+        let conn = Connection::open(&extract_collection(&path).unwrap().0).unwrap();
+        let (sfld, csum): (String, i64) = conn.query_row("SELECT sfld, csum FROM notes LIMIT 1", [], |r| Ok((r.get(0)?, r.get(1)?))).unwrap();
+        assert_eq!(csum, field_checksum(&sfld));
+        let decks: i64 = conn.query_row("SELECT COUNT(DISTINCT did) FROM cards", [], |r| r.get(0)).unwrap();
+        assert_eq!(decks, 2);
+    }
+}
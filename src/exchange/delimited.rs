@@ -0,0 +1,173 @@
+use std::error::Error;
+use std::path::Path;
+
+use encoding_rs::GBK;
+
+use crate::storage::write_atomic;
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+// CSV/TSV 文件的文字编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Utf8,
+    Utf8Bom,
+    Gbk, // Windows 中文版 Excel 另存的 CSV
+}
+
+impl TextEncoding {
+    pub const ALL: [TextEncoding; 3] = [TextEncoding::Utf8, TextEncoding::Utf8Bom, TextEncoding::Gbk];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf8Bom => "UTF-8（带 BOM）",
+            TextEncoding::Gbk => "GBK",
+        }
+    }
+
+    // 按该编码读取；UTF-8 中的非法字节替换为 �
+    pub fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            TextEncoding::Utf8 | TextEncoding::Utf8Bom => {
+                String::from_utf8_lossy(bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes)).into_owned()
+            }
+            TextEncoding::Gbk => GBK.decode_without_bom_handling(bytes).0.into_owned(),
+        }
+    }
+}
+
+// 有 BOM 的为带 BOM 的 UTF-8，否则能按 UTF-8 读取的为 UTF-8，其余按 GBK
+pub fn detect_encoding(bytes: &[u8]) -> TextEncoding {
+    if bytes.starts_with(UTF8_BOM) {
+        TextEncoding::Utf8Bom
+    } else if std::str::from_utf8(bytes).is_ok() {
+        TextEncoding::Utf8
+    } else {
+        TextEncoding::Gbk
+    }
+}
+
+// 单元格之间的分隔符
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimiter {
+    Comma,
+    Tab,
+    Semicolon, // 部分地区的 Excel 用分号分隔 CSV
+}
+
+impl Delimiter {
+    pub const ALL: [Delimiter; 3] = [Delimiter::Comma, Delimiter::Tab, Delimiter::Semicolon];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Delimiter::Comma => "逗号",
+            Delimiter::Tab => "制表符",
+            Delimiter::Semicolon => "分号",
+        }
+    }
+
+    fn byte(self) -> u8 {
+        match self {
+            Delimiter::Comma => b',',
+            Delimiter::Tab => b'\t',
+            Delimiter::Semicolon => b';',
+        }
+    }
+
+    // .tsv 文件用制表符，其余用逗号
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("tsv") => Delimiter::Tab,
+            _ => Delimiter::Comma,
+        }
+    }
+}
+
+// 按第一行中出现最多的分隔符判断
+pub fn detect_delimiter(text: &str) -> Delimiter {
+    let first = text.lines().next().unwrap_or_default();
+    Delimiter::ALL
+        .into_iter()
+        .max_by_key(|d| first.matches(d.byte() as char).count())
+        .filter(|d| first.contains(d.byte() as char))
+        .unwrap_or(Delimiter::Comma)
+}
+
+// 读取各行单元格，各行的列数可以不同；带引号的单元格中可以有分隔符与换行
+pub fn read_rows(text: &str, delimiter: Delimiter) -> Result<Vec<Vec<String>>, Box<dyn Error>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter.byte())
+        .from_reader(text.as_bytes());
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        rows.push(record?.iter().map(str::to_string).collect());
+    }
+    Ok(rows)
+}
+
+// 写成带 BOM 的 UTF-8，Excel 打开时中文不会乱码
+pub fn write_rows(path: &Path, rows: &[Vec<String>], delimiter: Delimiter) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv::WriterBuilder::new().flexible(true).delimiter(delimiter.byte()).from_writer(UTF8_BOM.to_vec());
+    for row in rows {
+        writer.write_record(row)?;
+    }
+    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    write_atomic(path, &bytes)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> Vec<u8> {
+        std::fs::read(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+    }
+
+    #[test]
+    fn encodings_are_detected() {
+        let gbk = fixture("words_gbk.csv");
+        assert_eq!(detect_encoding(&gbk), TextEncoding::Gbk);
+        let text = TextEncoding::Gbk.decode(&gbk);
+        assert!(text.starts_with("英文,释义"));
+
+        let bom = fixture("words_bom.tsv");
+        assert_eq!(detect_encoding(&bom), TextEncoding::Utf8Bom);
+        assert!(TextEncoding::Utf8Bom.decode(&bom).starts_with("word\t"));
+        assert_eq!(detect_encoding("apple,苹果".as_bytes()), TextEncoding::Utf8);
+    }
+
+    #[test]
+    fn rows_keep_quoted_delimiters_and_newlines() {
+        let text = TextEncoding::Gbk.decode(&fixture("words_gbk.csv"));
+        assert_eq!(detect_delimiter(&text), Delimiter::Comma);
+        let rows = read_rows(&text, Delimiter::Comma).unwrap();
+        assert_eq!(rows[0], ["英文", "释义", "标签"]);
+        assert_eq!(rows[1], ["apple", "苹果", "水果"]);
+        assert_eq!(rows[2], ["look up", "查阅，查找", ""]);
+        assert_eq!(rows[3], ["1,000", "一千\n数字"]);
+
+        let text = TextEncoding::Utf8Bom.decode(&fixture("words_bom.tsv"));
+        assert_eq!(detect_delimiter(&text), Delimiter::Tab);
+        assert_eq!(read_rows(&text, Delimiter::Tab).unwrap()[1], ["ice cream", "冰淇淋", "ˈaɪs kriːm"]);
+        assert_eq!(detect_delimiter("a;b;c"), Delimiter::Semicolon);
+        assert_eq!(detect_delimiter("apple"), Delimiter::Comma);
+    }
+
+    #[test]
+    fn written_rows_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let rows = vec![vec!["英文".to_string(), "释义".to_string()], vec!["a, \"b\"".to_string(), "甲\n乙".to_string()]];
+        for (name, delimiter) in [("out.csv", Delimiter::Comma), ("out.tsv", Delimiter::Tab)] {
+            let path = dir.path().join(name);
+            write_rows(&path, &rows, delimiter).unwrap();
+            let bytes = std::fs::read(&path).unwrap();
+            assert_eq!(detect_encoding(&bytes), TextEncoding::Utf8Bom);
+            assert_eq!(Delimiter::for_path(&path), delimiter);
+            assert_eq!(read_rows(&TextEncoding::Utf8Bom.decode(&bytes), delimiter).unwrap(), rows);
+        }
+    }
+}
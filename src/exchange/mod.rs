@@ -0,0 +1,228 @@
+// 单词表的导入与导出：外部文件先读成若干行单元格，再按列对应关系转换为单词
+pub mod anki;
+pub mod delimited;
+pub mod xlsx;
+
+use crate::grid::{self, Column, GridRow, ITEM_SEPARATOR};
+use crate::model::{FlashMemory, Word};
+use crate::quiz;
+
+// 导出分组时记录单词所在单词表的列
+pub const TABLE_COLUMN: &str = "单词表";
+
+// 表头中可以识别的列名（不区分大小写）
+fn aliases(column: Column) -> &'static [&'static str] {
+    match column {
+        Column::English => &["英文", "单词", "english", "word", "term", "front"],
+        Column::Chinese => &["释义", "中文", "chinese", "meaning", "definition", "translation", "back"],
+        Column::Phonetic => &["音标", "phonetic", "pronunciation", "ipa"],
+        Column::PartOfSpeech => &["词性", "part of speech", "pos"],
+        Column::Meanings => &["其他释义", "meanings"],
+        Column::Examples => &["例句", "example", "examples", "sentence"],
+        Column::Notes => &["笔记", "备注", "notes", "note"],
+        Column::Tags => &["标签", "tags", "tag"],
+    }
+}
+
+// 按列名猜测对应的字段
+pub fn column_for_header(header: &str) -> Option<Column> {
+    let header = quiz::normalize(header);
+    Column::ALL.into_iter().find(|&c| aliases(c).contains(&header.as_str()))
+}
+
+// 第一行的非空单元格都是可识别且互不相同的列名，或至少两个不同的列名时，视为表头
+pub fn looks_like_header(row: &[String]) -> bool {
+    let filled: Vec<&String> = row.iter().filter(|c| !c.trim().is_empty()).collect();
+    let mut columns: Vec<Column> = filled.iter().filter_map(|c| column_for_header(c)).collect();
+    columns.dedup();
+    let distinct = columns.iter().enumerate().filter(|(i, c)| !columns[..*i].contains(c)).count();
+    distinct >= 2 || (distinct >= 1 && distinct == filled.len())
+}
+
+// 待导入的表格：原始各行、第一行是否为表头，以及每一列对应的字段（None 表示忽略该列）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportSheet {
+    pub rows: Vec<Vec<String>>,
+    pub has_header: bool,
+    pub mapping: Vec<Option<Column>>,
+}
+
+impl ImportSheet {
+    // 自动识别表头并猜测各列对应的字段：有表头时按列名，没有时第一列为英文、第二列为释义
+    pub fn new(rows: Vec<Vec<String>>) -> Self {
+        let has_header = rows.first().is_some_and(|r| looks_like_header(r));
+        let mut sheet = ImportSheet { rows, has_header, mapping: Vec::new() };
+        sheet.guess_mapping();
+        sheet
+    }
+
+    pub fn column_count(&self) -> usize {
+        self.rows.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    pub fn header(&self) -> Option<&[String]> {
+        self.rows.first().filter(|_| self.has_header).map(Vec::as_slice)
+    }
+
+    pub fn data_rows(&self) -> &[Vec<String>] {
+        &self.rows[(self.has_header as usize).min(self.rows.len())..]
+    }
+
+    pub fn guess_mapping(&mut self) {
+        let count = self.column_count();
+        self.mapping = match self.header() {
+            Some(header) => {
+                let mut mapping: Vec<Option<Column>> = Vec::new();
+                for i in 0..count {
+                    // 同一字段只对应第一个同名列
+                    let column = header.get(i).and_then(|h| column_for_header(h)).filter(|c| !mapping.contains(&Some(*c)));
+                    mapping.push(column);
+                }
+                // 列名中没有英文或释义时，依次用前面未对应的列
+                for column in [Column::English, Column::Chinese] {
+                    if !mapping.contains(&Some(column)) {
+                        if let Some(free) = mapping.iter_mut().find(|m| m.is_none()) {
+                            *free = Some(column);
+                        }
+                    }
+                }
+                mapping
+            }
+            None => (0..count).map(|i| [Column::English, Column::Chinese].get(i).copied()).collect(),
+        };
+    }
+
+    // 按列对应关系转换为表格编辑器的行；多列对应同一字段时合并为多项
+    pub fn grid_rows(&self) -> Vec<GridRow> {
+        self.data_rows()
+            .iter()
+            .map(|cells| {
+                let mut row = GridRow::default();
+                for (value, column) in cells.iter().zip(&self.mapping) {
+                    let (Some(column), value) = (column, value.trim()) else { continue };
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let cell = row.cell_mut(*column);
+                    if !cell.is_empty() {
+                        cell.push_str(ITEM_SEPARATOR);
+                    }
+                    cell.push_str(&value.lines().map(str::trim).collect::<Vec<_>>().join(ITEM_SEPARATOR));
+                }
+                row
+            })
+            .collect()
+    }
+
+    // 返回可以导入的单词，以及缺少英文或释义而跳过的行数（空行不计）
+    pub fn words(&self, group: &str) -> (Vec<Word>, usize) {
+        let mut rows = self.grid_rows();
+        let mut invalid: Vec<usize> = grid::validate(&rows).into_iter().map(|e| e.row).collect();
+        invalid.dedup();
+        for &row in invalid.iter().rev() {
+            rows.remove(row);
+        }
+        (grid::words_from_rows(&rows, group), invalid.len())
+    }
+}
+
+fn header_row() -> Vec<String> {
+    Column::ALL.iter().map(|c| c.label().to_string()).collect()
+}
+
+// 导出单词表：第一行为表头，各列与表格编辑器相同
+pub fn table_rows(words: &[Word]) -> Vec<Vec<String>> {
+    let mut rows = vec![header_row()];
+    rows.extend(grid::rows_from_words(words).into_iter().map(|r| r.cells.to_vec()));
+    rows
+}
+
+// 导出分组：按目录顺序导出各层单词表，第一列记录单词所在的单词表
+pub fn group_rows(memory: &FlashMemory, group: &str) -> Vec<Vec<String>> {
+    let mut rows = vec![std::iter::once(TABLE_COLUMN.to_string()).chain(header_row()).collect()];
+    for table in memory.tables_under(group, None) {
+        for row in grid::rows_from_words(&table.words) {
+            rows.push(std::iter::once(table.name.clone()).chain(row.cells).collect());
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(lines: &[&[&str]]) -> Vec<Vec<String>> {
+        lines.iter().map(|l| l.iter().map(|c| c.to_string()).collect()).collect()
+    }
+
+    #[test]
+    fn header_is_detected_and_mapped_by_name() {
+        let sheet = ImportSheet::new(rows(&[
+            &["Word", "备注", "Meaning", "Tags", "Examples", "Examples"],
+            &["apple", "常见水果", "苹果", "水果 初级", "I ate an apple.", "An apple a day."],
+            &["", "", "梨"],
+            &[],
+        ]));
+        assert!(sheet.has_header);
+        assert_eq!(
+            sheet.mapping,
+            vec![Some(Column::English), Some(Column::Notes), Some(Column::Chinese), Some(Column::Tags), Some(Column::Examples), None]
+        );
+        let (words, skipped) = sheet.words("英语");
+        assert_eq!(skipped, 1);
+        assert_eq!((words[0].english.as_str(), words[0].chinese.as_str()), ("apple", "苹果"));
+        assert_eq!(words[0].notes, "常见水果");
+        assert_eq!(words[0].tags, vec!["水果", "初级"]);
+        assert_eq!(words[0].examples.len(), 1);
+
+        // 表头中没有英文与释义时用前面未对应的列
+        let mut sheet = ImportSheet { rows: rows(&[&["Question", "Tags", "Answer"]]), has_header: true, mapping: Vec::new() };
+        sheet.guess_mapping();
+        assert_eq!(sheet.mapping, vec![Some(Column::English), Some(Column::Tags), Some(Column::Chinese)]);
+    }
+
+    #[test]
+    fn without_header_first_columns_are_english_and_chinese() {
+        assert!(!looks_like_header(&rows(&[&["word", "单词"]])[0]));
+        assert!(!looks_like_header(&rows(&[&["apple", "苹果"]])[0]));
+        assert!(looks_like_header(&rows(&[&["英文", ""]])[0]));
+
+        let mut sheet = ImportSheet::new(rows(&[&["apple", "苹果", "ˈæpl"], &["pear", "梨"]]));
+        assert!(!sheet.has_header);
+        assert_eq!(sheet.mapping, vec![Some(Column::English), Some(Column::Chinese), None]);
+        sheet.mapping[2] = Some(Column::Phonetic);
+        let (words, skipped) = sheet.words("英语");
+        assert_eq!((words.len(), skipped), (2, 0));
+        assert_eq!(words[0].phonetic, "ˈæpl");
+
+        // 两列都对应同一字段时合并为多项
+        sheet.mapping = vec![Some(Column::English), Some(Column::Chinese), Some(Column::Chinese)];
+        assert_eq!(sheet.words("英语").0[0].chinese, "苹果 | ˈæpl");
+    }
+
+    #[test]
+    fn exported_rows_import_back() {
+        let mut memory = FlashMemory::new();
+        let lesson = memory.create_word_table("英语", None, "第一课").unwrap();
+        let unit = memory.create_folder("英语", None, "第一单元").unwrap();
+        let nested = memory.create_word_table("英语", Some(unit), "第二课").unwrap();
+        let mut apple = Word::new("apple", "苹果", "英语");
+        apple.meanings = vec!["苹果树".to_string()];
+        apple.tags = vec!["水果".to_string()];
+        memory.add_words_to_table("英语", lesson, vec![apple.clone()]).unwrap();
+        memory.add_words_to_table("英语", nested, vec![Word::new("cat", "猫", "英语")]).unwrap();
+
+        let sheet = ImportSheet::new(table_rows(&memory.get_word_table("英语", lesson).unwrap().words));
+        assert!(sheet.has_header);
+        let (words, _) = sheet.words("英语");
+        assert_eq!(words, vec![Word { id: words[0].id, ..apple }]);
+
+        let group = group_rows(&memory, "英语");
+        assert_eq!(group[0][0], TABLE_COLUMN);
+        assert_eq!(group[1][..3], ["第二课", "cat", "猫"]);
+        assert_eq!(group[2][..3], ["第一课", "apple", "苹果"]);
+        let (words, _) = ImportSheet::new(group).words("英语");
+        assert_eq!(words.len(), 2);
+    }
+}
@@ -0,0 +1,170 @@
+use std::error::Error;
+use std::path::Path;
+
+use calamine::{open_workbook_auto, Reader};
+use rust_xlsxwriter::{Format, Workbook};
+
+use super::table_rows;
+use crate::model::FlashMemory;
+use crate::storage::write_atomic;
+
+// Excel 对工作表名的限制
+const MAX_SHEET_NAME: usize = 31;
+const INVALID_SHEET_CHARS: [char; 7] = ['[', ']', ':', '*', '?', '/', '\\'];
+// 导出时的列宽（以字符计，中文按两个字符）
+const MIN_COLUMN_WIDTH: usize = 8;
+const MAX_COLUMN_WIDTH: usize = 50;
+
+// 工作簿中的一个工作表：名称与各行单元格（去掉行尾的空单元格）
+#[derive(Debug, Clone, PartialEq)]
+pub struct SheetRows {
+    pub name: String,
+    pub rows: Vec<Vec<String>>,
+}
+
+// 读取 .xlsx（以及 .xls、.ods）中的所有工作表；数字按显示的形式转为文本，如 1 而不是 1.0
+pub fn read_workbook(path: &Path) -> Result<Vec<SheetRows>, Box<dyn Error>> {
+    let mut workbook = open_workbook_auto(path)?;
+    let mut sheets = Vec::new();
+    for name in workbook.sheet_names() {
+        let range = workbook.worksheet_range(&name)?;
+        let rows = range
+            .rows()
+            .map(|row| {
+                let mut cells: Vec<String> = row.iter().map(|c| c.to_string().trim().to_string()).collect();
+                while cells.last().is_some_and(String::is_empty) {
+                    cells.pop();
+                }
+                cells
+            })
+            .collect();
+        sheets.push(SheetRows { name, rows });
+    }
+    Ok(sheets)
+}
+
+// 把单词表名改成可用的工作表名：去掉 Excel 不允许的字符，截到 31 个字符，与已用的名称重复时加上 2、3…
+fn sheet_name(table: &str, used: &[String]) -> String {
+    let cleaned: String = table.chars().filter(|c| !INVALID_SHEET_CHARS.contains(c)).collect();
+    let cleaned = cleaned.trim_matches('\'').trim();
+    let base = if cleaned.is_empty() { "单词表" } else { cleaned };
+    let taken = |name: &str| used.iter().any(|u| u.to_lowercase() == name.to_lowercase());
+    let mut idx = 1;
+    loop {
+        let suffix = if idx == 1 { String::new() } else { idx.to_string() };
+        let name: String = base.chars().take(MAX_SHEET_NAME - suffix.chars().count()).chain(suffix.chars()).collect();
+        if !taken(&name) {
+            return name;
+        }
+        idx += 1;
+    }
+}
+
+// 文本的显示宽度，中日韩文字按两个字符
+fn display_width(text: &str) -> usize {
+    text.chars().map(|c| if c >= '\u{2E80}' { 2 } else { 1 }).sum()
+}
+
+// 把分组导出为工作簿：按目录顺序每个单词表一个工作表，第一行为加粗并冻结的表头，列宽按内容调整。返回导出的单词数
+pub fn write_workbook(path: &Path, memory: &FlashMemory, group: &str) -> Result<usize, Box<dyn Error>> {
+    let mut workbook = Workbook::new();
+    let header = Format::new().set_bold();
+    let mut used = Vec::new();
+    let mut count = 0;
+    for table in memory.tables_under(group, None) {
+        let name = sheet_name(&table.name, &used);
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(&name)?;
+        used.push(name);
+        let rows = table_rows(&table.words);
+        for (r, row) in rows.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                if r == 0 {
+                    sheet.write_string_with_format(r as u32, c as u16, cell, &header)?;
+                } else if !cell.is_empty() {
+                    sheet.write_string(r as u32, c as u16, cell)?;
+                }
+            }
+        }
+        for c in 0..rows[0].len() {
+            let width = rows.iter().filter_map(|r| r.get(c)).map(|s| display_width(s)).max().unwrap_or(0) + 2;
+            sheet.set_column_width(c as u16, width.clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH) as f64)?;
+        }
+        sheet.set_freeze_panes(1, 0)?;
+        count += table.words.len();
+    }
+    if used.is_empty() {
+        workbook.add_worksheet().set_name(sheet_name(group, &used))?;
+    }
+    write_atomic(path, &workbook.save_to_buffer()?)?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchange::ImportSheet;
+    use crate::model::Word;
+
+    #[test]
+    fn sheet_names_follow_excel_rules() {
+        let used = vec!["第一课".to_string(), "Unit 1".to_string()];
+        assert_eq!(sheet_name("第一课", &used), "第一课2");
+        assert_eq!(sheet_name("unit 1", &used), "unit 12");
+        assert_eq!(sheet_name("a/b: [c]?", &used), "ab c");
+        assert_eq!(sheet_name("*?", &used), "单词表");
+        let long = "x".repeat(40);
+        assert_eq!(sheet_name(&long, &[]).len(), MAX_SHEET_NAME);
+        assert_eq!(sheet_name(&long, &["x".repeat(31)]), format!("{}2", "x".repeat(30)));
+    }
+
+    #[test]
+    fn numbers_and_blank_cells_are_read_as_text() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.xlsx");
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet().set_name("Unit 1").unwrap();
+        sheet.write_string(0, 0, "Word").unwrap();
+        sheet.write_string(0, 1, "Meaning").unwrap();
+        sheet.write_number(1, 0, 1000).unwrap();
+        sheet.write_string(1, 1, "一千").unwrap();
+        sheet.write_number(2, 0, 2.5).unwrap();
+        sheet.write_string(2, 1, " 二点五 ").unwrap();
+        sheet.write_string(2, 3, "").unwrap();
+        workbook.add_worksheet().set_name("空").unwrap();
+        workbook.save(&path).unwrap();
+
+        let sheets = read_workbook(&path).unwrap();
+        assert_eq!(sheets.len(), 2);
+        assert_eq!(sheets[0].name, "Unit 1");
+        assert_eq!(sheets[0].rows, vec![vec!["Word", "Meaning"], vec!["1000", "一千"], vec!["2.5", "二点五"]]);
+        assert!(sheets[1].rows.is_empty());
+    }
+
+    #[test]
+    fn exported_workbook_imports_back() {
+        let mut memory = FlashMemory::new();
+        let lesson = memory.create_word_table("英语", None, "第一课").unwrap();
+        let unit = memory.create_folder("英语", None, "第一单元").unwrap();
+        let nested = memory.create_word_table("英语", Some(unit), "第一课").unwrap();
+        let mut apple = Word::new("apple", "苹果", "英语");
+        apple.meanings = vec!["苹果树".to_string()];
+        apple.tags = vec!["水果".to_string(), "初级".to_string()];
+        memory.add_words_to_table("英语", lesson, vec![apple.clone()]).unwrap();
+        memory.add_words_to_table("英语", nested, vec![Word::new("cat", "猫", "英语")]).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("英语.xlsx");
+        assert_eq!(write_workbook(&path, &memory, "英语").unwrap(), 2);
+        let sheets = read_workbook(&path).unwrap();
+        let names: Vec<&str> = sheets.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["第一课", "第一课2"]);
+
+        let sheet = ImportSheet::new(sheets[1].rows.clone());
+        assert!(sheet.has_header);
+        let (words, skipped) = sheet.words("英语");
+        assert_eq!(skipped, 0);
+        assert_eq!(words, vec![Word { id: words[0].id, ..apple }]);
+        assert_eq!(ImportSheet::new(sheets[0].rows.clone()).words("英语").0[0].english, "cat");
+    }
+}
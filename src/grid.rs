@@ -0,0 +1,254 @@
+use crate::model::{Example, Word};
+
+// 表格编辑器中的一列，对应单词的一个字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    English,
+    Chinese,
+    Phonetic,
+    PartOfSpeech,
+    Meanings,
+    Examples,
+    Notes,
+    Tags,
+}
+
+pub const COLUMN_COUNT: usize = 8;
+
+impl Column {
+    pub const ALL: [Column; COLUMN_COUNT] = [
+        Column::English,
+        Column::Chinese,
+        Column::Phonetic,
+        Column::PartOfSpeech,
+        Column::Meanings,
+        Column::Examples,
+        Column::Notes,
+        Column::Tags,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Column::English => "英文",
+            Column::Chinese => "释义",
+            Column::Phonetic => "音标",
+            Column::PartOfSpeech => "词性",
+            Column::Meanings => "其他释义",
+            Column::Examples => "例句",
+            Column::Notes => "笔记",
+            Column::Tags => "标签",
+        }
+    }
+}
+
+// 一个单元格中有多项内容（其他释义、例句、笔记的多行）时的分隔
+pub const ITEM_SEPARATOR: &str = " | ";
+// 例句与翻译之间的分隔，与文本编辑器相同
+const TRANSLATION_SEPARATOR: &str = " = ";
+
+// 表格编辑器中的一行，各单元格按 Column::ALL 的顺序保存为文本
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GridRow {
+    pub cells: [String; COLUMN_COUNT],
+}
+
+// 某个单元格的校验错误
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellError {
+    pub row: usize,
+    pub column: Column,
+    pub message: &'static str,
+}
+
+fn split_items(cell: &str) -> impl Iterator<Item = &str> {
+    cell.split(ITEM_SEPARATOR.trim()).map(str::trim).filter(|s| !s.is_empty())
+}
+
+impl GridRow {
+    pub fn from_word(word: &Word) -> Self {
+        let examples: Vec<String> = word
+            .examples
+            .iter()
+            .map(|e| {
+                if e.translation.is_empty() {
+                    e.sentence.clone()
+                } else {
+                    format!("{}{}{}", e.sentence, TRANSLATION_SEPARATOR, e.translation)
+                }
+            })
+            .collect();
+        GridRow {
+            cells: [
+                word.english.clone(),
+                word.chinese.clone(),
+                word.phonetic.clone(),
+                word.part_of_speech.clone(),
+                word.meanings.join(ITEM_SEPARATOR),
+                examples.join(ITEM_SEPARATOR),
+                word.notes.lines().collect::<Vec<_>>().join(ITEM_SEPARATOR),
+                word.tags.join(" "),
+            ],
+        }
+    }
+
+    pub fn cell(&self, column: Column) -> &str {
+        &self.cells[column as usize]
+    }
+
+    pub fn cell_mut(&mut self, column: Column) -> &mut String {
+        &mut self.cells[column as usize]
+    }
+
+    // 所有单元格都为空的行在保存时忽略
+    pub fn is_blank(&self) -> bool {
+        self.cells.iter().all(|c| c.trim().is_empty())
+    }
+
+    pub fn to_word(&self, group: &str) -> Word {
+        let mut word = Word::new(self.cell(Column::English).trim(), self.cell(Column::Chinese).trim(), group);
+        word.phonetic = self.cell(Column::Phonetic).trim().to_string();
+        word.part_of_speech = self.cell(Column::PartOfSpeech).trim().to_string();
+        word.meanings = split_items(self.cell(Column::Meanings)).map(str::to_string).collect();
+        word.examples = split_items(self.cell(Column::Examples))
+            .map(|item| {
+                let (sentence, translation) = item.split_once(TRANSLATION_SEPARATOR).unwrap_or((item, ""));
+                Example { sentence: sentence.trim().to_string(), translation: translation.trim().to_string() }
+            })
+            .collect();
+        word.notes = split_items(self.cell(Column::Notes)).collect::<Vec<_>>().join("\n");
+        word.tags = self
+            .cell(Column::Tags)
+            .split([' ', ',', '，'])
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect();
+        word
+    }
+}
+
+pub fn rows_from_words(words: &[Word]) -> Vec<GridRow> {
+    words.iter().map(GridRow::from_word).collect()
+}
+
+// 把非空行转换为单词；应先用 validate 确认没有错误
+pub fn words_from_rows(rows: &[GridRow], group: &str) -> Vec<Word> {
+    rows.iter().filter(|r| !r.is_blank()).map(|r| r.to_word(group)).collect()
+}
+
+// 非空行必须同时有英文与释义
+pub fn validate(rows: &[GridRow]) -> Vec<CellError> {
+    let mut errors = Vec::new();
+    for (row, r) in rows.iter().enumerate().filter(|(_, r)| !r.is_blank()) {
+        if r.cell(Column::English).trim().is_empty() {
+            errors.push(CellError { row, column: Column::English, message: "缺少英文" });
+        }
+        if r.cell(Column::Chinese).trim().is_empty() {
+            errors.push(CellError { row, column: Column::Chinese, message: "缺少释义" });
+        }
+    }
+    errors
+}
+
+// 把从电子表格复制的内容（制表符分隔列、换行分隔行，含特殊字符的单元格带双引号）拆成单元格
+pub fn split_clipboard(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut cell = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.next_if_eq(&'"').is_some() {
+                    cell.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if cell.is_empty() => in_quotes = true,
+            '\t' if !in_quotes => row.push(std::mem::take(&mut cell)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                row.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => cell.push(c),
+        }
+    }
+    if !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        rows.push(row);
+    }
+    rows
+}
+
+// 从 (row, column) 开始把剪贴板中的表格填入，行数不够时在末尾添加；返回填入的行数
+pub fn paste_cells(rows: &mut Vec<GridRow>, row: usize, column: Column, text: &str) -> usize {
+    let pasted = split_clipboard(text);
+    for (i, cells) in pasted.iter().enumerate() {
+        if rows.len() <= row + i {
+            rows.resize(row + i + 1, GridRow::default());
+        }
+        let target = &mut rows[row + i];
+        for (value, col) in cells.iter().zip(Column::ALL.iter().skip(column as usize)) {
+            // 单元格内的换行改为多项分隔
+            *target.cell_mut(*col) = value.trim().lines().map(str::trim).collect::<Vec<_>>().join(ITEM_SEPARATOR);
+        }
+    }
+    pasted.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(cells: &[&str]) -> GridRow {
+        let mut r = GridRow::default();
+        for (cell, value) in r.cells.iter_mut().zip(cells) {
+            *cell = value.to_string();
+        }
+        r
+    }
+
+    #[test]
+    fn words_round_trip_through_rows() {
+        let mut word = Word::new("apple", "苹果", "英语");
+        word.phonetic = "ˈæpl".to_string();
+        word.meanings = vec!["苹果树".to_string(), "苹果公司".to_string()];
+        word.examples = vec![
+            Example { sentence: "I ate an apple.".to_string(), translation: "我吃了一个苹果。".to_string() },
+            Example { sentence: "An apple a day.".to_string(), translation: String::new() },
+        ];
+        word.notes = "可数名词\n常见水果".to_string();
+        word.tags = vec!["水果".to_string(), "初级".to_string()];
+
+        let rows = rows_from_words(std::slice::from_ref(&word));
+        assert_eq!(rows[0].cell(Column::Meanings), "苹果树 | 苹果公司");
+        let back = words_from_rows(&rows, "英语");
+        assert_eq!(back, vec![Word { id: back[0].id, ..word }]);
+    }
+
+    #[test]
+    fn blank_rows_are_skipped_and_missing_fields_reported() {
+        let rows = vec![row(&["apple", "苹果"]), GridRow::default(), row(&["", "梨"]), row(&["book", " ", "bʊk"])];
+        let errors: Vec<_> = validate(&rows).into_iter().map(|e| (e.row, e.column)).collect();
+        assert_eq!(errors, vec![(2, Column::English), (3, Column::Chinese)]);
+        assert_eq!(words_from_rows(&rows, "英语").len(), 3);
+    }
+
+    #[test]
+    fn pasted_table_fills_from_focused_cell() {
+        let mut rows = vec![row(&["apple", "苹果"])];
+        let text = "苹果树\tI ate an apple. = 我吃了一个苹果。\r\n\"多个\n释义\"\t\"say \"\"hi\"\"\"\r\n";
+        assert_eq!(paste_cells(&mut rows, 0, Column::Meanings, text), 2);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].cell(Column::English), "apple");
+        assert_eq!(rows[0].cell(Column::Examples), "I ate an apple. = 我吃了一个苹果。");
+        assert_eq!(rows[1].cell(Column::Meanings), "多个 | 释义");
+        assert_eq!(rows[1].cell(Column::Examples), "say \"hi\"");
+
+        // 超出最后一列的单元格被忽略
+        paste_cells(&mut rows, 1, Column::Tags, "初级\t多余");
+        assert_eq!(rows[1].cell(Column::Tags), "初级");
+    }
+}
@@ -0,0 +1,17 @@
+// 闪记的数据模型、解析、存储与学习逻辑，不依赖界面，可单独测试
+pub mod clock;
+pub mod exchange;
+pub mod grid;
+pub mod merge;
+pub mod migrate;
+pub mod model;
+pub mod ordering;
+pub mod parse;
+pub mod quiz;
+pub mod review_log;
+pub mod scheduler;
+pub mod session;
+pub mod storage;
+pub mod timing;
+
+pub use model::{Example, FlashMemory, Folder, Word, WordTable};
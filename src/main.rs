@@ -1,143 +1,40 @@
 use std::collections::HashMap;
-use eframe::egui;
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct Word {
-    english: String,
-    chinese: String,
-    group: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct WordTable {
-    name: String,
-    words: Vec<Word>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct FlashMemory {
-    groups: HashMap<String, Vec<WordTable>>,
-}
-
-impl FlashMemory {
-    fn new() -> Self {
-        FlashMemory { groups: HashMap::new() }
-    }
-
-    fn add_word(&mut self, word: Word) {
-        // 保持向后兼容，暂时不使用
-    }
-
-    fn create_group_if_absent(&mut self, group: &str) {
-        self.groups.entry(group.to_string()).or_insert_with(Vec::new);
-    }
-
-    fn create_word_table(&mut self, group: &str, table_name: &str) {
-        let tables = self.groups.entry(group.to_string()).or_insert_with(Vec::new);
-        let mut name = table_name.to_string();
-        let mut idx = 1;
-        while tables.iter().any(|t| t.name == name) {
-            idx += 1;
-            name = format!("{}{}", table_name, idx);
-        }
-        tables.push(WordTable {
-            name,
-            words: Vec::new(),
-        });
-    }
-
-    fn rename_group(&mut self, old: &str, new: &str) -> Result<(), &'static str> {
-        if old == new { return Ok(()); }
-        if self.groups.contains_key(new) { return Err("分组名已存在"); }
-        if let Some(tables) = self.groups.remove(old) {
-            self.groups.insert(new.to_string(), tables);
-            Ok(())
-        } else {
-            Err("原分组不存在")
-        }
-    }
-
-    fn rename_word_table(&mut self, group: &str, old_name: &str, new_name: &str) -> Result<(), &'static str> {
-        if old_name == new_name { return Ok(()); }
-        if let Some(tables) = self.groups.get_mut(group) {
-            if tables.iter().any(|t| t.name == new_name) {
-                return Err("单词表名已存在");
-            }
-            if let Some(table) = tables.iter_mut().find(|t| t.name == old_name) {
-                table.name = new_name.to_string();
-                Ok(())
-            } else {
-                Err("单词表不存在")
-            }
-        } else {
-            Err("分组不存在")
-        }
-    }
-
-    fn delete_group(&mut self, group: &str) -> Result<(), &'static str> {
-        if self.groups.remove(group).is_some() {
-            Ok(())
-        } else {
-            Err("分组不存在")
-        }
-    }
-
-    fn delete_word_table(&mut self, group: &str, table_name: &str) -> Result<(), &'static str> {
-        if let Some(tables) = self.groups.get_mut(group) {
-            if let Some(pos) = tables.iter().position(|t| t.name == table_name) {
-                tables.remove(pos);
-                Ok(())
-            } else {
-                Err("单词表不存在")
-            }
-        } else {
-            Err("分组不存在")
-        }
-    }
-
-    fn get_groups(&self) -> Vec<&String> { self.groups.keys().collect() }
-
-    fn get_word_tables_in_group(&self, group: &str) -> Option<&Vec<WordTable>> { 
-        self.groups.get(group) 
-    }
-
-    fn get_words_in_group(&self, group: &str) -> Option<&Vec<Word>> { 
-        // 保持向后兼容，暂时返回None
-        None
-    }
-    
-    fn add_words_to_table(&mut self, group: &str, table_name: &str, words: Vec<Word>) -> Result<(), &'static str> {
-        if let Some(tables) = self.groups.get_mut(group) {
-            if let Some(table) = tables.iter_mut().find(|t| t.name == table_name) {
-                // 修改逻辑：覆盖原有内容，而非追加
-                table.words = words;
-                Ok(())
-            } else {
-                Err("单词表不存在")
-            }
-        } else {
-            Err("分组不存在")
-        }
-    }
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
 
-    fn save_to_file(&self, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let json = serde_json::to_string_pretty(self)?;
-        std::fs::write(filename, json)?;
-        Ok(())
-    }
+use chrono::NaiveDate;
+use eframe::egui;
+use uuid::Uuid;
 
-    fn load_from_file(filename: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let content = std::fs::read_to_string(filename)?;
-        let flash_memory: FlashMemory = serde_json::from_str(&content)?;
-        Ok(flash_memory)
-    }
-}
+mod settings;
+use flash_memory::ordering::{self, SortMode};
+use flash_memory::parse::{format_words_as_text, parse_words_from_text, parse_words_with_report, ParseReport, Separator};
+use flash_memory::quiz::{self, AnswerCheck, DiffChar};
+use flash_memory::review_log::{ReviewLog, ReviewRecord, StudyMode};
+use flash_memory::scheduler::{self, Algorithm, Grade, OptimizeResult};
+use flash_memory::clock::SystemClock;
+use flash_memory::exchange::anki::{self, AnkiPackage};
+use flash_memory::exchange::delimited::{self, Delimiter, TextEncoding};
+use flash_memory::exchange::xlsx;
+use flash_memory::exchange::{self, ImportSheet};
+use flash_memory::grid::{self, Column, GridRow};
+use flash_memory::merge::{self, SaveMode, WordDiff};
+use flash_memory::session::{self, MergedCard, Presentation, Session, SessionEvent, Transition};
+use flash_memory::storage::{self, Storage, StorageKind};
+use flash_memory::timing::FlashTiming;
+use flash_memory::{FlashMemory, Folder, Word, WordTable};
+use settings::{AppSettings, EditorView, KeyBindings, StudyAction, SETTINGS_FILE};
 
 struct FlashMemoryApp {
     flash_memory: FlashMemory,
+    // 当前单词库的存储后端（words.json 或 words.db）
+    storage: Box<dyn Storage>,
+    // 数据文件读取失败的原因；处理（恢复备份或另存损坏文件）之前不自动保存，以免覆盖原文件
+    load_error: Option<String>,
     current_group: Option<String>,
-    current_word_table: Option<String>,
+    current_folder: Option<Uuid>,     // 选中的文件夹，学习时包括其中各层的单词表
+    current_word_table: Option<Uuid>, // 选中的单词表，优先于文件夹
+    checked_word_tables: Vec<Uuid>,   // Ctrl+点击多选的单词表，不为空时优先于单选
     
     // 消息显示
     message: String,
@@ -151,44 +48,85 @@ struct FlashMemoryApp {
     renaming_word_table_active: bool,
     renaming_word_table_input: String,
 
+    // 文件夹重命名
+    renaming_folder_active: bool,
+    renaming_folder_input: String,
+
     // 右键菜单状态
     context_menu_group: Option<String>,
     show_context_menu: bool,
     context_menu_pos: egui::Pos2,
     
     // 单词表右键菜单状态
-    context_menu_word_table: Option<(String, String)>, // (group, word_table)
+    context_menu_word_table: Option<(String, Uuid)>, // (group, word_table)
     show_word_table_context_menu: bool,
     word_table_context_menu_pos: egui::Pos2,
+
+    // 文件夹右键菜单状态
+    context_menu_folder: Option<(String, Uuid)>, // (group, folder)
+    show_folder_context_menu: bool,
+    folder_context_menu_pos: egui::Pos2,
     
     // 可拖动分界线的宽度
     sidebar_width: f32,
     
     // 单词表编辑相关
-    editing_word_table: Option<(String, String)>, // (group, word_table)
+    editing_word_table: Option<(String, Uuid)>, // (group, word_table)
     word_table_content: String,
-    
-    // 新建单词表相关
-    creating_new_word_table: Option<String>, // 正在为哪个分组创建新单词表
-    new_word_table_name: String, // 新单词表名称输入框
+    word_grid: Vec<GridRow>,              // 表格视图中的各行
+    grid_focus: Option<(usize, Column)>,  // 表格视图中获得焦点的单元格，粘贴时从这里开始
+    confirm_discard_lines: Option<usize>, // 文本视图保存时将被忽略的行数，等待确认
+    editing_separator: Separator,         // 文本视图中英文与释义的分隔符，保存时记到单词表
+    pending_save: Option<Vec<Word>>,      // 等待在保存预览中确认的单词
+
+    // CSV/TSV 导入窗口
+    csv_import: Option<CsvImport>,
+    anki_import: Option<AnkiImport>, // 待确认导入的 Anki 牌组
+    xlsx_import: Option<XlsxImport>, // Excel 工作簿导入窗口
     
     // 闪记系统相关
-    flash_mode: FlashMode, // 闪记模式状态
     current_page: usize, // 当前页码
-    words_per_page: usize, // 每页显示的单词数量
 
-    // 闪记计时与索引
-    flash_index: usize,      // 当前显示的单词索引
-    flash_timer: f32,        // 用于2秒切换的计时器
-    countdown_remaining: i32, // 321倒计时剩余秒数（0表示结束）
-    countdown_timer: f32,     // 倒计时计时器
-
-    // 真实时间计时
-    last_tick: std::time::Instant,
-    // 随机顺序开关与当前轮次词序
+    // 本轮学习（倒计时、计时、词序与测验成绩），None 表示预览
+    session: Option<Session>,
     background_color: egui::Color32,
+    // 随机顺序开关
     random_order: bool,
-    flash_words: Vec<Word>,
+
+    // 复习模式：只学习今天到期的单词，并在显示释义后评分
+    review_mode: bool,
+    flash_source: Option<(String, Vec<Uuid>)>, // 本轮学习对应的分组与单词表
+    flash_cards: HashMap<Uuid, MergedCard>,    // 本轮各卡片的来源单词表与重复单词
+
+    // 复习记录与分组复习设置窗口
+    review_log: ReviewLog,
+    scheduler_settings_group: Option<String>, // 正在设置复习算法的分组
+    optimize_report: String,                  // 最近一次参数优化的结果
+    optimizing: Option<(String, Receiver<Result<OptimizeResult, &'static str>>)>, // 后台正在优化参数的分组
+
+    // 拼写测验
+    typing_direction: TypingDirection,
+    typing_tolerance: usize,                      // 允许的编辑距离，0 表示必须完全正确
+    typing_input: String,
+    typing_result: Option<(AnswerCheck, String)>, // 已提交答案的判定与最接近的正确答案
+
+    // 选择题
+    choice_options: Vec<String>,
+    choice_answer: usize,           // 正确选项的下标
+    choice_selected: Option<usize>, // 已选择的选项
+
+    // 测验成绩
+    session_summary: Option<SessionSummary>,
+
+    // 单词表闪记节奏设置窗口
+    timing_settings_table: Option<(String, Uuid)>, // (group, word_table)
+
+    // 偏好设置与快捷键
+    settings: AppSettings,
+    settings_path: PathBuf,
+    show_key_help: bool,                     // 闪记时显示快捷键提示
+    key_settings_open: bool,                 // 快捷键设置窗口
+    capturing_action: Option<StudyAction>,   // 正在等待新按键的操作
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -196,49 +134,182 @@ enum FlashMode {
     Preview,  // 预览模式
     Started,  // 开始闪记
     Paused,   // 暂停
+    Typing,   // 拼写测验
+    Choice,   // 选择题
+}
+
+// 测验结束后的成绩
+struct SessionSummary {
+    title: &'static str,
+    correct: usize,
+    answered: usize,
+    missed: Vec<String>, // 答错过的单词
+}
+
+// 正在导入的 CSV/TSV 文件与导入位置
+struct CsvImport {
+    path: PathBuf,
+    bytes: Vec<u8>,
+    encoding: TextEncoding,
+    delimiter: Delimiter,
+    sheet: ImportSheet,
+    error: Option<String>, // 按当前编码与分隔符无法读取时的原因
+    group: String,
+    folder: Option<Uuid>,  // 新建单词表所在的文件夹
+    target: Option<Uuid>,  // 合并到的单词表，None 表示新建
+    new_name: String,
+}
+
+impl CsvImport {
+    // 按选择的编码与分隔符重新读取，并重新识别表头与各列
+    fn reload(&mut self) {
+        let text = self.encoding.decode(&self.bytes);
+        match delimited::read_rows(&text, self.delimiter) {
+            Ok(rows) => {
+                self.sheet = ImportSheet::new(rows);
+                self.error = None;
+            }
+            Err(e) => {
+                self.sheet = ImportSheet::new(Vec::new());
+                self.error = Some(e.to_string());
+            }
+        }
+    }
 }
 
-impl Default for FlashMemoryApp {
-    fn default() -> Self {
-        let flash_memory = FlashMemory::load_from_file("words.json").unwrap_or_else(|_| FlashMemory::new());
-        Self {
+// 正在导入的 Anki 牌组文件与其中读到的牌组
+struct AnkiImport {
+    path: PathBuf,
+    package: AnkiPackage,
+}
+
+// 正在导入的 Excel 工作簿：每个工作表导入为分组（或文件夹）中的一个单词表
+struct XlsxImport {
+    path: PathBuf,
+    group: String,
+    folder: Option<Uuid>,
+    sheets: Vec<XlsxSheet>,
+    current: usize, // 正在预览的工作表
+}
+
+struct XlsxSheet {
+    name: String, // 导入后的单词表名，默认为工作表名
+    sheet: ImportSheet,
+    include: bool,
+}
+
+// 拼写测验方向
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TypingDirection {
+    ChineseToEnglish, // 看中文写英文
+    EnglishToChinese, // 看英文写中文
+}
+
+impl FlashMemoryApp {
+    // 单词库依次取命令行指定的、上次打开的（仍然存在时）与应用数据目录中的默认单词库
+    fn new(library: Option<PathBuf>) -> Self {
+        let data_dir = storage::app_data_dir();
+        let _ = std::fs::create_dir_all(&data_dir);
+        let settings_path = data_dir.join(SETTINGS_FILE);
+        let mut settings = AppSettings::load_from_file(&settings_path).unwrap_or_default();
+        // 首次启动（没有指定也没有打开过单词库）时，把早期版本在程序目录中的 words.json 复制到应用数据目录
+        let adopted = (library.is_none() && settings.recent_libraries.is_empty())
+            .then(|| storage::adopt_legacy_library(Path::new("."), &data_dir).transpose())
+            .flatten();
+        let library = match library {
+            Some(path) => storage::resolve_library(&path),
+            None => settings
+                .recent_libraries
+                .first()
+                .filter(|path| path.exists())
+                .cloned()
+                .unwrap_or_else(storage::default_library),
+        };
+        if let Some(dir) = library.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        settings.remember_library(&library);
+        let _ = settings.save_to_file(&settings_path);
+
+        // 首次运行没有数据文件时使用空单词库；文件存在却无法读取时交给恢复窗口处理
+        let mut storage = storage::open(&library);
+        let (flash_memory, load_error) = match storage.load() {
+            Ok(flash_memory) => (flash_memory, None),
+            Err(e) => (FlashMemory::new(), Some(e.to_string())),
+        };
+        let review_log = load_review_log(storage.as_mut(), &flash_memory);
+        let mut app = Self {
             flash_memory,
+            storage,
+            load_error,
             current_group: None,
+            current_folder: None,
             current_word_table: None,
+            checked_word_tables: Vec::new(),
             message: String::new(),
             message_timer: 0.0,
             renaming_group_active: false,
             renaming_input: String::new(),
             renaming_word_table_active: false,
             renaming_word_table_input: String::new(),
+            renaming_folder_active: false,
+            renaming_folder_input: String::new(),
             context_menu_group: None,
             show_context_menu: false,
             context_menu_pos: egui::Pos2::ZERO,
             context_menu_word_table: None,
             show_word_table_context_menu: false,
             word_table_context_menu_pos: egui::Pos2::ZERO,
+            context_menu_folder: None,
+            show_folder_context_menu: false,
+            folder_context_menu_pos: egui::Pos2::ZERO,
             
             sidebar_width: 150.0,
             
             editing_word_table: None,
             word_table_content: String::new(),
+            word_grid: Vec::new(),
+            grid_focus: None,
+            confirm_discard_lines: None,
+            editing_separator: Separator::default(),
+            pending_save: None,
+            csv_import: None,
+            anki_import: None,
+            xlsx_import: None,
             
-            creating_new_word_table: None,
-            new_word_table_name: String::new(),
-            
-            flash_mode: FlashMode::Preview,
             current_page: 0,
-            words_per_page: 20, // 两列每列10个单词
 
-            flash_index: 0,
-            flash_timer: 0.0,
-            countdown_remaining: 0,
-            countdown_timer: 0.0,
-            last_tick: std::time::Instant::now(),
+            session: None,
             background_color: egui::Color32::from_rgb(223, 238, 223),
             random_order: false,
-            flash_words: Vec::new(),
+            review_mode: false,
+            flash_source: None,
+            flash_cards: HashMap::new(),
+            review_log,
+            scheduler_settings_group: None,
+            optimize_report: String::new(),
+            optimizing: None,
+            typing_direction: TypingDirection::ChineseToEnglish,
+            typing_tolerance: 1,
+            typing_input: String::new(),
+            typing_result: None,
+            choice_options: Vec::new(),
+            choice_answer: 0,
+            choice_selected: None,
+            session_summary: None,
+            timing_settings_table: None,
+            settings,
+            settings_path,
+            show_key_help: false,
+            key_settings_open: false,
+            capturing_action: None,
+        };
+        match adopted {
+            Some(Ok(path)) => app.show_message(&format!("已把程序目录中的单词库复制到 {}，原文件保留", path.display())),
+            Some(Err(e)) => app.show_message(&format!("无法复制程序目录中的单词库 words.json: {}", e)),
+            None => {}
         }
+        app
     }
 }
 
@@ -250,6 +321,9 @@ impl eframe::App for FlashMemoryApp {
             if self.message_timer <= 0.0 { self.message.clear(); }
         }
 
+        // 闪记快捷键
+        self.handle_study_keys(ctx);
+
         // 全局文字样式
         let mut style = (*ctx.style()).clone();
         style.text_styles.insert(egui::TextStyle::Heading, egui::FontId::new(20.0, egui::FontFamily::Proportional));
@@ -285,14 +359,7 @@ impl eframe::App for FlashMemoryApp {
                     
                     // 点击创建新分组
                     if resp.clicked() {
-                        let base = "新分组";
-                        let mut name = base.to_string();
-                        let mut idx = 1;
-                        while self.flash_memory.groups.contains_key(&name) {
-                            idx += 1;
-                            name = format!("{}{}", base, idx);
-                        }
-                        self.flash_memory.create_group_if_absent(&name);
+                        let name = self.flash_memory.create_group("新分组");
                         self.current_group = Some(name.clone());
                         self.renaming_group_active = true;
                         self.renaming_input = name;
@@ -310,11 +377,27 @@ impl eframe::App for FlashMemoryApp {
                     );
                 }
             });
+            // 排列方式：手动顺序下可拖动分组与单词表
+            let mut sort_mode = self.settings.sort_mode;
+            egui::ComboBox::from_id_salt("sort_mode")
+                .selected_text(format!("排序：{}", sort_mode.label()))
+                .show_ui(ui, |ui| {
+                    for mode in SortMode::ALL {
+                        ui.selectable_value(&mut sort_mode, mode, mode.label());
+                    }
+                })
+                .response
+                .on_hover_text("手动排序时可拖动分组或单词表调整顺序");
+            if sort_mode != self.settings.sort_mode {
+                self.settings.sort_mode = sort_mode;
+                self.save_settings();
+            }
+            let manual = sort_mode == SortMode::Manual;
+            let today = chrono::Local::now().date_naive();
             ui.separator();
 
             // 分组列表（垂直）
-            let mut groups: Vec<String> = self.flash_memory.get_groups().into_iter().map(|s| s.to_string()).collect();
-            groups.sort();
+            let groups = ordering::sorted_groups(&self.flash_memory, sort_mode, today);
             for g in groups {
                 let selected = self.current_group.as_ref() == Some(&g);
                 // 当前选中且处于重命名状态：显示输入框
@@ -354,7 +437,30 @@ impl eframe::App for FlashMemoryApp {
                     // 请求焦点以确保输入框保持活跃状态
                     resp.request_focus();
                 } else {
-                    let resp = ui.selectable_label(selected, g.clone());
+                    let label = if sort_mode == SortMode::DueCount {
+                        let tables = self.flash_memory.get_word_tables_in_group(&g).map(Vec::as_slice).unwrap_or(&[]);
+                        format!("{} ({})", g, tables.iter().map(|t| t.due_count(today)).sum::<usize>())
+                    } else {
+                        g.clone()
+                    };
+                    let mut resp = ui.selectable_label(selected, label);
+                    // 拖到另一个分组上时移到该分组的位置
+                    if manual {
+                        resp = resp.interact(egui::Sense::drag());
+                        resp.dnd_set_drag_payload(DraggedGroup(g.clone()));
+                        if let Some(dragged) = resp.dnd_hover_payload::<DraggedGroup>() {
+                            let order = self.flash_memory.get_groups();
+                            let from = order.iter().position(|n| **n == dragged.0);
+                            let to = order.iter().position(|n| **n == g);
+                            paint_drop_marker(ui, resp.rect, from, to);
+                        }
+                        if let Some(dragged) = resp.dnd_release_payload::<DraggedGroup>() {
+                            let to = self.flash_memory.get_groups().iter().position(|n| **n == g).unwrap_or(0);
+                            if self.flash_memory.move_group(&dragged.0, to).is_ok() {
+                                self.auto_save();
+                            }
+                        }
+                    }
                     // 双击重命名分组（优先处理双击）
                     if resp.double_clicked() {
                         self.current_group = Some(g.clone());
@@ -365,12 +471,15 @@ impl eframe::App for FlashMemoryApp {
                         if self.current_group.as_ref() == Some(&g) {
                             // 如果点击的是当前分组，则收起
                             self.current_group = None;
+                            self.current_folder = None;
                             self.current_word_table = None;
                         } else {
-                            // 如果点击的是其他分组，则展开
+                            // 如果点击的是其他分组，则展开；未选择单词表时学习整个分组
                             self.current_group = Some(g.clone());
+                            self.current_folder = None;
                             self.current_word_table = None; // 清除单词表选择
                         }
+                        self.checked_word_tables.clear();
                     }
                     // 右键菜单
                     if resp.secondary_clicked() {
@@ -380,76 +489,10 @@ impl eframe::App for FlashMemoryApp {
                     }
                 }
                 
-                // 显示该分组下的单词表
-                 if selected {
-                     let tables: Vec<WordTable> = self.flash_memory.get_word_tables_in_group(&g)
-                         .map(|t| t.clone())
-                         .unwrap_or_default();
-                     for table in tables {
-                         ui.add_space(2.0);
-                         ui.horizontal(|ui| {
-                             ui.add_space(20.0); // 缩进
-                             let table_selected = self.current_word_table.as_ref() == Some(&table.name);
-                             
-                             // 单词表重命名状态
-                             if table_selected && self.renaming_word_table_active {
-                                 let resp = ui.text_edit_singleline(&mut self.renaming_word_table_input);
-                                 
-                                 // 按 Enter 确认重命名
-                                 let confirm = ui.input(|i| i.key_pressed(egui::Key::Enter));
-                                 // 按 Escape 取消重命名
-                                 let cancel = ui.input(|i| i.key_pressed(egui::Key::Escape));
-                                 // 失去焦点时自动确认重命名（相当于按Enter）
-                                 let lost_focus = resp.lost_focus();
-                                 
-                                 if confirm || lost_focus {
-                                     let new_name = self.renaming_word_table_input.trim();
-                                     if new_name.is_empty() {
-                                         self.show_message("单词表名不能为空");
-                                         self.renaming_word_table_active = false;
-                                     } else {
-                                         match self.flash_memory.rename_word_table(&g, &table.name, new_name) {
-                                             Ok(_) => {
-                                                 self.current_word_table = Some(new_name.to_string());
-                                                 self.renaming_word_table_active = false;
-                                                 self.show_message("单词表已重命名");
-                                                 self.auto_save(); // 自动保存
-                                             }
-                                             Err(e) => {
-                                                 self.show_message(e);
-                                                 self.renaming_word_table_active = false;
-                                             }
-                                         }
-                                     }
-                                 } else if cancel {
-                                     self.renaming_word_table_active = false;
-                                 }
-                                 
-                                 // 请求焦点以确保输入框保持活跃状态
-                                 resp.request_focus();
-                             } else {
-                                 let resp = ui.selectable_label(table_selected, &table.name);
-                                 // 双击重命名（优先处理双击）
-                                 if resp.double_clicked() {
-                                     self.current_word_table = Some(table.name.clone());
-                                     self.renaming_word_table_active = true;
-                                     self.renaming_word_table_input = table.name.clone();
-                                     // 请求焦点到输入框
-                                     ui.memory_mut(|mem| mem.request_focus(egui::Id::new("word_table_rename")));
-                                 } else if resp.clicked() {
-                                     // 只有在非双击时才处理单击
-                                     self.current_word_table = Some(table.name.clone());
-                                 }
-                                 // 右键菜单
-                                 if resp.secondary_clicked() {
-                                     self.context_menu_word_table = Some((g.clone(), table.name.clone()));
-                                     self.show_word_table_context_menu = true;
-                                     self.word_table_context_menu_pos = resp.interact_pointer_pos().unwrap_or_default();
-                                 }
-                             }
-                         });
-                     }
-                 }
+                // 显示该分组下的文件夹与单词表
+                if selected {
+                    self.folder_contents_ui(ui, &g, None, 1, sort_mode, today);
+                }
                 ui.add_space(4.0);
             }
         });
@@ -463,24 +506,59 @@ impl eframe::App for FlashMemoryApp {
                     egui::Frame::popup(&ctx.style()).show(ui, |ui| {
                         ui.set_min_width(120.0);
                         if ui.button("新建单词表").clicked() {
-                            if let Some(ref group) = self.context_menu_group {
-                                self.flash_memory.create_word_table(group, "新单词表");
-                                // 直接进入重命名状态
-                                self.current_group = Some(group.clone());
-                                self.current_word_table = Some("新单词表".to_string());
-                                self.renaming_word_table_active = true;
-                                self.renaming_word_table_input = "新单词表".to_string();
-                                self.show_message("已创建新单词表，可直接重命名");
-                                self.auto_save(); // 自动保存
+                            if let Some(group) = self.context_menu_group.clone() {
+                                self.create_word_table_in(&group, None);
+                            }
+                            self.show_context_menu = false;
+                        }
+                        if ui.button("新建文件夹").clicked() {
+                            if let Some(group) = self.context_menu_group.clone() {
+                                self.create_folder_in(&group, None);
+                            }
+                            self.show_context_menu = false;
+                        }
+                        if ui.button("导入 CSV/TSV…").clicked() {
+                            if let Some(group) = self.context_menu_group.clone() {
+                                self.start_csv_import(&group, None, None);
+                            }
+                            self.show_context_menu = false;
+                        }
+                        if ui.button("导入 Excel…").clicked() {
+                            if let Some(group) = self.context_menu_group.clone() {
+                                self.start_xlsx_import(&group, None);
+                            }
+                            self.show_context_menu = false;
+                        }
+                        if ui.button("导出…").clicked() {
+                            if let Some(group) = self.context_menu_group.clone() {
+                                self.export_rows(exchange::group_rows(&self.flash_memory, &group), &group);
+                            }
+                            self.show_context_menu = false;
+                        }
+                        if ui.button("导出 Excel…").clicked() {
+                            if let Some(group) = self.context_menu_group.clone() {
+                                self.export_xlsx(&group);
+                            }
+                            self.show_context_menu = false;
+                        }
+                        if ui.button("导出 Anki 牌组…").clicked() {
+                            if let Some(group) = self.context_menu_group.clone() {
+                                self.export_anki(&group);
                             }
                             self.show_context_menu = false;
                         }
+                        if ui.button("复习设置").clicked() {
+                            self.scheduler_settings_group = self.context_menu_group.clone();
+                            self.optimize_report.clear();
+                            self.show_context_menu = false;
+                        }
                         if ui.button("删除分组").clicked() {
                             if let Some(ref group) = self.context_menu_group {
                                 match self.flash_memory.delete_group(group) {
                                     Ok(_) => {
                                         if self.current_group.as_ref() == Some(group) {
                                             self.current_group = None;
+                                            self.current_folder = None;
                                             self.current_word_table = None;
                                         }
                                         self.show_message("分组已删除");
@@ -506,36 +584,51 @@ impl eframe::App for FlashMemoryApp {
                    egui::Frame::popup(&ctx.style()).show(ui, |ui| {
                        ui.set_min_width(120.0);
                        if ui.button("修改").clicked() {
-                           if let Some((group, table_name)) = &self.context_menu_word_table {
+                           if let Some((group, table_id)) = &self.context_menu_word_table {
                                // 同步当前选择到该表，确保保存返回后预览显示
                                self.current_group = Some(group.clone());
-                               self.current_word_table = Some(table_name.clone());
+                               self.current_word_table = Some(*table_id);
                                self.current_page = 0; // 进入修改时预览回到第一页
-                               self.editing_word_table = Some((group.clone(), table_name.clone()));
-                               // 预填原有单词到编辑框（格式：英文 空格 中文）
-                               if let Some(tables) = self.flash_memory.get_word_tables_in_group(group) {
-                                   if let Some(table) = tables.iter().find(|t| t.name == *table_name) {
-                                       self.word_table_content = table.words
-                                           .iter()
-                                           .take(100) // 预填最多100行
-                                           .map(|w| format!("{} {}", w.english, w.chinese))
-                                           .collect::<Vec<_>>()
-                                           .join("\n");
-                                   } else {
-                                       self.word_table_content.clear();
-                                   }
-                               } else {
-                                   self.word_table_content.clear();
+                               self.editing_word_table = Some((group.clone(), *table_id));
+                               // 预填原有单词到表格与编辑框（格式：英文 分隔符 中文，详细信息缩进写在其后）
+                               let (words, separator) = self
+                                   .flash_memory
+                                   .get_word_table(group, *table_id)
+                                   .map(|t| (t.words.clone(), t.separator))
+                                   .unwrap_or_default();
+                               self.editing_separator = separator;
+                               self.word_table_content = format_words_as_text(&words, separator);
+                               self.word_grid = grid::rows_from_words(&words);
+                               self.grid_focus = None;
+                           }
+                           self.show_word_table_context_menu = false;
+                       }
+                       if ui.button("闪记设置").clicked() {
+                           self.timing_settings_table = self.context_menu_word_table.clone();
+                           self.show_word_table_context_menu = false;
+                       }
+                       if ui.button("导入 CSV/TSV…").clicked() {
+                           if let Some((group, table_id)) = self.context_menu_word_table.clone() {
+                               let folder = self.flash_memory.get_word_table(&group, table_id).and_then(|t| t.folder);
+                               self.start_csv_import(&group, folder, Some(table_id));
+                           }
+                           self.show_word_table_context_menu = false;
+                       }
+                       if ui.button("导出…").clicked() {
+                           if let Some((group, table_id)) = &self.context_menu_word_table {
+                               if let Some(table) = self.flash_memory.get_word_table(group, *table_id) {
+                                   let (rows, name) = (exchange::table_rows(&table.words), table.name.clone());
+                                   self.export_rows(rows, &name);
                                }
                            }
                            self.show_word_table_context_menu = false;
                        }
                        if ui.button("删除").clicked() {
-                           if let Some((group, table_name)) = &self.context_menu_word_table {
-                               match self.flash_memory.delete_word_table(group, table_name) {
+                           if let Some((group, table_id)) = &self.context_menu_word_table {
+                               match self.flash_memory.delete_word_table(group, *table_id) {
                                    Ok(_) => {
                                        // 如果删除的是当前选中的单词表，清除选择
-                                       if self.current_word_table.as_ref() == Some(table_name) {
+                                       if self.current_word_table == Some(*table_id) {
                                            self.current_word_table = None;
                                        }
                                        self.show_message("单词表已删除");
@@ -552,37 +645,156 @@ impl eframe::App for FlashMemoryApp {
                });
         }
 
+        // 文件夹右键菜单
+        if self.show_folder_context_menu {
+            egui::Area::new("folder_context_menu".into())
+                .fixed_pos(self.folder_context_menu_pos)
+                .order(egui::Order::Foreground)
+                .show(ctx, |ui| {
+                   egui::Frame::popup(&ctx.style()).show(ui, |ui| {
+                       ui.set_min_width(120.0);
+                       if ui.button("新建单词表").clicked() {
+                           if let Some((group, folder)) = self.context_menu_folder.clone() {
+                               self.create_word_table_in(&group, Some(folder));
+                           }
+                           self.show_folder_context_menu = false;
+                       }
+                       if ui.button("新建文件夹").clicked() {
+                           if let Some((group, folder)) = self.context_menu_folder.clone() {
+                               self.create_folder_in(&group, Some(folder));
+                           }
+                           self.show_folder_context_menu = false;
+                       }
+                       if ui.button("导入 CSV/TSV…").clicked() {
+                           if let Some((group, folder)) = self.context_menu_folder.clone() {
+                               self.start_csv_import(&group, Some(folder), None);
+                           }
+                           self.show_folder_context_menu = false;
+                       }
+                       if ui.button("导入 Excel…").clicked() {
+                           if let Some((group, folder)) = self.context_menu_folder.clone() {
+                               self.start_xlsx_import(&group, Some(folder));
+                           }
+                           self.show_folder_context_menu = false;
+                       }
+                       if ui.button("删除文件夹").clicked() {
+                           if let Some((group, folder)) = self.context_menu_folder.clone() {
+                               match self.flash_memory.delete_folder(&group, folder) {
+                                   Ok(_) => {
+                                       // 选中的文件夹或单词表随之删除时清除选择
+                                       if self.current_folder.is_some_and(|f| self.flash_memory.get_folder(&group, f).is_none()) {
+                                           self.current_folder = None;
+                                       }
+                                       if self.current_word_table.is_some_and(|t| self.flash_memory.get_word_table(&group, t).is_none()) {
+                                           self.current_word_table = None;
+                                       }
+                                       self.settings.expanded_folders.remove(&folder);
+                                       self.save_settings();
+                                       self.show_message("文件夹已删除");
+                                       self.auto_save(); // 自动保存
+                                   }
+                                   Err(e) => {
+                                       self.show_message(e);
+                                   }
+                               }
+                           }
+                           self.show_folder_context_menu = false;
+                       }
+                   });
+               });
+        }
+
+        // 点击其他地方关闭文件夹右键菜单
+        if self.show_folder_context_menu && ctx.input(|i| i.pointer.primary_clicked()) {
+            let menu_rect = egui::Rect::from_min_size(
+                self.folder_context_menu_pos,
+                egui::Vec2::new(120.0, 150.0)
+            );
+            if let Some(pointer_pos) = ctx.input(|i| i.pointer.interact_pos()) {
+                if !menu_rect.contains(pointer_pos) {
+                    self.show_folder_context_menu = false;
+                }
+            }
+        }
+
         // 点击其他地方关闭单词表右键菜单
-        if self.show_word_table_context_menu {
-            if ctx.input(|i| i.pointer.primary_clicked()) {
-                // 检查是否点击在菜单区域外
-                let menu_rect = egui::Rect::from_min_size(
-                    self.word_table_context_menu_pos,
-                    egui::Vec2::new(120.0, 60.0)
-                );
-                if let Some(pointer_pos) = ctx.input(|i| i.pointer.interact_pos()) {
-                    if !menu_rect.contains(pointer_pos) {
-                        self.show_word_table_context_menu = false;
-                    }
+        if self.show_word_table_context_menu && ctx.input(|i| i.pointer.primary_clicked()) {
+            // 检查是否点击在菜单区域外
+            let menu_rect = egui::Rect::from_min_size(
+                self.word_table_context_menu_pos,
+                egui::Vec2::new(120.0, 150.0)
+            );
+            if let Some(pointer_pos) = ctx.input(|i| i.pointer.interact_pos()) {
+                if !menu_rect.contains(pointer_pos) {
+                    self.show_word_table_context_menu = false;
                 }
             }
         }
 
         // 点击其他地方关闭右键菜单
-        if self.show_context_menu {
-            if ctx.input(|i| i.pointer.primary_clicked()) {
-                // 检查是否点击在菜单区域外
-                let menu_rect = egui::Rect::from_min_size(
-                    self.context_menu_pos,
-                    egui::Vec2::new(120.0, 60.0)
-                );
-                if let Some(pointer_pos) = ctx.input(|i| i.pointer.interact_pos()) {
-                    if !menu_rect.contains(pointer_pos) {
-                        self.show_context_menu = false;
-                    }
+        if self.show_context_menu && ctx.input(|i| i.pointer.primary_clicked()) {
+            // 检查是否点击在菜单区域外
+            let menu_rect = egui::Rect::from_min_size(
+                self.context_menu_pos,
+                egui::Vec2::new(120.0, 240.0)
+            );
+            if let Some(pointer_pos) = ctx.input(|i| i.pointer.interact_pos()) {
+                if !menu_rect.contains(pointer_pos) {
+                    self.show_context_menu = false;
                 }
             }
         }
+        // 数据文件读取失败时的恢复窗口
+        if self.load_error.is_some() {
+            self.show_recovery_dialog(ctx);
+        }
+
+        // 文本视图保存前确认忽略格式不正确的行
+        if let Some(lines) = self.confirm_discard_lines {
+            self.show_discard_confirm(ctx, lines);
+        }
+
+        // 保存前预览新增、修改与删除的单词
+        if self.pending_save.is_some() {
+            self.show_save_preview(ctx);
+        }
+
+        // CSV/TSV 导入窗口
+        if self.csv_import.is_some() {
+            self.show_csv_import(ctx);
+        }
+
+        // Excel 工作簿导入窗口
+        if self.xlsx_import.is_some() {
+            self.show_xlsx_import(ctx);
+        }
+
+        // Anki 牌组导入窗口
+        if self.anki_import.is_some() {
+            self.show_anki_import(ctx);
+        }
+
+        // 后台参数优化完成后应用到对应分组
+        self.poll_optimize(ctx);
+
+        // 分组复习设置窗口
+        if let Some(group) = self.scheduler_settings_group.clone() {
+            self.show_scheduler_settings(ctx, &group);
+        }
+
+        // 单词表闪记节奏设置窗口
+        if let Some((group, table_id)) = self.timing_settings_table.clone() {
+            self.show_timing_settings(ctx, &group, table_id);
+        }
+
+        // 快捷键设置窗口与闪记时的快捷键提示
+        if self.key_settings_open {
+            self.show_key_settings(ctx);
+        }
+        if self.show_key_help && self.flash_mode() != FlashMode::Preview {
+            self.show_key_help_overlay(ctx);
+        }
+
         // 右侧内容区 - 移除所有旧功能，只保留基本布局
         egui::CentralPanel::default().show(ctx, |ui| {
             // 背景色轻微绿色
@@ -590,73 +802,101 @@ impl eframe::App for FlashMemoryApp {
             ui.painter().rect_filled(rect, 0.0, self.background_color);
 
             // 检查是否在编辑单词表
-            if let Some((group, table_name)) = &self.editing_word_table.clone() {
-                // 单词表编辑界面（整体可滚动，输入框保持充满内容区域）
-                egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
-                    ui.add_space(10.0);
-                    
-                    // 按钮区域移到上方
-                    ui.horizontal(|ui| {
-                        if ui.button("返回").clicked() {
-                            self.editing_word_table = None;
-                            self.word_table_content.clear();
+            if self.editing_word_table.is_some() {
+                ui.add_space(10.0);
+
+                // 按钮区域移到上方
+                ui.horizontal(|ui| {
+                    if ui.button("返回").clicked() {
+                        self.editing_word_table = None;
+                        self.word_table_content.clear();
+                        self.word_grid.clear();
+                        self.confirm_discard_lines = None;
+                        self.pending_save = None;
+                    }
+
+                    ui.add_space(20.0);
+
+                    if ui.button("保存").clicked() {
+                        self.save_edited_words(false);
+                    }
+
+                    ui.add_space(20.0);
+
+                    // 表格与文本两种视图，切换时转换已编辑的内容
+                    let mut view = self.settings.editor_view;
+                    ui.selectable_value(&mut view, EditorView::Grid, "表格");
+                    ui.selectable_value(&mut view, EditorView::Text, "文本");
+                    if view != self.settings.editor_view {
+                        let group = self.editing_word_table.as_ref().map(|(g, _)| g.clone()).unwrap_or_default();
+                        match view {
+                            EditorView::Grid => {
+                                self.word_grid = grid::rows_from_words(&parse_words_from_text(
+                                    &self.word_table_content,
+                                    &group,
+                                    self.editing_separator,
+                                ));
+                                self.grid_focus = None;
+                            }
+                            EditorView::Text => {
+                                let words = grid::words_from_rows(&self.word_grid, &group);
+                                self.word_table_content = format_words_as_text(&words, self.editing_separator);
+                            }
                         }
-                        
+                        self.settings.editor_view = view;
+                        self.save_settings();
+                    }
+
+                    // 文本视图的分隔符：只改变读取方式，不改写已输入的文本
+                    if self.settings.editor_view == EditorView::Text {
                         ui.add_space(20.0);
-                        
-                        if ui.button("保存").clicked() {
-                            if let Some((group, table_name)) = &self.editing_word_table.clone() {
-                                // 最多处理前100行
-                                let total_lines = self.word_table_content.lines().count();
-                                let limited_text = self.word_table_content
-                                    .lines()
-                                    .take(100)
-                                    .collect::<Vec<_>>()
-                                    .join("\n");
-                                let words = self.parse_words_from_text(&limited_text, group);
-                                
-                                if total_lines > 100 {
-                                    self.show_message("超过100行，仅保存前100行");
-                                }
-                                
-                                if words.is_empty() {
-                                    self.show_message("没有找到有效的单词格式");
-                                } else {
-                                    match self.flash_memory.add_words_to_table(group, table_name, words.clone()) {
-                                        Ok(_) => {
-                                            self.auto_save();
-                                            self.show_message(&format!("成功保存 {} 个单词", words.len()));
-                                            // 保持当前选择为刚编辑的分组和表，回到预览直接显示
-                                            self.current_group = Some(group.clone());
-                                            self.current_word_table = Some(table_name.clone());
-                                            self.current_page = 0; // 保存后预览回到第一页，保证可见
-                                            self.editing_word_table = None;
-                                            self.word_table_content.clear();
-                                        }
-                                        Err(e) => {
-                                            self.show_message(&format!("保存失败: {}", e));
-                                        }
-                                    }
+                        ui.label("分隔符");
+                        egui::ComboBox::from_id_salt("editor_separator")
+                            .selected_text(self.editing_separator.label())
+                            .show_ui(ui, |ui| {
+                                for separator in Separator::ALL {
+                                    ui.selectable_value(&mut self.editing_separator, separator, separator.label());
                                 }
-                            }
-                        }
-                    });
-                    
-                    ui.add_space(10.0);
-                    
-                    // 说明文字
-                    ui.small("单词和释义之间用空格分隔，换行添加新单词（最多保存前100行）:");
-                    ui.add_space(5.0);
-                    
-                    // 编辑框高度：固定为剩余可用空间，TextEdit 自带滚动
-                    let edit_height = ui.available_height().max(200.0);
-                    ui.add_sized(
-                        [ui.available_width(), edit_height],
-                        egui::TextEdit::multiline(&mut self.word_table_content)
-                            .font(egui::TextStyle::Monospace)
-                            .desired_width(f32::INFINITY)
-                    );
+                            });
+                    }
                 });
+
+                ui.add_space(10.0);
+
+                match self.settings.editor_view {
+                    EditorView::Grid => self.word_grid_ui(ui),
+                    EditorView::Text => {
+                        // 文本视图（整体可滚动，输入框保持充满内容区域）
+                        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                            // 说明文字
+                            ui.small("单词和释义之间用所选分隔符分开（自动：制表符，或在英文与中文之间分开），换行添加新单词；单词含分隔符时用双引号括起来:");
+                            ui.small("单词下方缩进的行填写详细信息：音标、词性、释义（其他释义）、例句（例句 = 翻译）、笔记、标签（空格分隔）");
+                            ui.add_space(5.0);
+
+                            // 逐行列出将被忽略的行与重复的单词
+                            let report = parse_words_with_report(&self.word_table_content, "", self.editing_separator);
+                            Self::parse_diagnostics_ui(ui, &report);
+
+                            // 有问题的行在编辑框中标出：忽略的行为红色，重复的行为黄色
+                            let separator = self.editing_separator;
+                            let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+                                let mut job = highlight_problem_lines(ui, text, separator);
+                                job.wrap.max_width = wrap_width;
+                                ui.fonts(|f| f.layout_job(job))
+                            };
+
+                            // 编辑框高度：固定为剩余可用空间，TextEdit 自带滚动
+                            let edit_height = ui.available_height().max(200.0);
+                            ui.add_sized(
+                                [ui.available_width(), edit_height],
+                                egui::TextEdit::multiline(&mut self.word_table_content)
+                                    .font(egui::TextStyle::Monospace)
+                                    .desired_width(f32::INFINITY)
+                                    .layouter(&mut layouter)
+                            );
+                        });
+                    }
+                }
             } else {
                 // 闪记系统内容区
                 ui.vertical(|ui| {
@@ -666,84 +906,140 @@ impl eframe::App for FlashMemoryApp {
                         
                         // 开始按钮：仅预览状态可点击
                         let start_button = ui.add_enabled(
-                            self.flash_mode == FlashMode::Preview,
+                            self.flash_mode() == FlashMode::Preview,
                             egui::Button::new("开始")
                         );
                         if start_button.clicked() {
-                            let mut words = self.get_current_words();
-                            let total = words.len();
-                            if total == 0 {
-                                self.show_message("当前单词表为空，无法开始");
-                            } else {
-                                // 根据随机开关准备本轮词序
-                                if self.random_order {
-                                    use rand::seq::SliceRandom;
-                                    let mut rng = rand::thread_rng();
-                                    words.shuffle(&mut rng);
-                                }
-                                self.flash_words = words;
-                                self.flash_mode = FlashMode::Started;
-                                self.flash_index = 0;
-                                self.flash_timer = 0.0;
-                                self.countdown_remaining = 3;
-                                self.countdown_timer = 0.0;
-                                self.last_tick = std::time::Instant::now();
+                            let mode = if self.review_mode { StudyMode::Review } else { StudyMode::Flash };
+                            if self.start_session(mode) {
                                 ctx.request_repaint();
                             }
                         }
+
+                        ui.add_space(10.0);
+
+                        // 拼写测验：不计时，输入答案后检查
+                        let typing_button = ui.add_enabled(
+                            self.flash_mode() == FlashMode::Preview,
+                            egui::Button::new("拼写")
+                        );
+                        if typing_button.clicked() {
+                            self.start_session(StudyMode::Typing);
+                        }
+
+                        ui.add_space(10.0);
+
+                        // 选择题：四选一，干扰项取自同一单词表
+                        let choice_button = ui.add_enabled(
+                            self.flash_mode() == FlashMode::Preview,
+                            egui::Button::new("选择")
+                        );
+                        if choice_button.clicked() {
+                            if !self.has_enough_choices() {
+                                self.show_message("选择题至少需要两个不同的释义");
+                            } else {
+                                self.start_session(StudyMode::Choice);
+                            }
+                        }
                         
                         ui.add_space(10.0);
                         
                         // 暂停/继续按钮：在开始与暂停状态均可点击
-                        let pause_label = if self.flash_mode == FlashMode::Paused { "继续" } else { "暂停" };
-                        let pause_enabled = self.flash_mode == FlashMode::Started || self.flash_mode == FlashMode::Paused;
+                        let pause_label = if self.flash_mode() == FlashMode::Paused { "继续" } else { "暂停" };
+                        let pause_enabled = self.flash_mode() == FlashMode::Started || self.flash_mode() == FlashMode::Paused;
                         let pause_button = ui.add_enabled(pause_enabled, egui::Button::new(pause_label));
                         if pause_button.clicked() {
-                            match self.flash_mode {
-                                FlashMode::Started => {
-                                    self.flash_mode = FlashMode::Paused;
-                                    // 防止恢复时 dt 累计过大
-                                    self.last_tick = std::time::Instant::now();
-                                }
-                                FlashMode::Paused => {
-                                    self.flash_mode = FlashMode::Started;
-                                    self.last_tick = std::time::Instant::now();
-                                    // 恢复动画需要持续重绘
-                                    ctx.request_repaint();
-                                }
-                                _ => {}
-                            }
+                            self.toggle_pause(ctx);
                         }
                         
                         ui.add_space(10.0);
                         
                         // 结束按钮：非预览状态可点击
                         let end_button = ui.add_enabled(
-                            self.flash_mode != FlashMode::Preview,
+                            self.flash_mode() != FlashMode::Preview,
                             egui::Button::new("结束")
                         );
                         if end_button.clicked() {
-                            self.flash_mode = FlashMode::Preview;
-                            self.current_page = 0;
-                            self.flash_index = 0;
-                            self.flash_timer = 0.0;
-                            self.countdown_remaining = 0;
-                            self.countdown_timer = 0.0;
-                            self.last_tick = std::time::Instant::now();
-                            self.flash_words.clear();
+                            self.end_session();
                         }
                         
                         ui.add_space(10.0);
                         ui.toggle_value(&mut self.random_order, "随机");
-                        
-                        // 背景颜色选择方块（右侧）
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            let size = egui::Vec2::new(18.0, 18.0);
-                            let colors = [
-                                egui::Color32::from_rgb(223, 238, 223), // 浅绿（当前默认）
-                                egui::Color32::from_rgb(255, 245, 204), // 浅黄
-                                egui::Color32::from_rgb(240, 240, 240), // 浅灰
-                            ];
+                        ui.add_enabled_ui(self.flash_mode() == FlashMode::Preview, |ui| {
+                            ui.toggle_value(&mut self.review_mode, "复习")
+                                .on_hover_text("只学习今天到期的单词，显示释义后评分");
+                            ui.menu_button("拼写设置", |ui| {
+                                ui.radio_value(&mut self.typing_direction, TypingDirection::ChineseToEnglish, "看中文写英文");
+                                ui.radio_value(&mut self.typing_direction, TypingDirection::EnglishToChinese, "看英文写中文");
+                                ui.separator();
+                                ui.horizontal(|ui| {
+                                    ui.label("容错字符数");
+                                    ui.add(egui::DragValue::new(&mut self.typing_tolerance).range(0..=3));
+                                });
+                                ui.small("大小写与首尾空白始终忽略");
+                            });
+                            if ui.button("快捷键").clicked() {
+                                self.key_settings_open = true;
+                            }
+                            ui.menu_button("数据", |ui| {
+                                ui.label(format!(
+                                    "当前存储：{}（{}）",
+                                    self.storage.kind().label(),
+                                    self.storage.path().display()
+                                ));
+                                if ui.button("打开单词库…").clicked() {
+                                    ui.close_menu();
+                                    let picked = rfd::FileDialog::new()
+                                        .add_filter("单词库", &["json", "db"])
+                                        .pick_file();
+                                    if let Some(path) = picked {
+                                        self.open_library(&path);
+                                    }
+                                }
+                                ui.menu_button("最近打开", |ui| {
+                                    let current = std::fs::canonicalize(self.storage.path()).ok();
+                                    for path in self.settings.recent_libraries.clone() {
+                                        let enabled = current.as_ref() != Some(&path);
+                                        if ui.add_enabled(enabled, egui::Button::new(path.display().to_string())).clicked() {
+                                            self.open_library(&path);
+                                            ui.close_menu();
+                                        }
+                                    }
+                                });
+                                ui.separator();
+                                if ui.add_enabled(self.load_error.is_none(), egui::Button::new("导入 Anki 牌组…")).clicked() {
+                                    ui.close_menu();
+                                    self.start_anki_import();
+                                }
+                                ui.separator();
+                                let can_migrate = self.storage.kind() == StorageKind::Json && self.load_error.is_none();
+                                if ui.add_enabled(can_migrate, egui::Button::new("迁移到 SQLite")).clicked() {
+                                    self.migrate_to_sqlite();
+                                    ui.close_menu();
+                                }
+                            });
+                        });
+                        if let Some(group) = &self.current_group {
+                            let today = chrono::Local::now().date_naive();
+                            let tables: Vec<&WordTable> = self.selected_tables().iter()
+                                .filter_map(|&id| self.flash_memory.get_word_table(group, id))
+                                .collect();
+                            let summary = self.review_log.daily_summary(&tables, today);
+                            ui.label(format!("待复习 {}", self.get_current_due_words().len()));
+                            ui.label(format!("今日已学 {}", summary.cards));
+                            if let Some(rate) = (summary.correct * 100).checked_div(summary.graded) {
+                                ui.label(format!("正确率 {}%", rate));
+                            }
+                        }
+                        
+                        // 背景颜色选择方块（右侧）
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            let size = egui::Vec2::new(18.0, 18.0);
+                            let colors = [
+                                egui::Color32::from_rgb(223, 238, 223), // 浅绿（当前默认）
+                                egui::Color32::from_rgb(255, 245, 204), // 浅黄
+                                egui::Color32::from_rgb(240, 240, 240), // 浅灰
+                            ];
                             for &color in colors.iter() {
                                 let (rect, resp) = ui.allocate_at_least(size, egui::Sense::click());
                                 ui.painter().rect_filled(rect, 3.0, color);
@@ -763,33 +1059,54 @@ impl eframe::App for FlashMemoryApp {
                     ui.add_space(10.0);
                     
                     // 单词显示区域
-                    if let Some(ref table_name) = self.current_word_table {
+                    if self.current_group.is_some() {
                         // 预览保持原序；闪记阶段使用开始时的词序
-                        let all_words = if self.flash_mode == FlashMode::Preview {
-                            self.get_current_words()
-                        } else if !self.flash_words.is_empty() {
-                            self.flash_words.clone()
-                        } else {
-                            self.get_current_words()
+                        let all_words = match &self.session {
+                            Some(session) => session.words().to_vec(),
+                            None => self.get_current_words(),
                         };
                         if all_words.is_empty() {
                             ui.vertical_centered(|ui| {
                                 ui.add_space(50.0);
-                                ui.label("该单词表为空");
-                                ui.add_space(20.0);
-                                ui.label("请添加单词到此表中");
+                                if self.current_word_table.is_some() {
+                                    ui.label("该单词表为空");
+                                    ui.add_space(20.0);
+                                    ui.label("请添加单词到此表中");
+                                } else {
+                                    ui.label("所选范围中没有单词");
+                                    ui.add_space(20.0);
+                                    ui.label("请在右键菜单中新建单词表");
+                                }
                             });
                         } else {
-                            match self.flash_mode {
+                            match self.flash_mode() {
+                                FlashMode::Typing => {
+                                    self.typing_card_ui(ui);
+                                }
+                                FlashMode::Choice => {
+                                    self.choice_card_ui(ui);
+                                }
                                 FlashMode::Preview => {
+                                    self.session_summary_ui(ui);
                                     // 预览模式：展示全部单词表格
+                                    // 有详细信息的单词表增加“音标/词性”与“标签”两列
+                                    let with_details = all_words.iter().any(|w| w.has_details());
+                                    // 同时查看多个单词表时增加“单词表”一列
+                                    let sources: HashMap<Uuid, String> = if self.selected_tables().len() > 1 {
+                                        self.current_cards(|_| true).into_iter().map(|c| (c.word.id, c.tables.join("、"))).collect()
+                                    } else {
+                                        HashMap::new()
+                                    };
                                     egui::ScrollArea::vertical().show(ui, |ui| {
                                         egui_extras::TableBuilder::new(ui)
                                             .striped(true)
                                             .resizable(false)
                                             .column(egui_extras::Column::remainder())
+                                            .columns(egui_extras::Column::auto().at_least(80.0), if with_details { 1 } else { 0 })
                                             .column(egui_extras::Column::remainder())
-                                            .body(|mut body| {
+                                            .columns(egui_extras::Column::auto().at_least(80.0), if with_details { 1 } else { 0 })
+                                            .columns(egui_extras::Column::auto().at_least(80.0), if sources.is_empty() { 0 } else { 1 })
+                                            .body(|body| {
                                                 let font_size: f32 = 18.0;
                                                 let row_height: f32 = (font_size + 14.0_f32).max(32.0_f32);
                                                 let row_count = all_words.len();
@@ -801,60 +1118,75 @@ impl eframe::App for FlashMemoryApp {
                                                                 ui.label(egui::RichText::new(&word.english).size(font_size).strong());
                                                             });
                                                         });
+                                                        if with_details {
+                                                            row.col(|ui| {
+                                                                ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
+                                                                    ui.weak(phonetic_line(word));
+                                                                });
+                                                            });
+                                                        }
                                                         row.col(|ui| {
                                                             ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
-                                                                ui.label(egui::RichText::new(&word.chinese).size(font_size));
+                                                                let meanings = std::iter::once(&word.chinese).chain(&word.meanings);
+                                                                let text = meanings.cloned().collect::<Vec<_>>().join("；");
+                                                                let resp = ui.label(egui::RichText::new(text).size(font_size));
+                                                                // 例句与笔记在悬停时显示
+                                                                let extra = example_and_note_lines(word);
+                                                                if !extra.is_empty() {
+                                                                    resp.on_hover_text(extra.join("\n"));
+                                                                }
                                                             });
                                                         });
+                                                        if with_details {
+                                                            row.col(|ui| {
+                                                                ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
+                                                                    ui.weak(word.tags.join(" "));
+                                                                });
+                                                            });
+                                                        }
+                                                        if !sources.is_empty() {
+                                                            row.col(|ui| {
+                                                                ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
+                                                                    ui.weak(sources.get(&word.id).map(String::as_str).unwrap_or_default());
+                                                                });
+                                                            });
+                                                        }
                                                     }
                                                 });
                                             });
                                     });
                                 }
                                 _ => {
-                                    // 闪记模式：321倒计时 -> 每1.5秒切换一个单词（前1秒只显示英文，后0.5秒显示中文；到末尾自动结束，不循环）
-                                    if self.flash_mode == FlashMode::Started {
-                                        // 使用系统时间推进动画，并请求持续重绘
-                                        let now = std::time::Instant::now();
-                                        let dt = (now - self.last_tick).as_secs_f32();
-                                        self.last_tick = now;
+                                    // 闪记模式：倒计时 -> 每张卡片先只显示英文，再显示释义，随后可留空白间隔；时长按单词表的闪记设置，到末尾自动结束，不循环
+                                    if self.flash_mode() == FlashMode::Started {
+                                        // 按系统时间推进计时，并请求持续重绘
+                                        self.handle_session_event(SessionEvent::Tick);
                                         ctx.request_repaint();
-                                    
-                                        if self.countdown_remaining > 0 {
-                                            self.countdown_timer += dt;
-                                            if self.countdown_timer >= 1.0 {
-                                                self.countdown_remaining -= 1;
-                                                self.countdown_timer = 0.0;
-                                                // 倒计时刚结束时，确保词计时器从0开始，避免释义提前出现
-                                                if self.countdown_remaining == 0 {
-                                                    self.flash_timer = 0.0;
-                                                }
-                                            }
-                                        } else {
-                                            self.flash_timer += dt;
-                                            if self.flash_timer >= 2.0 {
-                                                self.flash_timer = 0.0;
-                                                if self.flash_index + 1 < all_words.len() {
-                                                    self.flash_index += 1;
-                                                } else {
-                                                    // 播放到最后一个词后自动结束（返回预览，不循环）
-                                                    self.flash_mode = FlashMode::Preview;
-                                                    self.current_page = 0;
-                                                    self.show_message("本轮学习结束");
-                                                }
-                                            }
-                                        }
                                     }
-                                    
+                                    // 最后一张播放完毕时本轮已结束，下一帧回到预览
+                                    let Some(session) = &self.session else { return };
+                                    let Some(word) = session.current_word().cloned() else { return };
+                                    let countdown = session.countdown();
+                                    let show_meaning = session.show_meaning(); // 正面时长内只显示英文
+                                    // 卡片间隔：释义展示结束后留空白
+                                    let in_gap = session.in_gap();
+                                    let grading = session.mode() == StudyMode::Review && show_meaning && !session.is_paused();
+
                                     let english_size: f32 = 64.0;
                                     let chinese_size: f32 = 32.0;
+                                    let detail_size: f32 = 18.0;
+                                    let details = card_detail_lines(&word);
+                                    let details_height = details.len() as f32 * (detail_size + 6.0);
+                                    // 同时学习多个单词表时在卡片上方显示所在的单词表
+                                    if let Some(source) = self.card_source(&word) {
+                                        ui.weak(source);
+                                    }
                                     let card_height = ui.available_height() - 20.0;
-                                    let show_meaning = self.countdown_remaining == 0 && self.flash_timer >= 1.0; // 前1秒只英文，后1秒显示中文
-                                    // 始终按“英文+预留释义”计算内容高度，避免释义出现导致整体向上/向下位移
-                                    let content_height = if self.countdown_remaining > 0 {
+                                    // 始终按“英文+预留释义与详细信息”计算内容高度，避免释义出现导致整体向上/向下位移
+                                    let content_height = if countdown > 0 {
                                         english_size + 20.0
                                     } else {
-                                        english_size + chinese_size + 24.0
+                                        english_size + chinese_size + 24.0 + details_height
                                     };
                                     let top_space = (card_height - content_height).max(0.0) / 2.0;
                                     
@@ -865,22 +1197,43 @@ impl eframe::App for FlashMemoryApp {
                                             ui.set_min_size(egui::Vec2::new(ui.available_width(), card_height));
                                             ui.add_space(top_space);
                                             ui.vertical_centered(|ui| {
-                                                if self.countdown_remaining > 0 {
-                                                    ui.label(egui::RichText::new(format!("{}", self.countdown_remaining)).size(english_size).strong());
+                                                if countdown > 0 {
+                                                    ui.label(egui::RichText::new(format!("{}", countdown)).size(english_size).strong());
+                                                } else if in_gap {
+                                                    ui.add_space(english_size + chinese_size + 12.0);
                                                 } else {
-                                                    let word = &all_words[self.flash_index];
                                                     ui.label(egui::RichText::new(&word.english).size(english_size).strong());
                                                     // 始终预留英文到释义的间距与释义高度，避免出现瞬间位移
                                                     ui.add_space(12.0);
                                                     if show_meaning {
                                                         ui.label(egui::RichText::new(&word.chinese).size(chinese_size));
+                                                        // 卡片背面：音标、词性、其他释义、例句、笔记与标签
+                                                        for line in &details {
+                                                            ui.label(egui::RichText::new(line).size(detail_size));
+                                                        }
                                                     } else {
                                                         // 不显示释义时，预留同等高度空间以稳定布局
-                                                        ui.add_space(chinese_size);
+                                                        ui.add_space(chinese_size + details_height);
                                                     }
                                                 }
                                             });
                                         });
+
+                                    // 复习模式：释义出现后评分
+                                    if grading {
+                                        let mut graded = None;
+                                        ui.horizontal(|ui| {
+                                            for grade in Grade::ALL {
+                                                if ui.button(grade.label()).clicked() {
+                                                    graded = Some(grade);
+                                                }
+                                                ui.add_space(10.0);
+                                            }
+                                        });
+                                        if let Some(grade) = graded {
+                                            self.handle_session_event(SessionEvent::Grade(grade));
+                                        }
+                                    }
                                 }
                             }
                         }
@@ -895,13 +1248,9 @@ impl eframe::App for FlashMemoryApp {
                                 ui.add_space(20.0);
                             }
                             
-                            if let Some(ref group) = self.current_group {
-                                ui.label(format!("当前分组: {}", group));
-                                ui.add_space(10.0);
-                                ui.label("请选择左侧单词表开始学习");
-                            } else {
-                                ui.label("请选择左侧分组和单词表");
-                            }
+                            ui.label("请选择左侧分组、文件夹或单词表");
+                            ui.add_space(10.0);
+                            ui.label("选择分组或文件夹时学习其中的全部单词表，Ctrl+点击可多选单词表");
                         });
                     }
                 });
@@ -917,9 +1266,16 @@ impl FlashMemoryApp {
     }
     
     fn auto_save(&mut self) {
-        match self.flash_memory.save_to_file("words.json") {
+        if self.load_error.is_some() {
+            self.show_message("数据文件读取失败，请先在恢复窗口中处理");
+            return;
+        }
+        match self.storage.save(&self.flash_memory) {
             Ok(_) => {
-                // 保存成功，不显示消息以避免干扰用户
+                // 保存成功，不显示消息以避免干扰用户；只有备份失败时提醒
+                if let Some(e) = self.storage.take_backup_error() {
+                    self.show_message(&format!("已保存，但备份失败: {}", e));
+                }
             }
             Err(e) => {
                 self.show_message(&format!("保存失败: {}", e));
@@ -927,84 +1283,1915 @@ impl FlashMemoryApp {
         }
     }
     
-    fn get_current_words(&self) -> Vec<Word> {
-        if let (Some(group), Some(table_name)) = (&self.current_group, &self.current_word_table) {
-            if let Some(tables) = self.flash_memory.get_word_tables_in_group(group) {
-                if let Some(table) = tables.iter().find(|t| t.name == *table_name) {
-                    return table.words.clone();
+    // 读取编辑器中的单词（按当前视图），确认后在保存预览中选择保存方式；表格中有错误时不保存，
+    // 文本视图中有将被忽略的行时先确认，force 为 true 表示已确认
+    fn save_edited_words(&mut self, force: bool) {
+        let Some((group, _)) = self.editing_word_table.clone() else { return };
+        let words = match self.settings.editor_view {
+            EditorView::Grid => {
+                if let Some(error) = grid::validate(&self.word_grid).first() {
+                    self.show_message(&format!("第 {} 行{}，请修改后再保存", error.row + 1, error.message));
+                    return;
+                }
+                grid::words_from_rows(&self.word_grid, &group)
+            }
+            EditorView::Text => {
+                let report = parse_words_with_report(&self.word_table_content, &group, self.editing_separator);
+                let rejected = report.rejected_count();
+                if rejected > 0 && !force && !report.words.is_empty() {
+                    self.confirm_discard_lines = Some(rejected);
+                    return;
                 }
+                report.words
             }
+        };
+        self.confirm_discard_lines = None;
+
+        if words.is_empty() {
+            self.show_message("没有找到有效的单词格式");
+            return;
         }
-        Vec::new()
+        self.pending_save = Some(words);
     }
-    
-    fn get_total_pages(&self) -> usize {
-        let words = self.get_current_words();
-        if words.is_empty() {
-            0
-        } else {
-            (words.len() + self.words_per_page - 1) / self.words_per_page
+
+    // 按所选的保存方式写入单词表
+    fn apply_edited_words(&mut self, words: Vec<Word>) {
+        let Some((group, table_id)) = self.editing_word_table.clone() else { return };
+        let mode = self.settings.save_mode;
+        let diff = match self.flash_memory.get_word_table(&group, table_id) {
+            Some(table) => merge::diff_words(&table.words, &merge::merge_words(&table.words, words.clone(), mode)),
+            None => WordDiff::default(),
+        };
+        let saved = self.flash_memory.save_words_to_table(&group, table_id, words, mode).and_then(|_| {
+            if self.settings.editor_view == EditorView::Text {
+                self.flash_memory.set_word_table_separator(&group, table_id, self.editing_separator)?;
+            }
+            Ok(())
+        });
+        match saved {
+            Ok(_) => {
+                self.auto_save();
+                self.show_message(&format!(
+                    "保存成功：新增 {} 个，修改 {} 个，删除 {} 个单词",
+                    diff.added.len(),
+                    diff.changed.len(),
+                    diff.removed.len()
+                ));
+                // 保持当前选择为刚编辑的分组和表，回到预览直接显示
+                self.current_group = Some(group);
+                self.current_word_table = Some(table_id);
+                self.current_page = 0; // 保存后预览回到第一页，保证可见
+                self.editing_word_table = None;
+                self.word_table_content.clear();
+                self.word_grid.clear();
+            }
+            Err(e) => {
+                self.show_message(&format!("保存失败: {}", e));
+            }
         }
     }
-    
-    fn get_current_page_words(&self) -> Vec<Word> {
-        let words = self.get_current_words();
-        let start = self.current_page * self.words_per_page;
-        let end = std::cmp::min(start + self.words_per_page, words.len());
-        if start < words.len() {
-            words[start..end].to_vec()
-        } else {
-            Vec::new()
+
+    // 文本视图上方的问题列表，没有问题时不显示
+    fn parse_diagnostics_ui(ui: &mut egui::Ui, report: &ParseReport) {
+        if report.diagnostics.is_empty() {
+            return;
         }
+        let rejected = report.rejected_count();
+        let duplicates = report.diagnostics.len() - rejected;
+        ui.label(format!("{} 行将被忽略，{} 个重复的单词：", rejected, duplicates));
+        egui::ScrollArea::vertical().id_salt("parse_diagnostics").max_height(100.0).show(ui, |ui| {
+            for d in &report.diagnostics {
+                let color = if d.issue.is_rejected() { egui::Color32::RED } else { egui::Color32::from_rgb(180, 130, 0) };
+                ui.colored_label(color, format!("第 {} 行：{}　{}", d.line, d.issue.message(), d.text));
+            }
+        });
+        ui.add_space(5.0);
     }
-    
-    fn parse_words_from_text(&self, text: &str, group: &str) -> Vec<Word> {
-        let mut words = Vec::new();
-        
-        for line in text.lines() {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
+
+    // 文本视图保存时有将被忽略的行，确认后才保存
+    fn show_discard_confirm(&mut self, ctx: &egui::Context, lines: usize) {
+        let mut save = false;
+        let mut back = false;
+        egui::Window::new("部分行将被忽略")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!("有 {} 行格式不正确，保存时将被忽略。", lines));
+                ui.horizontal(|ui| {
+                    save = ui.button("仍然保存").clicked();
+                    back = ui.button("返回修改").clicked();
+                });
+            });
+        if save {
+            self.save_edited_words(true);
+        } else if back {
+            self.confirm_discard_lines = None;
+        }
+    }
+
+    // 保存预览：选择替换、追加或按英文合并，列出保存后新增、修改与删除的单词
+    fn show_save_preview(&mut self, ctx: &egui::Context) {
+        let Some((group, table_id)) = self.editing_word_table.clone() else {
+            self.pending_save = None;
+            return;
+        };
+        let Some(words) = self.pending_save.clone() else { return };
+        let (table_name, old) = match self.flash_memory.get_word_table(&group, table_id) {
+            Some(table) => (table.name.clone(), table.words.clone()),
+            None => (String::new(), Vec::new()),
+        };
+        let mut mode = self.settings.save_mode;
+        let diff = merge::diff_words(&old, &merge::merge_words(&old, words.clone(), mode));
+        let pair = |w: &Word| format!("{} {}", w.english, w.chinese);
+        let mut confirm = false;
+        let mut cancel = false;
+        egui::Window::new(format!("保存预览 - {}", table_name))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("保存方式");
+                    for m in SaveMode::ALL {
+                        ui.selectable_value(&mut mode, m, m.label());
+                    }
+                });
+                ui.small(match mode {
+                    SaveMode::Replace => "单词表只保留编辑后的单词，能对应上的原单词保留复习记录",
+                    SaveMode::Append => "原有单词不变，在末尾添加新单词，英文与释义都相同的不重复添加",
+                    SaveMode::Merge => "英文相同的单词更新释义与详细信息并保留复习记录，其余单词追加，原有单词都保留",
+                });
+                ui.separator();
+                ui.label(format!(
+                    "新增 {} 个，修改 {} 个，删除 {} 个，不变 {} 个",
+                    diff.added.len(),
+                    diff.changed.len(),
+                    diff.removed.len(),
+                    diff.unchanged
+                ));
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    for w in &diff.added {
+                        ui.colored_label(egui::Color32::DARK_GREEN, format!("+ {}", pair(w)));
+                    }
+                    for (before, after) in &diff.changed {
+                        let text = if pair(before) == pair(after) {
+                            format!("~ {}（详细信息有修改）", pair(after))
+                        } else {
+                            format!("~ {} → {}", pair(before), pair(after))
+                        };
+                        ui.colored_label(egui::Color32::from_rgb(180, 130, 0), text);
+                    }
+                    for w in &diff.removed {
+                        ui.colored_label(egui::Color32::DARK_RED, format!("- {}", pair(w)));
+                    }
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    confirm = ui.button("确认保存").clicked();
+                    cancel = ui.button("返回修改").clicked();
+                });
+            });
+
+        if mode != self.settings.save_mode {
+            self.settings.save_mode = mode;
+            self.save_settings();
+        }
+        if confirm {
+            self.pending_save = None;
+            self.apply_edited_words(words);
+        } else if cancel {
+            self.pending_save = None;
+        }
+    }
+
+    // 表格视图：每个单词一行，每个字段一列；从电子表格复制的多行多列内容从获得焦点的单元格开始填入
+    fn word_grid_ui(&mut self, ui: &mut egui::Ui) {
+        let pasted = ui.input_mut(|i| {
+            let pos = i.events.iter().position(|e| matches!(e, egui::Event::Paste(text) if text.contains(['\t', '\n'])))?;
+            match i.events.remove(pos) {
+                egui::Event::Paste(text) => Some(text),
+                _ => None,
             }
-            
-            // 查找第一个空格的位置
-            if let Some(space_pos) = line.find(' ') {
-                let english = line[..space_pos].trim().to_string();
-                let chinese = line[space_pos + 1..].trim().to_string();
-                
-                if !english.is_empty() && !chinese.is_empty() {
-                    words.push(Word {
-                        english,
-                        chinese,
-                        group: group.to_string(),
+        });
+        if let Some(text) = pasted {
+            let (row, column) = self.grid_focus.unwrap_or((self.word_grid.len(), Column::English));
+            let count = grid::paste_cells(&mut self.word_grid, row, column, &text);
+            self.show_message(&format!("已粘贴 {} 行", count));
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("添加一行").clicked() {
+                self.word_grid.push(GridRow::default());
+            }
+            ui.small(format!("共 {} 行", self.word_grid.iter().filter(|r| !r.is_blank()).count()));
+        });
+        ui.small("其他释义、例句、笔记的多项之间用 | 分隔，例句与翻译之间用 = 分隔，标签之间用空格分隔；可直接粘贴从电子表格复制的内容");
+        ui.add_space(5.0);
+
+        let errors = grid::validate(&self.word_grid);
+        let mut insert = None;
+        let mut delete = None;
+        let mut focus = None;
+        let row_count = self.word_grid.len();
+        let rows = &mut self.word_grid;
+        egui_extras::TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .auto_shrink([false, false])
+            .column(egui_extras::Column::auto().at_least(30.0))
+            .columns(egui_extras::Column::initial(120.0).at_least(60.0).clip(true), grid::COLUMN_COUNT)
+            .column(egui_extras::Column::auto())
+            .header(24.0, |mut header| {
+                header.col(|ui| { ui.strong("#"); });
+                for column in Column::ALL {
+                    header.col(|ui| { ui.strong(column.label()); });
+                }
+                header.col(|_| {});
+            })
+            .body(|body| {
+                body.rows(28.0, row_count, |mut table_row| {
+                    let idx = table_row.index();
+                    table_row.col(|ui| { ui.weak(format!("{}", idx + 1)); });
+                    for column in Column::ALL {
+                        table_row.col(|ui| {
+                            let resp = ui.add(
+                                egui::TextEdit::singleline(rows[idx].cell_mut(column))
+                                    .id(egui::Id::new(("word_grid", idx, column as usize)))
+                                    .desired_width(f32::INFINITY),
+                            );
+                            if resp.has_focus() {
+                                focus = Some((idx, column));
+                            }
+                            // 出错的单元格标红，悬停显示原因
+                            if let Some(error) = errors.iter().find(|e| e.row == idx && e.column == column) {
+                                ui.painter().rect_stroke(resp.rect, 2.0, egui::Stroke::new(1.5, egui::Color32::RED));
+                                resp.on_hover_text(error.message);
+                            }
+                        });
+                    }
+                    table_row.col(|ui| {
+                        if ui.small_button("插入").on_hover_text("在此行上方插入空行").clicked() {
+                            insert = Some(idx);
+                        }
+                        if ui.small_button("删除").clicked() {
+                            delete = Some(idx);
+                        }
                     });
+                });
+            });
+
+        if focus.is_some() {
+            self.grid_focus = focus;
+        }
+        if let Some(idx) = insert {
+            self.word_grid.insert(idx, GridRow::default());
+            self.grid_focus = None;
+        }
+        if let Some(idx) = delete {
+            self.word_grid.remove(idx);
+            self.grid_focus = None;
+        }
+    }
+
+    // 在分组或文件夹中新建单词表，选中并直接进入重命名状态
+    fn create_word_table_in(&mut self, group: &str, folder: Option<Uuid>) {
+        match self.flash_memory.create_word_table(group, folder, "新单词表") {
+            Ok(id) => {
+                if let Some(folder) = folder {
+                    self.settings.expanded_folders.insert(folder);
+                    self.save_settings();
                 }
+                self.current_group = Some(group.to_string());
+                self.current_folder = folder;
+                self.current_word_table = Some(id);
+                self.renaming_word_table_active = true;
+                self.renaming_word_table_input = self.flash_memory.get_word_table(group, id).map(|t| t.name.clone()).unwrap_or_default();
+                self.show_message("已创建新单词表，可直接重命名");
+                self.auto_save(); // 自动保存
             }
+            Err(e) => self.show_message(e),
         }
-        
-        words
     }
-}
 
-// 尝试加载系统中文字体（simhei/simkai），用于支持中文显示
-fn configure_chinese_fonts(ctx: &egui::Context) {
-    let mut fonts = egui::FontDefinitions::default();
-    let mut loaded = false;
-    for path in [
-        "C\\Windows\\Fonts\\simhei.ttf",
-        "C\\Windows\\Fonts\\simkai.ttf",
-        "C:/Windows/Fonts/simhei.ttf",
-        "C:/Windows/Fonts/simkai.ttf",
-    ] {
-        if let Ok(bytes) = std::fs::read(path) {
-            fonts.font_data.insert("cjk".to_owned(), egui::FontData::from_owned(bytes));
-            fonts.families.entry(egui::FontFamily::Proportional).or_default().insert(0, "cjk".to_owned());
-            fonts.families.entry(egui::FontFamily::Monospace).or_default().insert(0, "cjk".to_owned());
-            loaded = true;
-            break;
+    // 选择 CSV/TSV 文件，识别编码、分隔符与表头后打开导入窗口；target 为 None 时导入到新建的单词表
+    fn start_csv_import(&mut self, group: &str, folder: Option<Uuid>, target: Option<Uuid>) {
+        let Some(path) = rfd::FileDialog::new().add_filter("CSV/TSV", &["csv", "tsv", "txt"]).pick_file() else { return };
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                self.show_message(&format!("读取失败: {}", e));
+                return;
+            }
+        };
+        let encoding = delimited::detect_encoding(&bytes);
+        let delimiter = match Delimiter::for_path(&path) {
+            Delimiter::Tab => Delimiter::Tab,
+            _ => delimited::detect_delimiter(&encoding.decode(&bytes)),
+        };
+        let new_name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        let mut import = CsvImport {
+            path,
+            bytes,
+            encoding,
+            delimiter,
+            sheet: ImportSheet::new(Vec::new()),
+            error: None,
+            group: group.to_string(),
+            folder,
+            target,
+            new_name,
+        };
+        import.reload();
+        self.csv_import = Some(import);
+    }
+
+    // 导入窗口：编码、分隔符、表头与各列对应的字段，预览前几行，选择新建单词表或合并到已有单词表
+    fn show_csv_import(&mut self, ctx: &egui::Context) {
+        let Some(mut import) = self.csv_import.take() else { return };
+        let tables: Vec<(Uuid, String)> = self
+            .flash_memory
+            .tables_under(&import.group, None)
+            .iter()
+            .map(|t| (t.id, t.name.clone()))
+            .collect();
+        let mut save_mode = self.settings.save_mode;
+        let (words, skipped) = import.sheet.words(&import.group);
+        let mut open = true;
+        let mut confirm = false;
+        let mut cancel = false;
+        let file_name = import.path.file_name().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        egui::Window::new(format!("导入 - {}", file_name))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                let (encoding, delimiter) = (import.encoding, import.delimiter);
+                ui.horizontal(|ui| {
+                    ui.label("编码");
+                    egui::ComboBox::from_id_salt("import_encoding").selected_text(import.encoding.label()).show_ui(ui, |ui| {
+                        for e in TextEncoding::ALL {
+                            ui.selectable_value(&mut import.encoding, e, e.label());
+                        }
+                    });
+                    ui.label("分隔符");
+                    egui::ComboBox::from_id_salt("import_delimiter").selected_text(import.delimiter.label()).show_ui(ui, |ui| {
+                        for d in Delimiter::ALL {
+                            ui.selectable_value(&mut import.delimiter, d, d.label());
+                        }
+                    });
+                    if ui.checkbox(&mut import.sheet.has_header, "第一行是表头").changed() {
+                        import.sheet.guess_mapping();
+                    }
+                });
+                if (encoding, delimiter) != (import.encoding, import.delimiter) {
+                    import.reload();
+                }
+                if let Some(error) = &import.error {
+                    ui.colored_label(egui::Color32::RED, format!("无法读取：{}", error));
+                }
+                ui.separator();
+
+                sheet_preview_ui(ui, &mut import.sheet);
+                ui.label(format!("共 {} 行，将导入 {} 个单词", import.sheet.data_rows().len(), words.len()));
+                if skipped > 0 {
+                    ui.colored_label(egui::Color32::from_rgb(180, 130, 0), format!("{} 行缺少英文或释义，将被跳过", skipped));
+                }
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut import.target, None, "新建单词表");
+                    ui.add_enabled(import.target.is_none(), egui::TextEdit::singleline(&mut import.new_name).desired_width(160.0));
+                });
+                ui.horizontal(|ui| {
+                    let existing = import.target.or(tables.first().map(|(id, _)| *id));
+                    if ui.add_enabled(existing.is_some(), egui::RadioButton::new(import.target.is_some(), "合并到已有单词表")).clicked() {
+                        import.target = existing;
+                    }
+                    if let Some(target) = &mut import.target {
+                        let name = tables.iter().find(|(id, _)| id == target).map(|(_, n)| n.as_str()).unwrap_or_default();
+                        egui::ComboBox::from_id_salt("import_target").selected_text(name).show_ui(ui, |ui| {
+                            for (id, name) in &tables {
+                                ui.selectable_value(target, *id, name);
+                            }
+                        });
+                        for m in SaveMode::ALL {
+                            ui.selectable_value(&mut save_mode, m, m.label());
+                        }
+                    }
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    confirm = ui.add_enabled(!words.is_empty(), egui::Button::new("导入")).clicked();
+                    cancel = ui.button("取消").clicked();
+                });
+            });
+
+        if save_mode != self.settings.save_mode {
+            self.settings.save_mode = save_mode;
+            self.save_settings();
+        }
+        if confirm {
+            self.import_words(&import, words);
+        } else if open && !cancel {
+            self.csv_import = Some(import);
+        }
+    }
+
+    fn import_words(&mut self, import: &CsvImport, words: Vec<Word>) {
+        let group = &import.group;
+        let (table_id, mode) = match import.target {
+            Some(id) => (id, self.settings.save_mode),
+            None => match self.flash_memory.create_word_table(group, import.folder, import.new_name.trim()) {
+                Ok(id) => (id, SaveMode::Replace),
+                Err(e) => {
+                    self.show_message(e);
+                    return;
+                }
+            },
+        };
+        // 计数按保存前后的差异，合并时重复的单词不计
+        let old = self.flash_memory.get_word_table(group, table_id).map(|t| t.words.clone()).unwrap_or_default();
+        let diff = merge::diff_words(&old, &merge::merge_words(&old, words.clone(), mode));
+        let count = diff.added.len() + diff.changed.len();
+        match self.flash_memory.save_words_to_table(group, table_id, words, mode) {
+            Ok(_) => {
+                self.auto_save();
+                self.current_group = Some(group.clone());
+                self.current_folder = import.folder;
+                self.current_word_table = Some(table_id);
+                self.current_page = 0;
+                let name = self.flash_memory.get_word_table(group, table_id).map(|t| t.name.clone()).unwrap_or_default();
+                self.show_message(&format!("已导入 {} 个单词到 {}", count, name));
+            }
+            Err(e) => self.show_message(&format!("导入失败: {}", e)),
+        }
+    }
+
+    // 选择保存位置后导出为 CSV 或 TSV（按扩展名），带 BOM 的 UTF-8
+    fn export_rows(&mut self, rows: Vec<Vec<String>>, name: &str) {
+        let picked = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .add_filter("TSV", &["tsv"])
+            .set_file_name(format!("{}.csv", name))
+            .save_file();
+        let Some(path) = picked else { return };
+        match delimited::write_rows(&path, &rows, Delimiter::for_path(&path)) {
+            Ok(_) => self.show_message(&format!("已导出 {} 个单词到 {}", rows.len() - 1, path.display())),
+            Err(e) => self.show_message(&format!("导出失败: {}", e)),
+        }
+    }
+
+    // 选择工作簿，读取各工作表并识别表头后打开导入窗口；没有内容的工作表默认不导入
+    fn start_xlsx_import(&mut self, group: &str, folder: Option<Uuid>) {
+        let picked = rfd::FileDialog::new().add_filter("Excel 工作簿", &["xlsx", "xls", "ods"]).pick_file();
+        let Some(path) = picked else { return };
+        match xlsx::read_workbook(&path) {
+            Ok(sheets) => {
+                let sheets = sheets
+                    .into_iter()
+                    .map(|s| {
+                        let sheet = ImportSheet::new(s.rows);
+                        XlsxSheet { name: s.name, include: !sheet.data_rows().is_empty(), sheet }
+                    })
+                    .collect();
+                self.xlsx_import = Some(XlsxImport { path, group: group.to_string(), folder, sheets, current: 0 });
+            }
+            Err(e) => self.show_message(&format!("读取失败: {}", e)),
+        }
+    }
+
+    // 导入窗口：选择要导入的工作表，逐个设置表头、各列对应的字段与单词表名；同名单词表已存在时按保存方式合并
+    fn show_xlsx_import(&mut self, ctx: &egui::Context) {
+        let Some(mut import) = self.xlsx_import.take() else { return };
+        let mut save_mode = self.settings.save_mode;
+        let mut open = true;
+        let mut confirm = false;
+        let mut cancel = false;
+        let file_name = import.path.file_name().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        egui::Window::new(format!("导入 - {}", file_name))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label("工作表");
+                    for (i, sheet) in import.sheets.iter_mut().enumerate() {
+                        ui.checkbox(&mut sheet.include, "");
+                        if ui.selectable_label(import.current == i, &sheet.name).clicked() {
+                            import.current = i;
+                        }
+                    }
+                });
+                ui.separator();
+
+                let current = import.current;
+                if let Some(entry) = import.sheets.get_mut(current) {
+                    ui.horizontal(|ui| {
+                        ui.label("单词表名");
+                        ui.add(egui::TextEdit::singleline(&mut entry.name).desired_width(160.0));
+                        if ui.checkbox(&mut entry.sheet.has_header, "第一行是表头").changed() {
+                            entry.sheet.guess_mapping();
+                        }
+                    });
+                    ui.push_id(current, |ui| sheet_preview_ui(ui, &mut entry.sheet));
+                    let (words, skipped) = entry.sheet.words(&import.group);
+                    ui.label(format!("共 {} 行，将导入 {} 个单词", entry.sheet.data_rows().len(), words.len()));
+                    if skipped > 0 {
+                        ui.colored_label(egui::Color32::from_rgb(180, 130, 0), format!("{} 行缺少英文或释义，将被跳过", skipped));
+                    }
+                }
+                ui.separator();
+
+                let included: Vec<&XlsxSheet> = import.sheets.iter().filter(|s| s.include).collect();
+                let existing = included
+                    .iter()
+                    .filter(|s| self.flash_memory.find_word_table(&import.group, import.folder, s.name.trim()).is_some())
+                    .count();
+                ui.label(format!("将导入 {} 个工作表", included.len()));
+                if existing > 0 {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{} 个同名单词表已存在", existing));
+                        for m in SaveMode::ALL {
+                            ui.selectable_value(&mut save_mode, m, m.label());
+                        }
+                    });
+                }
+                let named = included.iter().all(|s| !s.name.trim().is_empty());
+                // 两个工作表的单词表名相同时，后一个会覆盖前一个刚导入的单词
+                let duplicate = included
+                    .iter()
+                    .enumerate()
+                    .find(|(i, s)| included[..*i].iter().any(|o| o.name.trim() == s.name.trim()))
+                    .map(|(_, s)| s.name.trim());
+                if let Some(name) = duplicate {
+                    ui.colored_label(egui::Color32::RED, format!("多个工作表的单词表名都是“{}”，请修改", name));
+                }
+                ui.horizontal(|ui| {
+                    let ready = !included.is_empty() && named && duplicate.is_none();
+                    confirm = ui.add_enabled(ready, egui::Button::new("导入")).clicked();
+                    cancel = ui.button("取消").clicked();
+                });
+            });
+
+        if save_mode != self.settings.save_mode {
+            self.settings.save_mode = save_mode;
+            self.save_settings();
+        }
+        if confirm {
+            self.import_sheets(&import);
+        } else if open && !cancel {
+            self.xlsx_import = Some(import);
+        }
+    }
+
+    // 按勾选的工作表逐个导入，计数按保存前后的差异（合并时重复的单词不计）；先在单词库的副本上导入，
+    // 全部成功后才替换，某个工作表失败时单词库保持不变
+    fn import_sheets(&mut self, import: &XlsxImport) {
+        let group = &import.group;
+        let mut staged = self.flash_memory.clone();
+        let (mut count, mut tables) = (0, 0);
+        for entry in import.sheets.iter().filter(|s| s.include) {
+            let (words, _) = entry.sheet.words(group);
+            let name = entry.name.trim();
+            let result = match staged.find_word_table(group, import.folder, name) {
+                Some(table) => Ok((table.id, self.settings.save_mode, table.words.clone())),
+                None => staged.create_word_table(group, import.folder, name).map(|id| (id, SaveMode::Replace, Vec::new())),
+            };
+            let saved = result.and_then(|(id, mode, old)| {
+                let diff = merge::diff_words(&old, &merge::merge_words(&old, words.clone(), mode));
+                staged.save_words_to_table(group, id, words, mode)?;
+                Ok(diff.added.len() + diff.changed.len())
+            });
+            match saved {
+                Ok(saved) => {
+                    count += saved;
+                    tables += 1;
+                }
+                Err(e) => {
+                    self.show_message(&format!("导入 {} 失败，单词库未改动: {}", name, e));
+                    return;
+                }
+            }
+        }
+        self.flash_memory = staged;
+        self.auto_save();
+        self.current_group = Some(group.clone());
+        self.current_folder = import.folder;
+        self.current_word_table = None;
+        self.current_page = 0;
+        self.show_message(&format!("已导入 {} 个单词到 {} 个单词表", count, tables));
+    }
+
+    // 选择保存位置后把分组导出为工作簿，每个单词表一个工作表
+    fn export_xlsx(&mut self, group: &str) {
+        let picked = rfd::FileDialog::new()
+            .add_filter("Excel 工作簿", &["xlsx"])
+            .set_file_name(format!("{}.xlsx", group))
+            .save_file();
+        let Some(path) = picked else { return };
+        match xlsx::write_workbook(&path, &self.flash_memory, group) {
+            Ok(count) => self.show_message(&format!("已导出 {} 个单词到 {}", count, path.display())),
+            Err(e) => self.show_message(&format!("导出失败: {}", e)),
+        }
+    }
+
+    // 选择 .apkg / .colpkg 文件，读取其中的牌组后打开导入窗口
+    fn start_anki_import(&mut self) {
+        let picked = rfd::FileDialog::new().add_filter("Anki 牌组", &["apkg", "colpkg"]).pick_file();
+        let Some(path) = picked else { return };
+        match anki::read_package(&path) {
+            Ok(package) if package.decks.is_empty() => self.show_message("文件中没有可以导入的单词"),
+            Ok(package) => self.anki_import = Some(AnkiImport { path, package }),
+            Err(e) => self.show_message(&format!("读取失败: {}", e)),
+        }
+    }
+
+    // 导入窗口：列出各牌组对应的单词表与单词数，已有的单词表按选择的保存方式合并
+    fn show_anki_import(&mut self, ctx: &egui::Context) {
+        let Some(import) = self.anki_import.take() else { return };
+        let mut save_mode = self.settings.save_mode;
+        let mut open = true;
+        let mut confirm = false;
+        let mut cancel = false;
+        let file_name = import.path.file_name().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        egui::Window::new(format!("导入 Anki 牌组 - {}", file_name))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                ui.label("顶层牌组导入为分组，子牌组导入为文件夹与单词表；图片、声音与复习进度不导入");
+                ui.separator();
+                let mut existing = 0;
+                egui::ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+                    egui::Grid::new("anki_decks").striped(true).show(ui, |ui| {
+                        for deck in &import.package.decks {
+                            ui.label(deck.path.join(" / "));
+                            ui.label(format!("{} 个单词", deck.words.len()));
+                            if anki::deck_exists(&self.flash_memory, &deck.path) {
+                                existing += 1;
+                                ui.label("合并到已有单词表");
+                            } else {
+                                ui.label("新建");
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+                let total: usize = import.package.decks.iter().map(|d| d.words.len()).sum();
+                ui.label(format!("共 {} 个牌组，{} 个单词", import.package.decks.len(), total));
+                if import.package.skipped > 0 {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(180, 130, 0),
+                        format!("{} 条笔记缺少英文或释义，将被跳过", import.package.skipped),
+                    );
+                }
+                if existing > 0 {
+                    ui.horizontal(|ui| {
+                        ui.label("已有的单词表");
+                        for m in SaveMode::ALL {
+                            ui.selectable_value(&mut save_mode, m, m.label());
+                        }
+                    });
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    confirm = ui.button("导入").clicked();
+                    cancel = ui.button("取消").clicked();
+                });
+            });
+
+        if save_mode != self.settings.save_mode {
+            self.settings.save_mode = save_mode;
+            self.save_settings();
+        }
+        if confirm {
+            let group = import.package.decks[0].path[0].clone();
+            match anki::import_decks(&mut self.flash_memory, import.package.decks, save_mode) {
+                Ok(count) => {
+                    self.auto_save();
+                    self.current_group = Some(group);
+                    self.current_folder = None;
+                    self.current_word_table = None;
+                    self.current_page = 0;
+                    self.show_message(&format!("已导入 {} 个单词", count));
+                }
+                Err(e) => self.show_message(&format!("导入失败，单词库未改动: {}", e)),
+            }
+        } else if open && !cancel {
+            self.anki_import = Some(import);
+        }
+    }
+
+    // 选择保存位置后把分组导出为 .apkg
+    fn export_anki(&mut self, group: &str) {
+        let picked = rfd::FileDialog::new()
+            .add_filter("Anki 牌组", &["apkg"])
+            .set_file_name(format!("{}.apkg", group))
+            .save_file();
+        let Some(path) = picked else { return };
+        match anki::write_package(&path, &self.flash_memory, group) {
+            Ok(count) => self.show_message(&format!("已导出 {} 个单词到 {}", count, path.display())),
+            Err(e) => self.show_message(&format!("导出失败: {}", e)),
+        }
+    }
+
+    // 在分组或文件夹中新建子文件夹，选中并直接进入重命名状态
+    fn create_folder_in(&mut self, group: &str, parent: Option<Uuid>) {
+        match self.flash_memory.create_folder(group, parent, "新文件夹") {
+            Ok(id) => {
+                if let Some(parent) = parent {
+                    self.settings.expanded_folders.insert(parent);
+                    self.save_settings();
+                }
+                self.current_group = Some(group.to_string());
+                self.current_folder = Some(id);
+                self.current_word_table = None;
+                self.renaming_folder_active = true;
+                self.renaming_folder_input = self.flash_memory.get_folder(group, id).map(|f| f.name.clone()).unwrap_or_default();
+                self.show_message("已创建新文件夹，可直接重命名");
+                self.auto_save(); // 自动保存
+            }
+            Err(e) => self.show_message(e),
+        }
+    }
+
+    // 目录中分组或文件夹的内容：先子文件夹（展开时接着显示其内容），后单词表
+    fn folder_contents_ui(&mut self, ui: &mut egui::Ui, group: &str, parent: Option<Uuid>, depth: usize, sort_mode: SortMode, today: NaiveDate) {
+        let folders: Vec<Folder> = ordering::sorted_folders(&self.flash_memory, group, parent, sort_mode, today)
+            .into_iter()
+            .cloned()
+            .collect();
+        for folder in folders {
+            self.folder_row_ui(ui, group, &folder, depth, sort_mode, today);
+            if self.settings.expanded_folders.contains(&folder.id) {
+                self.folder_contents_ui(ui, group, Some(folder.id), depth + 1, sort_mode, today);
+            }
+        }
+        let tables: Vec<WordTable> = ordering::sorted_tables(&self.flash_memory, group, parent, sort_mode, today)
+            .into_iter()
+            .cloned()
+            .collect();
+        for table in tables {
+            self.word_table_row_ui(ui, group, &table, depth, sort_mode, today);
+        }
+    }
+
+    fn folder_row_ui(&mut self, ui: &mut egui::Ui, group: &str, folder: &Folder, depth: usize, sort_mode: SortMode, today: NaiveDate) {
+        ui.add_space(2.0);
+        ui.horizontal(|ui| {
+            ui.add_space(20.0 * depth as f32); // 缩进
+            // 点击三角展开或收起，展开状态保存在偏好设置中
+            let expanded = self.settings.expanded_folders.contains(&folder.id);
+            let arrow = ui.add(egui::Label::new(if expanded { "▼" } else { "▶" }).sense(egui::Sense::click()));
+            if arrow.clicked() {
+                if expanded {
+                    self.settings.expanded_folders.remove(&folder.id);
+                } else {
+                    self.settings.expanded_folders.insert(folder.id);
+                }
+                self.save_settings();
+            }
+            let folder_selected = self.current_folder == Some(folder.id) && self.current_word_table.is_none();
+
+            // 文件夹重命名状态
+            if folder_selected && self.renaming_folder_active {
+                let resp = ui.text_edit_singleline(&mut self.renaming_folder_input);
+                let confirm = ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let cancel = ui.input(|i| i.key_pressed(egui::Key::Escape));
+                if confirm || resp.lost_focus() {
+                    let new_name = self.renaming_folder_input.trim().to_string();
+                    self.renaming_folder_active = false;
+                    if new_name.is_empty() {
+                        self.show_message("文件夹名不能为空");
+                    } else {
+                        match self.flash_memory.rename_folder(group, folder.id, &new_name) {
+                            Ok(_) => {
+                                self.show_message("文件夹已重命名");
+                                self.auto_save(); // 自动保存
+                            }
+                            Err(e) => self.show_message(e),
+                        }
+                    }
+                } else if cancel {
+                    self.renaming_folder_active = false;
+                }
+                resp.request_focus();
+                return;
+            }
+
+            let label = if sort_mode == SortMode::DueCount {
+                let due: usize = self.flash_memory.tables_under(group, Some(folder.id)).iter().map(|t| t.due_count(today)).sum();
+                format!("{} ({})", folder.name, due)
+            } else {
+                folder.name.clone()
+            };
+            let mut resp = ui.selectable_label(folder_selected, label);
+            if sort_mode == SortMode::Manual {
+                // 拖到同级的另一个文件夹上时移到该文件夹的位置
+                resp = resp.interact(egui::Sense::drag());
+                resp.dnd_set_drag_payload(DraggedFolder(group.to_string(), folder.id));
+                let siblings: Vec<Uuid> = self.flash_memory.child_folders(group, folder.parent).iter().map(|f| f.id).collect();
+                let to = siblings.iter().position(|&id| id == folder.id);
+                if let Some(dragged) = resp.dnd_hover_payload::<DraggedFolder>() {
+                    let from = siblings.iter().position(|&id| dragged.0 == group && id == dragged.1);
+                    paint_drop_marker(ui, resp.rect, from, to);
+                }
+                if let Some(dragged) = resp.dnd_release_payload::<DraggedFolder>() {
+                    if dragged.0 != group || !siblings.contains(&dragged.1) {
+                        self.show_message("文件夹只能在同一层中调整顺序");
+                    } else if self.flash_memory.move_folder(group, dragged.1, to.unwrap_or(0)).is_ok() {
+                        self.auto_save();
+                    }
+                }
+                // 单词表拖到文件夹上时移入该文件夹
+                if resp.dnd_hover_payload::<DraggedTable>().is_some_and(|dragged| dragged.0 == group) {
+                    ui.painter().rect_stroke(resp.rect, 2.0, egui::Stroke::new(2.0, egui::Color32::DARK_BLUE));
+                }
+                if let Some(dragged) = resp.dnd_release_payload::<DraggedTable>() {
+                    if dragged.0 != group {
+                        self.show_message("单词表只能在所在分组内移动");
+                    } else {
+                        match self.flash_memory.move_word_table(group, dragged.1, Some(folder.id), usize::MAX) {
+                            Ok(_) => {
+                                self.settings.expanded_folders.insert(folder.id);
+                                self.save_settings();
+                                self.auto_save();
+                            }
+                            Err(e) => self.show_message(e),
+                        }
+                    }
+                }
+            }
+            // 双击重命名（优先处理双击）
+            if resp.double_clicked() {
+                self.current_folder = Some(folder.id);
+                self.current_word_table = None;
+                self.renaming_folder_active = true;
+                self.renaming_folder_input = folder.name.clone();
+            } else if resp.clicked() {
+                // 选中文件夹后学习其中各层的全部单词表
+                self.current_folder = Some(folder.id);
+                self.current_word_table = None;
+                self.checked_word_tables.clear();
+                self.current_page = 0;
+            }
+            // 右键菜单
+            if resp.secondary_clicked() {
+                self.context_menu_folder = Some((group.to_string(), folder.id));
+                self.show_folder_context_menu = true;
+                self.folder_context_menu_pos = resp.interact_pointer_pos().unwrap_or_default();
+            }
+        });
+    }
+
+    fn word_table_row_ui(&mut self, ui: &mut egui::Ui, group: &str, table: &WordTable, depth: usize, sort_mode: SortMode, today: NaiveDate) {
+        ui.add_space(2.0);
+        ui.horizontal(|ui| {
+            ui.add_space(20.0 * depth as f32); // 缩进
+            let table_selected = self.current_word_table == Some(table.id) || self.checked_word_tables.contains(&table.id);
+
+            // 单词表重命名状态
+            if table_selected && self.renaming_word_table_active {
+                let resp = ui.text_edit_singleline(&mut self.renaming_word_table_input);
+
+                // 按 Enter 确认重命名
+                let confirm = ui.input(|i| i.key_pressed(egui::Key::Enter));
+                // 按 Escape 取消重命名
+                let cancel = ui.input(|i| i.key_pressed(egui::Key::Escape));
+                // 失去焦点时自动确认重命名（相当于按Enter）
+                let lost_focus = resp.lost_focus();
+
+                if confirm || lost_focus {
+                    let new_name = self.renaming_word_table_input.trim().to_string();
+                    self.renaming_word_table_active = false;
+                    if new_name.is_empty() {
+                        self.show_message("单词表名不能为空");
+                    } else {
+                        match self.flash_memory.rename_word_table(group, table.id, &new_name) {
+                            Ok(_) => {
+                                self.show_message("单词表已重命名");
+                                self.auto_save(); // 自动保存
+                            }
+                            Err(e) => self.show_message(e),
+                        }
+                    }
+                } else if cancel {
+                    self.renaming_word_table_active = false;
+                }
+
+                // 请求焦点以确保输入框保持活跃状态
+                resp.request_focus();
+                return;
+            }
+
+            let label = if sort_mode == SortMode::DueCount {
+                format!("{} ({})", table.name, table.due_count(today))
+            } else {
+                table.name.clone()
+            };
+            let mut resp = ui.selectable_label(table_selected, label);
+            // 拖到同一分组的另一个单词表上时移到该单词表的位置（不同文件夹之间同时移入目标文件夹）
+            if sort_mode == SortMode::Manual {
+                resp = resp.interact(egui::Sense::drag());
+                resp.dnd_set_drag_payload(DraggedTable(group.to_string(), table.id));
+                let siblings: Vec<Uuid> = self.flash_memory.child_tables(group, table.folder).iter().map(|t| t.id).collect();
+                let to = siblings.iter().position(|&id| id == table.id);
+                if let Some(dragged) = resp.dnd_hover_payload::<DraggedTable>() {
+                    if dragged.0 == group {
+                        // 从其他文件夹拖来时画在上沿
+                        let from = siblings.iter().position(|&id| id == dragged.1).or(to.map(|to| to + 1));
+                        paint_drop_marker(ui, resp.rect, from, to);
+                    }
+                }
+                if let Some(dragged) = resp.dnd_release_payload::<DraggedTable>() {
+                    if dragged.0 != group {
+                        self.show_message("单词表只能在所在分组内移动");
+                    } else {
+                        match self.flash_memory.move_word_table(group, dragged.1, table.folder, to.unwrap_or(0)) {
+                            Ok(_) => self.auto_save(),
+                            Err(e) => self.show_message(e),
+                        }
+                    }
+                }
+            }
+            // 双击重命名（优先处理双击）
+            if resp.double_clicked() {
+                self.current_folder = table.folder;
+                self.current_word_table = Some(table.id);
+                self.renaming_word_table_active = true;
+                self.renaming_word_table_input = table.name.clone();
+                // 请求焦点到输入框
+                ui.memory_mut(|mem| mem.request_focus(egui::Id::new("word_table_rename")));
+            } else if resp.clicked() && ui.input(|i| i.modifiers.command) {
+                // Ctrl+点击多选，一起学习；已单选的单词表作为多选的第一个
+                if self.checked_word_tables.is_empty() {
+                    self.checked_word_tables.extend(self.current_word_table.take());
+                }
+                if let Some(pos) = self.checked_word_tables.iter().position(|&id| id == table.id) {
+                    self.checked_word_tables.remove(pos);
+                } else {
+                    self.checked_word_tables.push(table.id);
+                }
+                self.current_folder = None;
+                self.current_word_table = None;
+            } else if resp.clicked() {
+                // 只有在非双击时才处理单击
+                self.current_folder = table.folder;
+                self.current_word_table = Some(table.id);
+                self.checked_word_tables.clear();
+            }
+            // 右键菜单
+            if resp.secondary_clicked() {
+                self.context_menu_word_table = Some((group.to_string(), table.id));
+                self.show_word_table_context_menu = true;
+                self.word_table_context_menu_pos = resp.interact_pointer_pos().unwrap_or_default();
+            }
+        });
+    }
+
+    // 切换到另一个单词库；无法读取时保持当前单词库不变
+    fn open_library(&mut self, path: &Path) {
+        let path = storage::resolve_library(path);
+        let mut storage = storage::open(&path);
+        match storage.load() {
+            Ok(flash_memory) => {
+                self.review_log = load_review_log(storage.as_mut(), &flash_memory);
+                self.flash_memory = flash_memory;
+                self.storage = storage;
+                self.load_error = None;
+                self.current_group = None;
+                self.current_folder = None;
+                self.current_word_table = None;
+                self.checked_word_tables.clear();
+                self.current_page = 0;
+                self.editing_word_table = None;
+                self.scheduler_settings_group = None;
+                self.timing_settings_table = None;
+                self.session_summary = None;
+                self.settings.remember_library(&path);
+                self.save_settings();
+                self.show_message(&format!("已打开 {}", path.display()));
+            }
+            Err(e) => self.show_message(&format!("无法打开 {}: {}", path.display(), e)),
+        }
+    }
+
+    // 把当前 JSON 单词库与复习记录复制到同名的 .db 文件，之后改用 SQLite 保存；原文件保持不变
+    fn migrate_to_sqlite(&mut self) {
+        let db_path = self.storage.path().with_extension("db");
+        match storage::migrate_to_sqlite(&self.flash_memory, self.review_log.records(), &db_path) {
+            Ok(sqlite) => {
+                self.storage = Box::new(sqlite);
+                self.settings.remember_library(&db_path);
+                self.save_settings();
+                self.show_message(&format!("已迁移到 {}", db_path.display()));
+            }
+            Err(e) => self.show_message(&format!("迁移失败: {}", e)),
+        }
+    }
+
+    // 卡片展示完毕：评分的单词更新复习状态并保存，所有展示写入复习记录
+    fn record_presentation(&mut self, presentation: &Presentation) {
+        let Some((group, _)) = self.flash_source.clone() else { return };
+        let word = &presentation.word;
+        let card = self.flash_cards.get(&word.id);
+        // 记录单词所在单词表的名称（同时学习多个单词表时来自不同的单词表）
+        let table_name = card.and_then(|c| c.tables.first().cloned()).unwrap_or_default();
+        let copies = card.map(|c| c.copies.clone()).unwrap_or_default();
+        if let Some(grade) = presentation.grade {
+            let today = chrono::Local::now().date_naive();
+            // 其他单词表中相同的单词一并评分
+            let graded = std::iter::once(word.id)
+                .chain(copies)
+                .try_for_each(|id| self.flash_memory.grade_word(&group, &Word { id, ..word.clone() }, grade, today));
+            match graded {
+                Ok(_) => self.auto_save(),
+                Err(e) => self.show_message(e),
+            }
+        }
+        let record = ReviewRecord {
+            word_id: Some(word.id),
+            group,
+            table: table_name,
+            english: word.english.clone(),
+            chinese: word.chinese.clone(),
+            mode: presentation.mode,
+            grade: presentation.grade,
+            reveal_ms: presentation.reveal_ms,
+            response_ms: presentation.response_ms,
+            reviewed_at: chrono::Local::now(),
+        };
+        if let Err(e) = self.storage.append_review(&record) {
+            self.show_message(&format!("复习记录保存失败: {}", e));
+        }
+        self.review_log.push(record);
+    }
+
+    // 把事件交给本轮学习，并处理产生的状态变化
+    fn handle_session_event(&mut self, event: SessionEvent) {
+        let Some(session) = self.session.as_mut() else { return };
+        let transitions = session.handle(event);
+        for transition in transitions {
+            match transition {
+                Transition::Presented(presentation) => self.record_presentation(&presentation),
+                Transition::CardShown(_) if self.flash_mode() == FlashMode::Choice => self.prepare_choice_question(),
+                Transition::Finished => self.finish_session(),
+                _ => {}
+            }
+        }
+    }
+
+    // 当前所处的学习状态
+    fn flash_mode(&self) -> FlashMode {
+        match &self.session {
+            None => FlashMode::Preview,
+            Some(session) => match session.mode() {
+                StudyMode::Typing => FlashMode::Typing,
+                StudyMode::Choice => FlashMode::Choice,
+                _ if session.is_paused() => FlashMode::Paused,
+                _ => FlashMode::Started,
+            },
+        }
+    }
+
+    // 数据文件无法读取：从备份恢复，或把损坏的文件改名保留后使用空单词库
+    fn show_recovery_dialog(&mut self, ctx: &egui::Context) {
+        let data_file = self.storage.path().to_path_buf();
+        // 备份只针对 JSON 单词库
+        let backups = match self.storage.kind() {
+            StorageKind::Json => storage::list_backups(&data_file),
+            StorageKind::Sqlite => Vec::new(),
+        };
+        let mut restore = None;
+        let mut set_aside = false;
+        egui::Window::new("数据文件无法读取")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!("读取 {} 失败：{}", data_file.display(), self.load_error.as_deref().unwrap_or_default()));
+                ui.label("处理之前不会保存任何修改，原文件保持不变。");
+                ui.separator();
+                if backups.is_empty() {
+                    ui.weak("没有找到备份");
+                } else {
+                    ui.label("从备份恢复：");
+                    egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                        egui::Grid::new("backup_list").num_columns(2).spacing([20.0, 6.0]).show(ui, |ui| {
+                            for (time, path) in &backups {
+                                ui.label(time.format("%Y-%m-%d %H:%M:%S").to_string());
+                                if ui.button("恢复").clicked() {
+                                    restore = Some((*time, path.clone()));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    });
+                }
+                ui.separator();
+                if ui.button("保留损坏的文件，使用空单词库").clicked() {
+                    set_aside = true;
+                }
+            });
+
+        if let Some((time, path)) = restore {
+            match FlashMemory::restore_backup(&path, &data_file.to_string_lossy()) {
+                Ok(flash_memory) => {
+                    self.flash_memory = flash_memory;
+                    self.load_error = None;
+                    self.current_group = None;
+                    self.current_folder = None;
+                    self.current_word_table = None;
+                    self.checked_word_tables.clear();
+                    self.show_message(&format!("已从 {} 的备份恢复", time.format("%Y-%m-%d %H:%M:%S")));
+                }
+                Err(e) => self.show_message(&format!("备份无法读取: {}", e)),
+            }
+        } else if set_aside {
+            match storage::set_aside_broken(&data_file, chrono::Local::now().naive_local()) {
+                Ok(path) => {
+                    self.storage = storage::open(&data_file);
+                    self.load_error = None;
+                    self.show_message(&format!("损坏的文件已另存为 {}", path.display()));
+                }
+                Err(e) => self.show_message(&format!("无法另存损坏的文件: {}", e)),
+            }
+        }
+    }
+
+    fn show_timing_settings(&mut self, ctx: &egui::Context, group: &str, table_id: Uuid) {
+        let Some(table) = self.flash_memory.get_word_table(group, table_id) else {
+            self.timing_settings_table = None;
+            return;
+        };
+        let (table_name, mut timing) = (table.name.clone(), table.timing.clone());
+        let before = timing.clone();
+        let mut open = true;
+        let mut commit = false;
+        egui::Window::new(format!("闪记设置 - {}", table_name))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                // 拖动结束后再保存，避免拖动过程中反复写盘
+                let mut track = |resp: egui::Response| {
+                    commit |= resp.drag_stopped() || (resp.changed() && !resp.dragged());
+                };
+                let seconds = |value, range| egui::Slider::new(value, range).step_by(0.1).suffix(" 秒");
+                track(ui.add(seconds(&mut timing.front, 0.3..=10.0).text("英文展示")));
+                track(ui.add(seconds(&mut timing.back, 0.3..=10.0).text("释义展示")));
+                track(ui.add(seconds(&mut timing.gap, 0.0..=5.0).text("卡片间隔")));
+                track(ui.add(egui::Slider::new(&mut timing.countdown, 0..=10).suffix(" 秒").text("开始倒计时")));
+                track(ui.checkbox(&mut timing.adaptive, "自适应时长")
+                    .on_hover_text("长单词的英文展示时间更长；上次答错的单词整体延长一半"));
+                ui.add_space(6.0);
+                if ui.button("恢复默认").clicked() {
+                    timing = FlashTiming::default();
+                    commit = true;
+                }
+            });
+
+        if timing != before {
+            if let Err(e) = self.flash_memory.set_word_table_timing(group, table_id, timing) {
+                self.show_message(e);
+            }
+        }
+        if commit {
+            self.auto_save();
+        }
+        if !open {
+            self.timing_settings_table = None;
+        }
+    }
+
+    fn show_scheduler_settings(&mut self, ctx: &egui::Context, group: &str) {
+        let mut open = true;
+        let mut settings = self.flash_memory.scheduler_settings.get(group).cloned().unwrap_or_default();
+        let mut commit = false;
+        let mut optimize_clicked = false;
+        egui::Window::new(format!("复习设置 - {}", group))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("调度算法");
+                    for algorithm in [Algorithm::Sm2, Algorithm::Fsrs] {
+                        if ui.radio_value(&mut settings.algorithm, algorithm, algorithm.label()).changed() {
+                            commit = true;
+                        }
+                    }
+                });
+                if settings.algorithm == Algorithm::Fsrs {
+                    ui.add_space(6.0);
+                    let resp = ui.add(egui::Slider::new(&mut settings.target_retention, 0.7..=0.97).text("目标保持率"));
+                    // 拖动结束后再重新排期，避免拖动过程中反复写盘
+                    if resp.drag_stopped() || (resp.changed() && !resp.dragged()) {
+                        commit = true;
+                    }
+                    ui.add_space(6.0);
+                    ui.label(if settings.fsrs_weights.is_some() { "当前使用优化后的参数" } else { "当前使用默认参数" });
+                    ui.horizontal(|ui| {
+                        let idle = self.optimizing.is_none();
+                        if ui.add_enabled(idle, egui::Button::new("优化参数")).on_hover_text("根据本分组的复习记录拟合 FSRS 参数").clicked() {
+                            optimize_clicked = true;
+                        }
+                        if !idle {
+                            ui.spinner();
+                        }
+                        if ui.add_enabled(settings.fsrs_weights.is_some(), egui::Button::new("恢复默认参数")).clicked() {
+                            settings.fsrs_weights = None;
+                            commit = true;
+                        }
+                    });
+                }
+                if !self.optimize_report.is_empty() {
+                    ui.add_space(6.0);
+                    ui.small(&self.optimize_report);
+                }
+            });
+
+        // 优化需要反复重放全部复习记录，放到后台线程，完成后由 poll_optimize 应用
+        if optimize_clicked {
+            let histories: Vec<_> = self.review_log
+                .histories_in_group(&self.flash_memory, group)
+                .into_values()
+                .collect();
+            let initial = settings.fsrs_weights
+                .as_deref()
+                .and_then(|w| <[f64; 17]>::try_from(w).ok())
+                .unwrap_or(scheduler::DEFAULT_WEIGHTS);
+            let (sender, receiver) = mpsc::channel();
+            std::thread::spawn(move || {
+                let _ = sender.send(scheduler::optimize(&histories, initial));
+            });
+            self.optimizing = Some((group.to_string(), receiver));
+            self.optimize_report = "正在优化参数…".to_string();
+        }
+
+        self.flash_memory.scheduler_settings.insert(group.to_string(), settings);
+        if commit {
+            self.flash_memory.reschedule_group(group, &self.review_log);
+            self.auto_save();
+        }
+        if !open {
+            self.scheduler_settings_group = None;
+        }
+    }
+
+    // 取回后台优化的结果：成功时保存参数并按新参数重新排期
+    fn poll_optimize(&mut self, ctx: &egui::Context) {
+        let Some((group, receiver)) = &self.optimizing else { return };
+        let result = match receiver.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => {
+                ctx.request_repaint_after(std::time::Duration::from_millis(100));
+                return;
+            }
+            Err(mpsc::TryRecvError::Disconnected) => Err("参数优化意外中止"),
+        };
+        let group = group.clone();
+        self.optimizing = None;
+        match result {
+            Ok(result) => {
+                let mut settings = self.flash_memory.scheduler_settings.get(&group).cloned().unwrap_or_default();
+                settings.fsrs_weights = Some(result.weights.to_vec());
+                self.flash_memory.scheduler_settings.insert(group.clone(), settings);
+                self.flash_memory.reschedule_group(&group, &self.review_log);
+                self.auto_save();
+                self.optimize_report = format!(
+                    "已根据 {} 次复习优化参数，对数损失 {:.4} → {:.4}",
+                    result.samples, result.loss_before, result.loss_after
+                );
+            }
+            Err(e) => self.optimize_report = e.to_string(),
+        }
+    }
+
+    fn get_current_due_words(&self) -> Vec<Word> {
+        let today = chrono::Local::now().date_naive();
+        self.current_cards(|w| w.schedule.is_due(today)).into_iter().map(|c| c.word).collect()
+    }
+
+    fn toggle_pause(&mut self, ctx: &egui::Context) {
+        match self.flash_mode() {
+            FlashMode::Started => self.handle_session_event(SessionEvent::Pause),
+            FlashMode::Paused => {
+                self.handle_session_event(SessionEvent::Resume);
+                // 恢复动画需要持续重绘
+                ctx.request_repaint();
+            }
+            _ => {}
+        }
+    }
+
+    fn end_session(&mut self) {
+        self.handle_session_event(SessionEvent::End);
+        self.session = None;
+        self.current_page = 0;
+        self.flash_source = None;
+        self.flash_cards.clear();
+    }
+
+    // 闪记过程中的键盘操作；输入框获得焦点时（如拼写测验）不处理
+    fn handle_study_keys(&mut self, ctx: &egui::Context) {
+        if self.flash_mode() == FlashMode::Preview || self.capturing_action.is_some() || ctx.wants_keyboard_input() {
+            return;
+        }
+        let in_flash = matches!(self.flash_mode(), FlashMode::Started | FlashMode::Paused);
+        let on_card = in_flash && self.session.as_ref().is_some_and(|s| s.on_card());
+        let enabled = |action| match action {
+            StudyAction::End | StudyAction::ToggleHelp => true,
+            StudyAction::PauseResume => in_flash,
+            _ => on_card,
+        };
+        let actions = ctx.input_mut(|i| self.settings.key_bindings.take_pressed(i, enabled));
+        for action in actions {
+            // 前一个操作可能已经结束本轮或切换了卡片
+            if self.flash_mode() == FlashMode::Preview {
+                break;
+            }
+            let in_flash = matches!(self.flash_mode(), FlashMode::Started | FlashMode::Paused);
+            match action {
+                StudyAction::End => self.end_session(),
+                StudyAction::ToggleHelp => self.show_key_help = !self.show_key_help,
+                StudyAction::PauseResume if in_flash => self.toggle_pause(ctx),
+                StudyAction::Previous if in_flash => self.handle_session_event(SessionEvent::Back),
+                StudyAction::Next if in_flash => self.handle_session_event(SessionEvent::Skip),
+                StudyAction::Reveal if in_flash => self.handle_session_event(SessionEvent::Reveal),
+                StudyAction::MarkKnown if in_flash => self.handle_session_event(SessionEvent::Grade(Grade::Good)),
+                StudyAction::MarkUnknown if in_flash => self.handle_session_event(SessionEvent::Grade(Grade::Again)),
+                _ => {}
+            }
+        }
+    }
+
+    fn save_settings(&mut self) {
+        if let Err(e) = self.settings.save_to_file(&self.settings_path) {
+            self.show_message(&format!("设置保存失败: {}", e));
+        }
+    }
+
+    fn show_key_settings(&mut self, ctx: &egui::Context) {
+        // 等待新按键：取第一个按下的键并消耗掉，避免触发按钮
+        if let Some(action) = self.capturing_action {
+            let key = ctx.input_mut(|i| {
+                let key = i.events.iter().find_map(|e| match e {
+                    egui::Event::Key { key, pressed: true, .. } => Some(*key),
+                    _ => None,
+                });
+                if let Some(key) = key {
+                    i.consume_key(egui::Modifiers::NONE, key);
+                }
+                key
+            });
+            if let Some(key) = key {
+                if let Some(other) = self.settings.key_bindings.bind(action, key) {
+                    self.show_message(&format!("按键已被“{}”使用，已互换", other.label()));
+                }
+                self.capturing_action = None;
+                self.save_settings();
+            }
+        }
+
+        let mut open = true;
+        let mut reset = false;
+        egui::Window::new("快捷键设置")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("key_bindings_grid").num_columns(3).spacing([16.0, 6.0]).show(ui, |ui| {
+                    for action in StudyAction::ALL {
+                        ui.label(action.label());
+                        if self.capturing_action == Some(action) {
+                            ui.colored_label(egui::Color32::DARK_BLUE, "请按下新按键…");
+                        } else {
+                            ui.monospace(self.settings.key_bindings.key(action).symbol_or_name());
+                        }
+                        if ui.button("修改").clicked() {
+                            self.capturing_action = Some(action);
+                        }
+                        ui.end_row();
+                    }
+                });
+                ui.add_space(6.0);
+                if ui.button("恢复默认").clicked() {
+                    reset = true;
+                }
+            });
+        if reset {
+            self.settings.key_bindings = KeyBindings::default();
+            self.capturing_action = None;
+            self.save_settings();
+        }
+        if !open {
+            self.key_settings_open = false;
+            self.capturing_action = None;
+        }
+    }
+
+    fn show_key_help_overlay(&self, ctx: &egui::Context) {
+        egui::Area::new("key_help_overlay".into())
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::Vec2::new(-16.0, -16.0))
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(&ctx.style()).show(ui, |ui| {
+                    ui.strong("快捷键");
+                    egui::Grid::new("key_help_grid").num_columns(2).show(ui, |ui| {
+                        for action in StudyAction::ALL {
+                            ui.label(action.label());
+                            ui.monospace(self.settings.key_bindings.key(action).symbol_or_name());
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+    }
+
+    // 按当前选择的单词表开始一轮学习，没有可学的单词时返回 false
+    fn start_session(&mut self, mode: StudyMode) -> bool {
+        let today = chrono::Local::now().date_naive();
+        let cards = if self.review_mode {
+            self.current_cards(|w| w.schedule.is_due(today))
+        } else {
+            self.current_cards(|_| true)
+        };
+        let mut words: Vec<Word> = cards.iter().map(|c| c.word.clone()).collect();
+        if words.is_empty() {
+            if self.review_mode && !self.get_current_words().is_empty() {
+                self.show_message("今天没有需要复习的单词");
+            } else {
+                self.show_message("当前单词表为空，无法开始");
+            }
+            return false;
+        }
+        // 根据随机开关准备本轮词序
+        if self.random_order {
+            use rand::seq::SliceRandom;
+            let mut rng = rand::thread_rng();
+            words.shuffle(&mut rng);
+        }
+        self.flash_source = self.current_group.clone().map(|group| (group, self.selected_tables()));
+        self.flash_cards = cards.into_iter().map(|c| (c.word.id, c)).collect();
+        // 同时学习多个单词表时使用第一个单词表的节奏
+        let timing = self.flash_source
+            .as_ref()
+            .and_then(|(group, tables)| self.flash_memory.get_word_table(group, *tables.first()?))
+            .map(|table| table.timing.clone())
+            .unwrap_or_default();
+        self.session = Some(Session::new(mode, words, timing, Box::new(SystemClock)));
+        self.session_summary = None;
+        self.typing_input.clear();
+        self.typing_result = None;
+        if mode == StudyMode::Choice {
+            self.prepare_choice_question();
+        }
+        true
+    }
+
+    // 学完最后一张后回到预览（测验结束时显示成绩）
+    fn finish_session(&mut self) {
+        let Some(session) = self.session.take() else { return };
+        match session.mode() {
+            StudyMode::Typing | StudyMode::Choice => {
+                self.session_summary = Some(SessionSummary {
+                    title: if session.mode() == StudyMode::Typing { "拼写测验结束" } else { "选择题结束" },
+                    correct: session.correct(),
+                    answered: session.answered(),
+                    missed: session.missed().to_vec(),
+                });
+            }
+            StudyMode::Review => self.show_message("本轮复习结束"),
+            StudyMode::Flash => self.show_message("本轮学习结束"),
+        }
+        self.current_page = 0;
+        self.flash_source = None;
+        self.flash_cards.clear();
+    }
+
+    // 拼写题的题面与答案
+    fn typing_prompt(&self, word: &Word) -> (String, String) {
+        match self.typing_direction {
+            TypingDirection::ChineseToEnglish => (word.chinese.clone(), word.english.clone()),
+            TypingDirection::EnglishToChinese => (word.english.clone(), word.chinese.clone()),
+        }
+    }
+
+    fn submit_typing_answer(&mut self) {
+        let Some(word) = self.current_session_word() else { return };
+        let (_, expected) = self.typing_prompt(&word);
+        let (check, closest) = quiz::check_answer(&self.typing_input, &expected, self.typing_tolerance);
+        let grade = match check {
+            AnswerCheck::Exact => Grade::Good,
+            AnswerCheck::Close(_) => Grade::Hard,
+            AnswerCheck::Wrong => Grade::Again,
+        };
+        // 拼写题提交即揭晓答案，拼错的单词进入下一题时放回队尾
+        self.handle_session_event(SessionEvent::Grade(grade));
+        self.typing_result = Some((check, closest));
+    }
+
+    fn next_typing_card(&mut self) {
+        self.typing_input.clear();
+        self.typing_result = None;
+        self.handle_session_event(SessionEvent::Skip);
+    }
+
+    fn current_session_word(&self) -> Option<Word> {
+        self.session.as_ref()?.current_word().cloned()
+    }
+
+    // 当前单词表所在分组中至少有两个不同的释义才能出选择题
+    fn has_enough_choices(&self) -> bool {
+        let Some(group) = &self.current_group else { return false };
+        session::has_enough_choices(&self.flash_memory, group, &self.selected_tables())
+    }
+
+    fn prepare_choice_question(&mut self) {
+        let Some(word) = self.current_session_word() else { return };
+        let Some((group, tables)) = self.flash_source.clone() else { return };
+        let (table_pool, group_pool) = session::choice_pools(&self.flash_memory, &group, &tables);
+        let mut options = quiz::pick_distractors(&word.chinese, &table_pool, &group_pool, 3);
+        options.push(word.chinese.clone());
+        use rand::seq::SliceRandom;
+        options.shuffle(&mut rand::thread_rng());
+        self.choice_answer = options.iter().position(|o| *o == word.chinese).unwrap_or(0);
+        self.choice_options = options;
+        self.choice_selected = None;
+    }
+
+    fn answer_choice(&mut self, index: usize) {
+        if self.choice_selected.is_some() || index >= self.choice_options.len() {
+            return;
+        }
+        let correct = index == self.choice_answer;
+        self.choice_selected = Some(index);
+        // 答错的单词进入下一题时插回几题之后再考一次
+        self.handle_session_event(SessionEvent::Grade(if correct { Grade::Good } else { Grade::Again }));
+    }
+
+    fn next_choice_question(&mut self) {
+        self.handle_session_event(SessionEvent::Skip);
+    }
+
+    // 当前题号与本轮题数
+    fn session_progress(&self) -> String {
+        self.session
+            .as_ref()
+            .map(|s| format!("{} / {}", s.index() + 1, s.words().len()))
+            .unwrap_or_default()
+    }
+
+    // 同时学习多个单词表时，卡片所在的单词表
+    fn card_source(&self, word: &Word) -> Option<String> {
+        let (_, tables) = self.flash_source.as_ref()?;
+        if tables.len() < 2 {
+            return None;
+        }
+        self.flash_cards.get(&word.id).map(|c| format!("来自：{}", c.tables.join("、")))
+    }
+
+    fn choice_card_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(word) = self.current_session_word() else { return };
+        let mut answer = None;
+        let mut next = false;
+        // 数字键 1-4 作答，作答后回车进入下一题
+        if self.choice_selected.is_none() {
+            let keys = [egui::Key::Num1, egui::Key::Num2, egui::Key::Num3, egui::Key::Num4];
+            ui.input(|i| {
+                for (idx, key) in keys.iter().enumerate() {
+                    if i.key_pressed(*key) {
+                        answer = Some(idx);
+                    }
+                }
+            });
+        } else if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            next = true;
+        }
+        ui.vertical_centered(|ui| {
+            ui.add_space(40.0);
+            ui.weak(self.session_progress());
+            if let Some(source) = self.card_source(&word) {
+                ui.weak(source);
+            }
+            ui.add_space(10.0);
+            ui.label(egui::RichText::new(&word.english).size(48.0).strong());
+            ui.add_space(24.0);
+            for (idx, option) in self.choice_options.iter().enumerate() {
+                let mut button = egui::Button::new(egui::RichText::new(format!("{}. {}", idx + 1, option)).size(22.0))
+                    .min_size(egui::Vec2::new(360.0, 44.0));
+                if let Some(selected) = self.choice_selected {
+                    if idx == self.choice_answer {
+                        button = button.fill(egui::Color32::from_rgb(170, 220, 170));
+                    } else if idx == selected {
+                        button = button.fill(egui::Color32::from_rgb(235, 170, 170));
+                    }
+                }
+                if ui.add(button).clicked() && self.choice_selected.is_none() {
+                    answer = Some(idx);
+                }
+                ui.add_space(8.0);
+            }
+            if self.choice_selected.is_some() {
+                ui.add_space(8.0);
+                if ui.button("下一个").clicked() {
+                    next = true;
+                }
+            }
+        });
+        if let Some(idx) = answer {
+            self.answer_choice(idx);
+        } else if next {
+            self.next_choice_question();
+        }
+    }
+
+    // 测验结束后在预览上方显示成绩
+    fn session_summary_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(summary) = &self.session_summary else { return };
+        let mut close = false;
+        egui::Frame::group(ui.style()).show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.heading(summary.title);
+            let rate = (summary.correct * 100).checked_div(summary.answered).unwrap_or(0);
+            ui.label(format!("答对 {} / {}，正确率 {}%", summary.correct, summary.answered, rate));
+            if !summary.missed.is_empty() {
+                ui.label(format!("需要加强：{}", summary.missed.join("、")));
+            }
+            if ui.button("关闭").clicked() {
+                close = true;
+            }
+        });
+        ui.add_space(10.0);
+        if close {
+            self.session_summary = None;
+        }
+    }
+
+
+    fn typing_card_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(word) = self.current_session_word() else { return };
+        let (prompt, expected) = self.typing_prompt(&word);
+        let result = self.typing_result.clone();
+        let mut submit = false;
+        let mut next = false;
+        ui.vertical_centered(|ui| {
+            ui.add_space(40.0);
+            ui.weak(self.session_progress());
+            if let Some(source) = self.card_source(&word) {
+                ui.weak(source);
+            }
+            ui.add_space(10.0);
+            ui.label(egui::RichText::new(&prompt).size(48.0).strong());
+            ui.add_space(24.0);
+            let resp = ui.add_enabled(
+                result.is_none(),
+                egui::TextEdit::singleline(&mut self.typing_input)
+                    .font(egui::TextStyle::Heading)
+                    .desired_width(320.0)
+                    .hint_text("输入答案后按回车"),
+            );
+            let enter = ui.input(|i| i.key_pressed(egui::Key::Enter));
+            ui.add_space(16.0);
+            match result {
+                None => {
+                    resp.request_focus();
+                    if ui.button("提交").clicked() || (enter && resp.lost_focus()) {
+                        submit = true;
+                    }
+                }
+                Some((check, closest)) => {
+                    let (text, color) = match check {
+                        AnswerCheck::Exact => ("正确".to_string(), egui::Color32::DARK_GREEN),
+                        AnswerCheck::Close(d) => (format!("接近（差 {} 个字符）", d), egui::Color32::from_rgb(180, 120, 0)),
+                        AnswerCheck::Wrong => ("错误".to_string(), egui::Color32::DARK_RED),
+                    };
+                    ui.label(egui::RichText::new(text).size(24.0).color(color).strong());
+                    if check != AnswerCheck::Exact {
+                        ui.add_space(8.0);
+                        ui.label(diff_layout(&quiz::diff_chars(&self.typing_input, &closest), 28.0));
+                    }
+                    ui.add_space(8.0);
+                    ui.label(egui::RichText::new(format!("答案：{}", expected)).size(20.0));
+                    ui.add_space(16.0);
+                    if ui.button("下一个").clicked() || enter {
+                        next = true;
+                    }
+                }
+            }
+        });
+        if submit {
+            self.submit_typing_answer();
+        } else if next {
+            self.next_typing_card();
+        }
+    }
+
+    // 选中的单词表：多选的单词表，或单选的单词表；选中文件夹或分组时为其中各层的全部单词表
+    fn selected_tables(&self) -> Vec<Uuid> {
+        let Some(group) = &self.current_group else { return Vec::new() };
+        if !self.checked_word_tables.is_empty() {
+            return self.checked_word_tables
+                .iter()
+                .copied()
+                .filter(|&id| self.flash_memory.get_word_table(group, id).is_some())
+                .collect();
+        }
+        match self.current_word_table {
+            Some(table) => vec![table],
+            None => self.flash_memory.tables_under(group, self.current_folder).iter().map(|t| t.id).collect(),
+        }
+    }
+
+    // 选中的单词表中满足 keep 的单词，重复的单词只保留一个
+    fn current_cards(&self, keep: impl Fn(&Word) -> bool) -> Vec<MergedCard> {
+        let Some(group) = &self.current_group else { return Vec::new() };
+        session::merge_tables(&self.flash_memory, group, &self.selected_tables(), keep)
+    }
+
+    fn get_current_words(&self) -> Vec<Word> {
+        self.current_cards(|_| true).into_iter().map(|c| c.word).collect()
+    }
+}
+
+// 拼写差异视图：写错或多余的字符标红划掉，缺少的字符以绿色下划线补出
+fn diff_layout(diff: &[DiffChar], size: f32) -> egui::text::LayoutJob {
+    let mut job = egui::text::LayoutJob::default();
+    for part in diff {
+        let mut format = egui::TextFormat {
+            font_id: egui::FontId::monospace(size),
+            color: egui::Color32::BLACK,
+            ..Default::default()
+        };
+        let c = match *part {
+            DiffChar::Same(c) => c,
+            DiffChar::Extra(c) => {
+                format.color = egui::Color32::DARK_RED;
+                format.strikethrough = egui::Stroke::new(2.0, egui::Color32::DARK_RED);
+                c
+            }
+            DiffChar::Missing(c) => {
+                format.color = egui::Color32::DARK_GREEN;
+                format.underline = egui::Stroke::new(2.0, egui::Color32::DARK_GREEN);
+                c
+            }
+        };
+        job.append(&c.to_string(), 0.0, format);
+    }
+    job
+}
+
+// 文本编辑器的排版：保存时会被忽略的行标红，重复的单词标黄
+// 导入窗口的预览：第一行选择各列对应的字段，其下为表头与前几行
+fn sheet_preview_ui(ui: &mut egui::Ui, sheet: &mut ImportSheet) {
+    egui::ScrollArea::both().max_height(260.0).show(ui, |ui| {
+        egui::Grid::new("import_preview").striped(true).show(ui, |ui| {
+            for (i, mapped) in sheet.mapping.iter_mut().enumerate() {
+                let text = mapped.map_or("忽略", |c| c.label());
+                egui::ComboBox::from_id_salt(("import_column", i)).selected_text(text).show_ui(ui, |ui| {
+                    ui.selectable_value(mapped, None, "忽略");
+                    for c in Column::ALL {
+                        ui.selectable_value(mapped, Some(c), c.label());
+                    }
+                });
+            }
+            ui.end_row();
+            if let Some(header) = sheet.header() {
+                for cell in header {
+                    ui.strong(cell);
+                }
+                ui.end_row();
+            }
+            for row in sheet.data_rows().iter().take(10) {
+                for cell in row {
+                    ui.label(cell.replace('\n', " "));
+                }
+                ui.end_row();
+            }
+        });
+    });
+}
+
+fn highlight_problem_lines(ui: &egui::Ui, text: &str, separator: Separator) -> egui::text::LayoutJob {
+    let report = parse_words_with_report(text, "", separator);
+    let mut job = egui::text::LayoutJob::default();
+    let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+    for (idx, line) in text.split_inclusive('\n').enumerate() {
+        let issue = report.diagnostics.iter().find(|d| d.line == idx + 1).map(|d| &d.issue);
+        let mut format = egui::TextFormat::simple(font_id.clone(), ui.visuals().text_color());
+        match issue {
+            Some(issue) if issue.is_rejected() => {
+                format.color = egui::Color32::DARK_RED;
+                format.background = egui::Color32::from_rgb(255, 220, 220);
+            }
+            Some(_) => format.background = egui::Color32::from_rgb(255, 240, 190),
+            None => {}
+        }
+        job.append(line, 0.0, format);
+    }
+    job
+}
+
+// 尝试加载系统中文字体（simhei/simkai），用于支持中文显示
+fn configure_chinese_fonts(ctx: &egui::Context) {
+    let mut fonts = egui::FontDefinitions::default();
+    for path in [
+        "C\\Windows\\Fonts\\simhei.ttf",
+        "C\\Windows\\Fonts\\simkai.ttf",
+        "C:/Windows/Fonts/simhei.ttf",
+        "C:/Windows/Fonts/simkai.ttf",
+    ] {
+        if let Ok(bytes) = std::fs::read(path) {
+            fonts.font_data.insert("cjk".to_owned(), egui::FontData::from_owned(bytes));
+            fonts.families.entry(egui::FontFamily::Proportional).or_default().insert(0, "cjk".to_owned());
+            fonts.families.entry(egui::FontFamily::Monospace).or_default().insert(0, "cjk".to_owned());
+            break;
+        }
+    }
+    ctx.set_fonts(fonts);
+}
+
+// 音标与词性，如 “/ˈæpl/ n.”
+fn phonetic_line(word: &Word) -> String {
+    let phonetic = if word.phonetic.is_empty() { String::new() } else { format!("/{}/", word.phonetic) };
+    [phonetic.as_str(), word.part_of_speech.as_str()]
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// 例句（含翻译）与笔记，每项一行
+fn example_and_note_lines(word: &Word) -> Vec<String> {
+    let mut lines = Vec::new();
+    for example in &word.examples {
+        lines.push(example.sentence.clone());
+        if !example.translation.is_empty() {
+            lines.push(example.translation.clone());
+        }
+    }
+    lines.extend(word.notes.lines().map(|note| format!("笔记：{}", note)));
+    lines
+}
+
+// 卡片背面在主要释义下方显示的详细信息
+fn card_detail_lines(word: &Word) -> Vec<String> {
+    let mut lines = Vec::new();
+    let phonetic = phonetic_line(word);
+    if !phonetic.is_empty() {
+        lines.push(phonetic);
+    }
+    if !word.meanings.is_empty() {
+        lines.push(word.meanings.join("；"));
+    }
+    lines.extend(example_and_note_lines(word));
+    if !word.tags.is_empty() {
+        lines.push(format!("标签：{}", word.tags.join(" ")));
+    }
+    lines
+}
+
+// 目录中正在拖动的分组
+struct DraggedGroup(String);
+// 目录中正在拖动的文件夹 (分组, 文件夹)
+struct DraggedFolder(String, Uuid);
+// 目录中正在拖动的单词表 (分组, 单词表)
+struct DraggedTable(String, Uuid);
+
+// 在放下位置画一条线：从下方拖来时画在目标上沿，从上方拖来时画在下沿
+fn paint_drop_marker(ui: &egui::Ui, rect: egui::Rect, from: Option<usize>, to: Option<usize>) {
+    let (Some(from), Some(to)) = (from, to) else { return };
+    if from == to {
+        return;
+    }
+    let y = if from > to { rect.top() } else { rect.bottom() };
+    ui.painter().hline(rect.x_range(), y, egui::Stroke::new(2.0, egui::Color32::DARK_BLUE));
+}
+
+// 读取复习记录；早期没有单词标识的记录按名称补上标识并写回，之后改名也不会丢失历史
+fn load_review_log(storage: &mut dyn Storage, memory: &FlashMemory) -> ReviewLog {
+    let mut review_log = ReviewLog::new(storage.load_reviews().unwrap_or_default());
+    if review_log.link_word_ids(memory) > 0 {
+        let _ = storage.replace_reviews(review_log.records());
+    }
+    review_log
+}
+
+// 命令行参数 --library <路径>：本次打开的单词库（文件，或包含 words.json / words.db 的目录）
+fn library_arg() -> Option<PathBuf> {
+    let mut args = std::env::args_os().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--library" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.to_str().and_then(|a| a.strip_prefix("--library=")) {
+            return Some(PathBuf::from(path));
         }
     }
-    if loaded { ctx.set_fonts(fonts); } else { ctx.set_fonts(fonts); }
+    None
 }
 
 fn main() -> Result<(), eframe::Error> {
@@ -1015,6 +3202,6 @@ fn main() -> Result<(), eframe::Error> {
     eframe::run_native(
         "单词闪记工具",
         options,
-        Box::new(|cc| { configure_chinese_fonts(&cc.egui_ctx); Ok(Box::new(FlashMemoryApp::default())) }),
+        Box::new(|cc| { configure_chinese_fonts(&cc.egui_ctx); Ok(Box::new(FlashMemoryApp::new(library_arg()))) }),
     )
 }
\ No newline at end of file
@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::{carry_over_identity, Word};
+use crate::quiz;
+
+// 保存编辑的单词时如何与单词表原有的单词合并
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SaveMode {
+    #[default]
+    Replace, // 替换：单词表只保留编辑后的单词，能对应上的原单词保留标识与复习状态
+    Append,  // 追加：原有单词不变，英文与释义都相同的单词不重复添加
+    Merge,   // 按英文合并：英文相同的单词更新释义与详细信息，其余追加，原有单词都保留
+}
+
+impl SaveMode {
+    pub const ALL: [SaveMode; 3] = [SaveMode::Replace, SaveMode::Append, SaveMode::Merge];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SaveMode::Replace => "替换",
+            SaveMode::Append => "追加",
+            SaveMode::Merge => "按英文合并",
+        }
+    }
+}
+
+// 保存前后单词表的差异，按单词标识比较
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WordDiff {
+    pub added: Vec<Word>,
+    pub changed: Vec<(Word, Word)>, // (原单词, 保存后的单词)
+    pub removed: Vec<Word>,
+    pub unchanged: usize,
+}
+
+impl WordDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+// 英文、释义与详细信息都相同（不比较标识、分组与复习状态）
+fn same_content(a: &Word, b: &Word) -> bool {
+    a.english == b.english
+        && a.chinese == b.chinese
+        && a.phonetic == b.phonetic
+        && a.part_of_speech == b.part_of_speech
+        && a.meanings == b.meanings
+        && a.examples == b.examples
+        && a.notes == b.notes
+        && a.tags == b.tags
+}
+
+fn extend_unique<T: PartialEq>(list: &mut Vec<T>, items: Vec<T>) {
+    for item in items {
+        if !list.contains(&item) {
+            list.push(item);
+        }
+    }
+}
+
+// 用新单词更新原单词：释义替换，填写了的详细信息覆盖，其他释义、例句与标签合并
+fn update_word(word: &mut Word, new: Word) {
+    word.english = new.english;
+    word.chinese = new.chinese;
+    if !new.phonetic.is_empty() {
+        word.phonetic = new.phonetic;
+    }
+    if !new.part_of_speech.is_empty() {
+        word.part_of_speech = new.part_of_speech;
+    }
+    if !new.notes.is_empty() {
+        word.notes = new.notes;
+    }
+    extend_unique(&mut word.meanings, new.meanings);
+    extend_unique(&mut word.examples, new.examples);
+    extend_unique(&mut word.tags, new.tags);
+}
+
+// 按保存方式得到保存后的单词表内容
+pub fn merge_words(old: &[Word], mut new: Vec<Word>, mode: SaveMode) -> Vec<Word> {
+    match mode {
+        SaveMode::Replace => {
+            carry_over_identity(old, &mut new);
+            new
+        }
+        SaveMode::Append => {
+            let mut words = old.to_vec();
+            for word in new {
+                let key = (quiz::normalize(&word.english), quiz::normalize(&word.chinese));
+                if !words.iter().any(|w| (quiz::normalize(&w.english), quiz::normalize(&w.chinese)) == key) {
+                    words.push(word);
+                }
+            }
+            words
+        }
+        SaveMode::Merge => {
+            let mut words = old.to_vec();
+            for word in new {
+                let key = quiz::normalize(&word.english);
+                match words.iter_mut().find(|w| quiz::normalize(&w.english) == key) {
+                    Some(existing) => update_word(existing, word),
+                    None => words.push(word),
+                }
+            }
+            words
+        }
+    }
+}
+
+pub fn diff_words(old: &[Word], new: &[Word]) -> WordDiff {
+    let mut diff = WordDiff::default();
+    for word in new {
+        match old.iter().find(|o| o.id == word.id) {
+            Some(o) if same_content(o, word) => diff.unchanged += 1,
+            Some(o) => diff.changed.push((o.clone(), word.clone())),
+            None => diff.added.push(word.clone()),
+        }
+    }
+    diff.removed = old.iter().filter(|o| !new.iter().any(|w| w.id == o.id)).cloned().collect();
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Example;
+
+    fn old_words() -> Vec<Word> {
+        let mut apple = Word::new("apple", "苹果", "英语");
+        apple.schedule.repetitions = 3;
+        apple.examples = vec![Example { sentence: "An apple a day.".to_string(), translation: String::new() }];
+        vec![apple, Word::new("pear", "梨", "英语")]
+    }
+
+    fn pairs(words: &[Word]) -> Vec<(&str, &str)> {
+        words.iter().map(|w| (w.english.as_str(), w.chinese.as_str())).collect()
+    }
+
+    #[test]
+    fn replace_keeps_only_edited_words() {
+        let old = old_words();
+        let new = vec![Word::new("apple", "苹果", "英语"), Word::new("grape", "葡萄", "英语")];
+        let words = merge_words(&old, new, SaveMode::Replace);
+        assert_eq!(pairs(&words), vec![("apple", "苹果"), ("grape", "葡萄")]);
+        assert_eq!(words[0].id, old[0].id);
+        assert_eq!(words[0].schedule.repetitions, 3);
+
+        let diff = diff_words(&old, &words);
+        assert_eq!(pairs(&diff.added), vec![("grape", "葡萄")]);
+        assert_eq!(pairs(&diff.removed), vec![("pear", "梨")]);
+        // 编辑后的单词没有例句，算作修改
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.unchanged, 0);
+    }
+
+    #[test]
+    fn replace_keeps_identity_only_for_spelling_fixes() {
+        let old = vec![Word::new("aple", "苹果", "英语"), Word::new("big", "大", "英语")];
+        let new = vec![Word::new("apple", "苹果", "英语"), Word::new("large", "大", "英语")];
+        let words = merge_words(&old, new, SaveMode::Replace);
+        assert_eq!(words[0].id, old[0].id);
+        // 释义相同的近义词替换了删去的单词，是新词
+        assert!(old.iter().all(|o| o.id != words[1].id));
+
+        let diff = diff_words(&old, &words);
+        assert_eq!(pairs(&diff.added), vec![("large", "大")]);
+        assert_eq!(pairs(&diff.removed), vec![("big", "大")]);
+    }
+
+    #[test]
+    fn append_skips_words_already_in_the_table() {
+        let old = old_words();
+        let new = vec![Word::new("Apple", " 苹果", "英语"), Word::new("apple", "苹果公司", "英语")];
+        let words = merge_words(&old, new, SaveMode::Append);
+        assert_eq!(pairs(&words), vec![("apple", "苹果"), ("pear", "梨"), ("apple", "苹果公司")]);
+        assert_eq!(words[..2], old[..]);
+
+        let diff = diff_words(&old, &words);
+        assert_eq!(pairs(&diff.added), vec![("apple", "苹果公司")]);
+        assert!(diff.changed.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.unchanged, 2);
+    }
+
+    #[test]
+    fn merge_updates_by_headword_and_keeps_history() {
+        let old = old_words();
+        let mut apple = Word::new("Apple", "苹果；苹果公司", "英语");
+        apple.phonetic = "ˈæpl".to_string();
+        apple.examples = vec![
+            Example { sentence: "An apple a day.".to_string(), translation: String::new() },
+            Example { sentence: "I ate an apple.".to_string(), translation: "我吃了一个苹果。".to_string() },
+        ];
+        let words = merge_words(&old, vec![apple, Word::new("grape", "葡萄", "英语")], SaveMode::Merge);
+        assert_eq!(pairs(&words), vec![("Apple", "苹果；苹果公司"), ("pear", "梨"), ("grape", "葡萄")]);
+        assert_eq!((words[0].id, words[0].schedule.repetitions), (old[0].id, 3));
+        assert_eq!(words[0].phonetic, "ˈæpl");
+        assert_eq!(words[0].examples.len(), 2);
+
+        let diff = diff_words(&old, &words);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].0.chinese, "苹果");
+        assert_eq!(pairs(&diff.added), vec![("grape", "葡萄")]);
+        assert!(diff.removed.is_empty());
+        assert!(diff_words(&old, &old).is_empty());
+    }
+}
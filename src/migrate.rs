@@ -0,0 +1,294 @@
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::parse::Separator;
+use crate::scheduler::Schedule;
+use crate::timing::FlashTiming;
+
+// 数据文件格式版本；没有 version 字段的旧文件视为版本 1
+pub const CURRENT_VERSION: u64 = 7;
+
+#[derive(Debug, PartialEq)]
+pub enum MigrateError {
+    TooNew(u64),            // 文件来自更新版本的应用
+    Invalid(&'static str),  // 文件结构不符合对应版本
+}
+
+impl std::fmt::Display for MigrateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrateError::TooNew(version) => write!(
+                f,
+                "数据文件来自更新版本的应用（格式版本 {}，当前最高支持 {}），请升级应用后再打开",
+                version, CURRENT_VERSION
+            ),
+            MigrateError::Invalid(reason) => write!(f, "数据文件格式不正确：{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for MigrateError {}
+
+// 一个升级步骤：就地把文档升级到下一个版本
+type Step = fn(&mut Map<String, Value>) -> Result<(), MigrateError>;
+
+// 第 i 项把版本 i+1 的文档升级到版本 i+2
+const STEPS: [Step; (CURRENT_VERSION - 1) as usize] = [v1_to_v2, v2_to_v3, v3_to_v4, v4_to_v5, v5_to_v6, v6_to_v7];
+
+// 读取文档的格式版本
+pub fn version_of(doc: &Value) -> Result<u64, MigrateError> {
+    match doc.get("version") {
+        None => Ok(1),
+        Some(v) => v.as_u64().filter(|&v| v >= 1).ok_or(MigrateError::Invalid("version 不是正整数")),
+    }
+}
+
+// 把任意旧版本的文档逐步升级到当前版本
+pub fn migrate(mut doc: Value) -> Result<Value, MigrateError> {
+    let mut version = version_of(&doc)?;
+    if version > CURRENT_VERSION {
+        return Err(MigrateError::TooNew(version));
+    }
+    let obj = doc.as_object_mut().ok_or(MigrateError::Invalid("顶层不是对象"))?;
+    while version < CURRENT_VERSION {
+        STEPS[(version - 1) as usize](obj)?;
+        version += 1;
+        obj.insert("version".to_string(), Value::from(version));
+    }
+    Ok(doc)
+}
+
+// 版本 1 → 2：补齐后来加入的复习状态、闪记节奏与分组复习设置，并开始记录版本号
+fn v1_to_v2(doc: &mut Map<String, Value>) -> Result<(), MigrateError> {
+    let default_schedule = serde_json::to_value(Schedule::default()).expect("默认复习状态可以序列化");
+    let default_timing = serde_json::to_value(FlashTiming::default()).expect("默认闪记节奏可以序列化");
+    let groups = doc
+        .get_mut("groups")
+        .and_then(Value::as_object_mut)
+        .ok_or(MigrateError::Invalid("缺少 groups"))?;
+    for tables in groups.values_mut() {
+        let tables = tables.as_array_mut().ok_or(MigrateError::Invalid("分组内容不是单词表列表"))?;
+        for table in tables {
+            let table = table.as_object_mut().ok_or(MigrateError::Invalid("单词表不是对象"))?;
+            table.entry("timing").or_insert_with(|| default_timing.clone());
+            let words = table
+                .get_mut("words")
+                .and_then(Value::as_array_mut)
+                .ok_or(MigrateError::Invalid("单词表缺少 words"))?;
+            for word in words {
+                let word = word.as_object_mut().ok_or(MigrateError::Invalid("单词不是对象"))?;
+                word.entry("schedule").or_insert_with(|| default_schedule.clone());
+            }
+        }
+    }
+    doc.entry("scheduler_settings").or_insert_with(|| Value::Object(Map::new()));
+    Ok(())
+}
+
+// 版本 2 → 3：单词加入音标、词性、例句等可选字段，旧文件无需改动；
+// 提升版本号是为了让旧版应用拒绝打开新文件，而不是在保存时丢掉这些字段
+fn v2_to_v3(_doc: &mut Map<String, Value>) -> Result<(), MigrateError> {
+    Ok(())
+}
+
+// 版本 3 → 4：为每个分组、单词表与单词分配标识（分组的标识记录在 group_ids 中）
+fn v3_to_v4(doc: &mut Map<String, Value>) -> Result<(), MigrateError> {
+    let new_id = || Value::String(Uuid::new_v4().to_string());
+    let groups = doc
+        .get_mut("groups")
+        .and_then(Value::as_object_mut)
+        .ok_or(MigrateError::Invalid("缺少 groups"))?;
+    let mut group_ids = Map::new();
+    for (name, tables) in groups.iter_mut() {
+        group_ids.insert(name.clone(), new_id());
+        let tables = tables.as_array_mut().ok_or(MigrateError::Invalid("分组内容不是单词表列表"))?;
+        for table in tables {
+            let table = table.as_object_mut().ok_or(MigrateError::Invalid("单词表不是对象"))?;
+            table.entry("id").or_insert_with(new_id);
+            let words = table
+                .get_mut("words")
+                .and_then(Value::as_array_mut)
+                .ok_or(MigrateError::Invalid("单词表缺少 words"))?;
+            for word in words {
+                let word = word.as_object_mut().ok_or(MigrateError::Invalid("单词不是对象"))?;
+                word.entry("id").or_insert_with(new_id);
+            }
+        }
+    }
+    doc.insert("group_ids".to_string(), Value::Object(group_ids));
+    Ok(())
+}
+
+// 版本 4 → 5：分组改为按 group_order 中的手动顺序排列，初始顺序与此前目录中按名称排序的顺序相同；
+// 早期的分组与单词表没有创建时间
+fn v4_to_v5(doc: &mut Map<String, Value>) -> Result<(), MigrateError> {
+    let groups = doc
+        .get("groups")
+        .and_then(Value::as_object)
+        .ok_or(MigrateError::Invalid("缺少 groups"))?;
+    let mut names: Vec<&String> = groups.keys().collect();
+    names.sort();
+    let order = names.into_iter().map(|name| Value::String(name.clone())).collect();
+    doc.insert("group_order".to_string(), Value::Array(order));
+    Ok(())
+}
+
+// 版本 5 → 6：分组中可以建立多层文件夹（folders），原有单词表都直接位于分组下，无需改动；
+// 提升版本号是为了让旧版应用拒绝打开新文件，而不是丢掉文件夹、把单词表都摊平到分组下
+fn v5_to_v6(_doc: &mut Map<String, Value>) -> Result<(), MigrateError> {
+    Ok(())
+}
+
+// 版本 6 → 7：单词表记住文本编辑器中的分隔符（separator），原有单词表使用默认的智能识别；
+// 提升版本号是为了让旧版应用拒绝打开新文件，而不是在保存时丢掉各单词表的分隔符
+fn v6_to_v7(doc: &mut Map<String, Value>) -> Result<(), MigrateError> {
+    let default_separator = serde_json::to_value(Separator::default()).expect("默认分隔符可以序列化");
+    let groups = doc
+        .get_mut("groups")
+        .and_then(Value::as_object_mut)
+        .ok_or(MigrateError::Invalid("缺少 groups"))?;
+    for tables in groups.values_mut() {
+        let tables = tables.as_array_mut().ok_or(MigrateError::Invalid("分组内容不是单词表列表"))?;
+        for table in tables {
+            let table = table.as_object_mut().ok_or(MigrateError::Invalid("单词表不是对象"))?;
+            table.entry("separator").or_insert_with(|| default_separator.clone());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::FlashMemory;
+
+    fn fixture(name: &str) -> Value {
+        let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn v1_baseline_layout_upgrades_to_current() {
+        let doc = migrate(fixture("v1_baseline.json")).unwrap();
+        assert_eq!(doc["version"], CURRENT_VERSION);
+        let word = &doc["groups"]["英语"][0]["words"][0];
+        assert_eq!(word["schedule"], serde_json::to_value(Schedule::default()).unwrap());
+        assert_eq!(doc["groups"]["英语"][0]["timing"], serde_json::to_value(FlashTiming::default()).unwrap());
+        assert_eq!(doc["scheduler_settings"], Value::Object(Map::new()));
+
+        let memory: FlashMemory = serde_json::from_value(doc).unwrap();
+        assert_eq!(memory.find_word_table("英语", None, "第一课").unwrap().words.len(), 2);
+        assert!(memory.get_word_tables_in_group("空分组").unwrap().is_empty());
+    }
+
+    #[test]
+    fn v1_with_newer_fields_keeps_them() {
+        let doc = migrate(fixture("v1_scheduled.json")).unwrap();
+        let word = &doc["groups"]["英语"][0]["words"][0];
+        assert_eq!(word["schedule"]["interval"], 6);
+        assert_eq!(word["schedule"]["due"], "2025-11-01");
+        assert_eq!(doc["groups"]["英语"][0]["timing"]["front"], 2.0);
+        assert_eq!(doc["scheduler_settings"]["英语"]["algorithm"], "Fsrs");
+    }
+
+    #[test]
+    fn v2_upgrades_without_changes_to_words() {
+        let doc = migrate(fixture("v2.json")).unwrap();
+        let memory: FlashMemory = serde_json::from_value(doc).unwrap();
+        assert!(!memory.find_word_table("英语", None, "第一课").unwrap().words[0].has_details());
+    }
+
+    #[test]
+    fn v3_gets_unique_ids() {
+        let doc = migrate(fixture("v3.json")).unwrap();
+        let memory: FlashMemory = serde_json::from_value(doc).unwrap();
+        let table = memory.find_word_table("英语", None, "第一课").unwrap();
+        let mut ids = vec![memory.group_id("英语").unwrap(), table.id];
+        ids.extend(table.words.iter().map(|w| w.id));
+        let count = ids.len();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), count);
+        // 其余字段保持不变
+        assert_eq!(table.words[0].phonetic, "ˈæpl");
+    }
+
+    #[test]
+    fn v4_groups_keep_alphabetical_order() {
+        let doc = serde_json::json!({
+            "version": 4,
+            "groups": { "Unit 2": [], "Unit 10": [], "Unit 1": [] },
+            "group_ids": {
+                "Unit 2": "0b9d7c1e-2f4a-4b6c-8d0e-3f5a7b9c1d2e",
+                "Unit 10": "a1b2c3d4-e5f6-4a7b-8c9d-0e1f2a3b4c5d",
+                "Unit 1": "b2c3d4e5-f6a7-4b8c-9d0e-1f2a3b4c5d6e"
+            },
+            "scheduler_settings": {}
+        });
+        let memory: FlashMemory = serde_json::from_value(migrate(doc).unwrap()).unwrap();
+        assert_eq!(memory.get_groups(), vec!["Unit 1", "Unit 10", "Unit 2"]);
+        assert!(memory.group_created.is_empty());
+    }
+
+    #[test]
+    fn v5_keeps_tables_directly_in_groups() {
+        let memory: FlashMemory = serde_json::from_value(migrate(fixture("v5.json")).unwrap()).unwrap();
+        assert!(memory.folders.is_empty());
+        assert_eq!(memory.child_tables("日语", None).len(), 2);
+    }
+
+    #[test]
+    fn v6_tables_use_smart_separator() {
+        let doc = migrate(fixture("v6.json")).unwrap();
+        assert_eq!(doc["version"], 7);
+        assert_eq!(doc["groups"]["英语"][0]["separator"], "Smart");
+        let memory: FlashMemory = serde_json::from_value(doc).unwrap();
+        let tables = memory.get_word_tables_in_group("日语").unwrap();
+        assert!(tables.iter().all(|t| t.separator == Separator::Smart));
+        assert_eq!(memory.get_folders_in_group("日语").len(), 2);
+    }
+
+    #[test]
+    fn current_version_is_unchanged() {
+        let doc = fixture("v7.json");
+        assert_eq!(migrate(doc.clone()).unwrap(), doc);
+        let memory: FlashMemory = serde_json::from_value(doc).unwrap();
+        assert_eq!(memory.group_id("英语").unwrap().to_string(), "6f1c2a4e-8d3b-4c5a-9e7f-1a2b3c4d5e6f");
+        let apple = &memory.find_word_table("英语", None, "第一课").unwrap().words[0];
+        assert_eq!(apple.phonetic, "ˈæpl");
+        assert_eq!(apple.examples[0].translation, "我吃了一个苹果。");
+        assert_eq!(apple.tags, vec!["水果", "初级"]);
+        // 手动顺序与创建时间原样读出
+        assert_eq!(memory.get_groups(), vec!["英语", "日语"]);
+        assert!(memory.group_created.contains_key("日语") && !memory.group_created.contains_key("英语"));
+        let tables = memory.get_word_tables_in_group("日语").unwrap();
+        assert_eq!(tables[0].name, "第二课");
+        assert!(tables[0].created.is_some());
+        // 文件夹层级原样读出
+        let unit = memory.get_word_table("日语", tables[1].id).unwrap().folder.unwrap();
+        let n5 = memory.get_folder("日语", unit).unwrap().parent.unwrap();
+        assert_eq!(memory.get_folder("日语", n5).unwrap().name, "N5");
+        assert_eq!(memory.tables_under("日语", Some(n5))[0].name, "第一课");
+        // 各单词表的分隔符原样读出
+        assert_eq!(memory.find_word_table("英语", None, "第一课").unwrap().separator, Separator::Tab);
+        assert_eq!(tables[1].separator, Separator::Pipe);
+    }
+
+    #[test]
+    fn newer_version_is_rejected() {
+        let err = migrate(fixture("future.json")).unwrap_err();
+        assert_eq!(err, MigrateError::TooNew(99));
+        assert!(err.to_string().contains("请升级应用"));
+    }
+
+    #[test]
+    fn malformed_documents_are_rejected() {
+        assert_eq!(migrate(serde_json::json!([])), Err(MigrateError::Invalid("顶层不是对象")));
+        assert_eq!(migrate(serde_json::json!({ "version": "2" })), Err(MigrateError::Invalid("version 不是正整数")));
+        assert_eq!(migrate(serde_json::json!({})), Err(MigrateError::Invalid("缺少 groups")));
+        assert_eq!(
+            migrate(serde_json::json!({ "groups": { "英语": [ { "name": "第一课" } ] } })),
+            Err(MigrateError::Invalid("单词表缺少 words"))
+        );
+    }
+}
@@ -0,0 +1,860 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::merge::{merge_words, SaveMode};
+use crate::parse::Separator;
+use crate::quiz;
+use crate::review_log::{ReviewLog, WordKey};
+use crate::scheduler::{Grade, Schedule, SchedulerSettings};
+use crate::timing::FlashTiming;
+
+// add_word 添加单词时使用的单词表
+pub const UNSORTED_TABLE: &str = "未分类";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Word {
+    pub id: Uuid, // 不随编辑改变的标识，复习记录通过它指向单词
+    pub english: String,
+    pub chinese: String, // 主要释义
+    pub group: String,
+    #[serde(default)]
+    pub schedule: Schedule, // SM-2 复习状态
+    // 以下为可选的详细信息，旧文件中没有时为空，为空时也不写入文件
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub phonetic: String, // 国际音标，如 ˈæpl
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub part_of_speech: String, // 词性，如 n.、v.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub meanings: Vec<String>, // 主要释义之外的其他释义
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<Example>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+// 例句及其翻译
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Example {
+    pub sentence: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub translation: String,
+}
+
+impl Word {
+    pub fn new(english: &str, chinese: &str, group: &str) -> Self {
+        Word {
+            id: Uuid::new_v4(),
+            english: english.to_string(),
+            chinese: chinese.to_string(),
+            group: group.to_string(),
+            schedule: Schedule::default(),
+            phonetic: String::new(),
+            part_of_speech: String::new(),
+            meanings: Vec::new(),
+            examples: Vec::new(),
+            notes: String::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    // 是否填写了音标、词性等详细信息
+    pub fn has_details(&self) -> bool {
+        !self.phonetic.is_empty()
+            || !self.part_of_speech.is_empty()
+            || !self.meanings.is_empty()
+            || !self.examples.is_empty()
+            || !self.notes.is_empty()
+            || !self.tags.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WordTable {
+    pub id: Uuid,
+    pub name: String,
+    pub words: Vec<Word>,
+    #[serde(default)]
+    pub timing: FlashTiming, // 闪记节奏
+    #[serde(default)]
+    pub separator: Separator, // 文本编辑器中英文与释义的分隔符
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<DateTime<Local>>, // 创建时间，早期创建的单词表没有记录
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder: Option<Uuid>, // 所在文件夹，None 表示直接位于分组下
+}
+
+// 分组中的文件夹，可以多层嵌套
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Folder {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Uuid>, // 上级文件夹，None 表示直接位于分组下
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<DateTime<Local>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FlashMemory {
+    pub groups: HashMap<String, Vec<WordTable>>,
+    pub group_ids: HashMap<String, Uuid>, // 各分组的标识，重命名时随之移动
+    pub group_order: Vec<String>,         // 分组的手动顺序
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub group_created: HashMap<String, DateTime<Local>>, // 各分组的创建时间，早期创建的分组没有记录
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub folders: HashMap<String, Vec<Folder>>, // 各分组中的文件夹，同级文件夹按手动顺序
+    #[serde(default)]
+    pub scheduler_settings: HashMap<String, SchedulerSettings>, // 各分组的复习算法设置
+}
+
+impl WordTable {
+    pub fn new(name: &str) -> Self {
+        WordTable {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            words: Vec::new(),
+            timing: FlashTiming::default(),
+            separator: Separator::default(),
+            created: Some(Local::now()),
+            folder: None,
+        }
+    }
+
+    // 今天需要复习的单词数（新词与到期词）
+    pub fn due_count(&self, today: NaiveDate) -> usize {
+        self.words.iter().filter(|w| w.schedule.is_due(today)).count()
+    }
+}
+
+impl Folder {
+    pub fn new(name: &str, parent: Option<Uuid>) -> Self {
+        Folder { id: Uuid::new_v4(), name: name.to_string(), parent, created: Some(Local::now()) }
+    }
+}
+
+// 把 item 插到满足 sibling 的元素中的第 to 位（超出时放在最后一个之后，没有同级元素时放回原处 from）
+fn insert_among<T>(items: &mut Vec<T>, item: T, from: usize, to: usize, sibling: impl Fn(&T) -> bool) {
+    let positions: Vec<usize> = items.iter().enumerate().filter(|(_, i)| sibling(i)).map(|(p, _)| p).collect();
+    let at = match positions.get(to) {
+        Some(&p) => p,
+        None => positions.last().map_or(from, |&p| p + 1),
+    };
+    items.insert(at, item);
+}
+
+// 只改了拼写时英文最多相差的字符数
+const MAX_SPELLING_FIX: usize = 2;
+
+// 编辑后的单词沿用原单词的标识与复习状态：先按英文与释义都相同的配对，再按英文相同配对，
+// 最后按释义相同且英文只差几个字符（修正拼写）配对；每个原单词只用一次，配不上的视为新词。
+// 只有释义相同的近义词（如 big 换成 large）是另一个单词，不沿用标识
+pub(crate) fn carry_over_identity(old: &[Word], new: &mut [Word]) {
+    let mut used = vec![false; old.len()];
+    let mut matched = vec![false; new.len()];
+    let passes: [fn(&Word, &Word) -> bool; 3] = [
+        |a, b| a.english == b.english && a.chinese == b.chinese,
+        |a, b| a.english == b.english,
+        |a, b| {
+            a.chinese == b.chinese
+                && quiz::edit_distance(&quiz::normalize(&a.english), &quiz::normalize(&b.english)) <= MAX_SPELLING_FIX
+        },
+    ];
+    for same in passes {
+        for (word, done) in new.iter_mut().zip(matched.iter_mut()).filter(|(_, done)| !**done) {
+            let found = old.iter().zip(used.iter_mut()).find(|(o, u)| !**u && same(o, word));
+            if let Some((o, u)) = found {
+                word.id = o.id;
+                word.schedule = o.schedule.clone();
+                *u = true;
+                *done = true;
+            }
+        }
+    }
+}
+
+impl FlashMemory {
+    pub fn new() -> Self {
+        FlashMemory {
+            groups: HashMap::new(),
+            group_ids: HashMap::new(),
+            group_order: Vec::new(),
+            group_created: HashMap::new(),
+            folders: HashMap::new(),
+            scheduler_settings: HashMap::new(),
+        }
+    }
+
+    pub fn group_id(&self, group: &str) -> Option<Uuid> {
+        self.group_ids.get(group).copied()
+    }
+
+    // 把单词加到所属分组中直接位于分组下的“未分类”单词表末尾，分组或单词表不存在时新建；返回该单词表的标识
+    pub fn add_word(&mut self, word: Word) -> Uuid {
+        let group = word.group.clone();
+        let table = match self.find_word_table(&group, None, UNSORTED_TABLE) {
+            Some(table) => table.id,
+            None => self.create_word_table(&group, None, UNSORTED_TABLE).unwrap_or_default(),
+        };
+        if let Some(target) = self.groups.get_mut(&group).and_then(|tables| tables.iter_mut().find(|t| t.id == table)) {
+            target.words.push(word);
+        }
+        table
+    }
+
+    // 新分组排在最后
+    pub fn create_group_if_absent(&mut self, group: &str) {
+        if !self.groups.contains_key(group) {
+            self.groups.insert(group.to_string(), Vec::new());
+            self.group_order.push(group.to_string());
+            self.group_created.insert(group.to_string(), Local::now());
+        }
+        self.group_ids.entry(group.to_string()).or_insert_with(Uuid::new_v4);
+    }
+
+    // 以 base 为名创建分组，重名时依次加上 2、3…，返回实际使用的名称
+    pub fn create_group(&mut self, base: &str) -> String {
+        let mut name = base.to_string();
+        let mut idx = 1;
+        while self.groups.contains_key(&name) {
+            idx += 1;
+            name = format!("{}{}", base, idx);
+        }
+        self.create_group_if_absent(&name);
+        name
+    }
+
+    // 在分组（folder 为 None）或其中的文件夹里创建单词表，同一位置重名时依次加上 2、3…，返回新单词表的标识
+    pub fn create_word_table(&mut self, group: &str, folder: Option<Uuid>, table_name: &str) -> Result<Uuid, &'static str> {
+        if folder.is_some_and(|f| self.get_folder(group, f).is_none()) {
+            return Err("文件夹不存在");
+        }
+        self.create_group_if_absent(group);
+        let tables = self.groups.entry(group.to_string()).or_default();
+        let mut name = table_name.to_string();
+        let mut idx = 1;
+        while tables.iter().any(|t| t.folder == folder && t.name == name) {
+            idx += 1;
+            name = format!("{}{}", table_name, idx);
+        }
+        let mut table = WordTable::new(&name);
+        table.folder = folder;
+        let id = table.id;
+        tables.push(table);
+        Ok(id)
+    }
+
+    // 在分组或文件夹中创建子文件夹，同一位置重名时依次加上 2、3…，返回新文件夹的标识
+    pub fn create_folder(&mut self, group: &str, parent: Option<Uuid>, folder_name: &str) -> Result<Uuid, &'static str> {
+        if !self.groups.contains_key(group) {
+            return Err("分组不存在");
+        }
+        if parent.is_some_and(|p| self.get_folder(group, p).is_none()) {
+            return Err("文件夹不存在");
+        }
+        let folders = self.folders.entry(group.to_string()).or_default();
+        let mut name = folder_name.to_string();
+        let mut idx = 1;
+        while folders.iter().any(|f| f.parent == parent && f.name == name) {
+            idx += 1;
+            name = format!("{}{}", folder_name, idx);
+        }
+        let folder = Folder::new(&name, parent);
+        let id = folder.id;
+        folders.push(folder);
+        Ok(id)
+    }
+
+    pub fn rename_group(&mut self, old: &str, new: &str) -> Result<(), &'static str> {
+        if old == new { return Ok(()); }
+        if self.groups.contains_key(new) { return Err("分组名已存在"); }
+        if let Some(mut tables) = self.groups.remove(old) {
+            // 单词记录的所属分组随之更新
+            for word in tables.iter_mut().flat_map(|t| t.words.iter_mut()) {
+                word.group = new.to_string();
+            }
+            self.groups.insert(new.to_string(), tables);
+            if let Some(id) = self.group_ids.remove(old) {
+                self.group_ids.insert(new.to_string(), id);
+            }
+            if let Some(name) = self.group_order.iter_mut().find(|n| *n == old) {
+                *name = new.to_string();
+            }
+            if let Some(created) = self.group_created.remove(old) {
+                self.group_created.insert(new.to_string(), created);
+            }
+            if let Some(folders) = self.folders.remove(old) {
+                self.folders.insert(new.to_string(), folders);
+            }
+            if let Some(settings) = self.scheduler_settings.remove(old) {
+                self.scheduler_settings.insert(new.to_string(), settings);
+            }
+            Ok(())
+        } else {
+            Err("原分组不存在")
+        }
+    }
+
+    pub fn rename_word_table(&mut self, group: &str, table: Uuid, new_name: &str) -> Result<(), &'static str> {
+        let tables = self.groups.get_mut(group).ok_or("分组不存在")?;
+        let folder = tables.iter().find(|t| t.id == table).ok_or("单词表不存在")?.folder;
+        if tables.iter().any(|t| t.id != table && t.folder == folder && t.name == new_name) {
+            return Err("单词表名已存在");
+        }
+        let target = tables.iter_mut().find(|t| t.id == table).ok_or("单词表不存在")?;
+        target.name = new_name.to_string();
+        Ok(())
+    }
+
+    pub fn rename_folder(&mut self, group: &str, folder: Uuid, new_name: &str) -> Result<(), &'static str> {
+        let folders = self.folders.get_mut(group).ok_or("文件夹不存在")?;
+        let parent = folders.iter().find(|f| f.id == folder).ok_or("文件夹不存在")?.parent;
+        if folders.iter().any(|f| f.id != folder && f.parent == parent && f.name == new_name) {
+            return Err("文件夹名已存在");
+        }
+        let target = folders.iter_mut().find(|f| f.id == folder).ok_or("文件夹不存在")?;
+        target.name = new_name.to_string();
+        Ok(())
+    }
+
+    pub fn delete_group(&mut self, group: &str) -> Result<(), &'static str> {
+        if self.groups.remove(group).is_some() {
+            self.group_ids.remove(group);
+            self.group_order.retain(|n| n != group);
+            self.group_created.remove(group);
+            self.folders.remove(group);
+            self.scheduler_settings.remove(group);
+            Ok(())
+        } else {
+            Err("分组不存在")
+        }
+    }
+
+    pub fn delete_word_table(&mut self, group: &str, table: Uuid) -> Result<(), &'static str> {
+        let tables = self.groups.get_mut(group).ok_or("分组不存在")?;
+        let pos = tables.iter().position(|t| t.id == table).ok_or("单词表不存在")?;
+        tables.remove(pos);
+        Ok(())
+    }
+
+    // 删除文件夹及其中的子文件夹与单词表
+    pub fn delete_folder(&mut self, group: &str, folder: Uuid) -> Result<(), &'static str> {
+        if self.get_folder(group, folder).is_none() {
+            return Err("文件夹不存在");
+        }
+        let mut removed = vec![folder];
+        let mut idx = 0;
+        while idx < removed.len() {
+            let parent = Some(removed[idx]);
+            removed.extend(self.get_folders_in_group(group).iter().filter(|f| f.parent == parent).map(|f| f.id));
+            idx += 1;
+        }
+        if let Some(folders) = self.folders.get_mut(group) {
+            folders.retain(|f| !removed.contains(&f.id));
+        }
+        if let Some(tables) = self.groups.get_mut(group) {
+            tables.retain(|t| !t.folder.is_some_and(|f| removed.contains(&f)));
+        }
+        Ok(())
+    }
+
+    // 按手动顺序排列的分组
+    pub fn get_groups(&self) -> Vec<&String> { self.group_order.iter().collect() }
+
+    // 把分组移到手动顺序中的第 to 位（超出时放到最后）
+    pub fn move_group(&mut self, group: &str, to: usize) -> Result<(), &'static str> {
+        let from = self.group_order.iter().position(|n| n == group).ok_or("分组不存在")?;
+        let name = self.group_order.remove(from);
+        let to = to.min(self.group_order.len());
+        self.group_order.insert(to, name);
+        Ok(())
+    }
+
+    // 把单词表移到 folder（None 为分组下）中同级单词表的第 to 位（超出时放到最后）
+    pub fn move_word_table(&mut self, group: &str, table: Uuid, folder: Option<Uuid>, to: usize) -> Result<(), &'static str> {
+        if folder.is_some_and(|f| self.get_folder(group, f).is_none()) {
+            return Err("文件夹不存在");
+        }
+        let tables = self.groups.get_mut(group).ok_or("分组不存在")?;
+        let from = tables.iter().position(|t| t.id == table).ok_or("单词表不存在")?;
+        if tables.iter().any(|t| t.id != table && t.folder == folder && t.name == tables[from].name) {
+            return Err("目标位置已有同名单词表");
+        }
+        let mut moved = tables.remove(from);
+        moved.folder = folder;
+        insert_among(tables, moved, from, to, |t| t.folder == folder);
+        Ok(())
+    }
+
+    // 把文件夹移到同级文件夹中的第 to 位（超出时放到最后）
+    pub fn move_folder(&mut self, group: &str, folder: Uuid, to: usize) -> Result<(), &'static str> {
+        let folders = self.folders.get_mut(group).ok_or("文件夹不存在")?;
+        let from = folders.iter().position(|f| f.id == folder).ok_or("文件夹不存在")?;
+        let moved = folders.remove(from);
+        let parent = moved.parent;
+        insert_among(folders, moved, from, to, |f| f.parent == parent);
+        Ok(())
+    }
+
+    // 分组中的全部单词表（包括各层文件夹中的），按保存顺序
+    pub fn get_word_tables_in_group(&self, group: &str) -> Option<&Vec<WordTable>> {
+        self.groups.get(group)
+    }
+
+    pub fn get_word_table(&self, group: &str, table: Uuid) -> Option<&WordTable> {
+        self.groups.get(group)?.iter().find(|t| t.id == table)
+    }
+
+    // 按名称查找分组或文件夹中直接包含的单词表（同一位置的单词表不重名）
+    pub fn find_word_table(&self, group: &str, folder: Option<Uuid>, table_name: &str) -> Option<&WordTable> {
+        self.groups.get(group)?.iter().find(|t| t.folder == folder && t.name == table_name)
+    }
+
+    // 分组中的全部文件夹，同级文件夹按手动顺序
+    pub fn get_folders_in_group(&self, group: &str) -> &[Folder] {
+        self.folders.get(group).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn get_folder(&self, group: &str, folder: Uuid) -> Option<&Folder> {
+        self.get_folders_in_group(group).iter().find(|f| f.id == folder)
+    }
+
+    // 分组或文件夹中直接包含的子文件夹与单词表
+    pub fn child_folders(&self, group: &str, parent: Option<Uuid>) -> Vec<&Folder> {
+        self.get_folders_in_group(group).iter().filter(|f| f.parent == parent).collect()
+    }
+
+    pub fn child_tables(&self, group: &str, folder: Option<Uuid>) -> Vec<&WordTable> {
+        self.groups.get(group).into_iter().flatten().filter(|t| t.folder == folder).collect()
+    }
+
+    // 分组或文件夹下各层的全部单词表，按目录中的顺序（先子文件夹，后直接包含的单词表）
+    pub fn tables_under(&self, group: &str, folder: Option<Uuid>) -> Vec<&WordTable> {
+        let mut tables = Vec::new();
+        for child in self.child_folders(group, folder) {
+            tables.extend(self.tables_under(group, Some(child.id)));
+        }
+        tables.extend(self.child_tables(group, folder));
+        tables
+    }
+
+    pub fn set_word_table_timing(&mut self, group: &str, table: Uuid, timing: FlashTiming) -> Result<(), &'static str> {
+        let tables = self.groups.get_mut(group).ok_or("分组不存在")?;
+        let table = tables.iter_mut().find(|t| t.id == table).ok_or("单词表不存在")?;
+        table.timing = timing;
+        Ok(())
+    }
+
+    pub fn set_word_table_separator(&mut self, group: &str, table: Uuid, separator: Separator) -> Result<(), &'static str> {
+        let tables = self.groups.get_mut(group).ok_or("分组不存在")?;
+        let table = tables.iter_mut().find(|t| t.id == table).ok_or("单词表不存在")?;
+        table.separator = separator;
+        Ok(())
+    }
+
+    // 分组中各层单词表的全部单词，按目录中的顺序；分组不存在时返回 None
+    pub fn get_words_in_group(&self, group: &str) -> Option<Vec<Word>> {
+        self.groups.get(group)?;
+        Some(self.tables_under(group, None).iter().flat_map(|t| t.words.iter().cloned()).collect())
+    }
+
+    // 覆盖原有内容，能对应上的单词保留标识与复习状态
+    pub fn add_words_to_table(&mut self, group: &str, table: Uuid, words: Vec<Word>) -> Result<(), &'static str> {
+        self.save_words_to_table(group, table, words, SaveMode::Replace)
+    }
+
+    // 按保存方式（替换、追加或按英文合并）把编辑的单词写入单词表
+    pub fn save_words_to_table(&mut self, group: &str, table: Uuid, words: Vec<Word>, mode: SaveMode) -> Result<(), &'static str> {
+        let tables = self.groups.get_mut(group).ok_or("分组不存在")?;
+        let table = tables.iter_mut().find(|t| t.id == table).ok_or("单词表不存在")?;
+        table.words = merge_words(&table.words, words, mode);
+        Ok(())
+    }
+
+    // 单词表中今天需要复习的单词（新词与到期词）
+    pub fn get_due_words(&self, group: &str, table: Uuid, today: NaiveDate) -> Vec<Word> {
+        self.get_word_table(group, table)
+            .map(|table| table.words.iter().filter(|w| w.schedule.is_due(today)).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    // 根据评分更新单词的复习状态（在分组的各单词表中按标识定位单词）
+    pub fn grade_word(&mut self, group: &str, word: &Word, grade: Grade, today: NaiveDate) -> Result<(), &'static str> {
+        let tables = self.groups.get_mut(group).ok_or("分组不存在")?;
+        let target = tables.iter_mut().flat_map(|t| t.words.iter_mut()).find(|w| w.id == word.id).ok_or("单词不存在")?;
+        let scheduler = self.scheduler_settings.get(group).cloned().unwrap_or_default().scheduler();
+        scheduler.review(&mut target.schedule, grade, today);
+        Ok(())
+    }
+
+    // 按分组当前的算法，从复习记录重新计算各单词的复习状态（没有记录的单词保持不变）
+    pub fn reschedule_group(&mut self, group: &str, log: &ReviewLog) {
+        let scheduler = self.scheduler_settings.get(group).cloned().unwrap_or_default().scheduler();
+        let histories = log.histories_in_group(self, group);
+        if let Some(tables) = self.groups.get_mut(group) {
+            for word in tables.iter_mut().flat_map(|t| t.words.iter_mut()) {
+                if let Some(reviews) = histories.get(&WordKey::Id(word.id)) {
+                    word.schedule = scheduler.replay(reviews);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::Algorithm;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+    }
+
+    fn sample() -> FlashMemory {
+        let mut memory = FlashMemory::new();
+        let first = memory.create_word_table("英语", None, "第一课").unwrap();
+        memory.create_word_table("英语", None, "第二课").unwrap();
+        memory
+            .add_words_to_table("英语", first, vec![Word::new("apple", "苹果", "英语"), Word::new("pear", "梨", "英语")])
+            .unwrap();
+        memory
+    }
+
+    fn table(memory: &FlashMemory, group: &str, name: &str) -> Uuid {
+        memory.find_word_table(group, None, name).unwrap().id
+    }
+
+    #[test]
+    fn create_group_appends_suffix_on_conflict() {
+        let mut memory = FlashMemory::new();
+        assert_eq!(memory.create_group("新分组"), "新分组");
+        assert_eq!(memory.create_group("新分组"), "新分组2");
+        assert_eq!(memory.create_group("新分组"), "新分组3");
+        assert_eq!(memory.groups.len(), 3);
+    }
+
+    #[test]
+    fn create_group_if_absent_keeps_existing_tables() {
+        let mut memory = sample();
+        memory.create_group_if_absent("英语");
+        assert_eq!(memory.get_word_tables_in_group("英语").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn create_word_table_appends_suffix_on_conflict() {
+        let mut memory = sample();
+        let name = |memory: &FlashMemory, id| memory.get_word_table("英语", id).unwrap().name.clone();
+        let second = memory.create_word_table("英语", None, "第一课").unwrap();
+        assert_eq!(name(&memory, second), "第一课2");
+        let third = memory.create_word_table("英语", None, "第一课").unwrap();
+        assert_eq!(name(&memory, third), "第一课3");
+        // 不同分组之间、不同文件夹之间允许同名
+        let japanese = memory.create_word_table("日语", None, "第一课").unwrap();
+        assert_eq!(memory.get_word_table("日语", japanese).unwrap().name, "第一课");
+        let unit = memory.create_folder("英语", None, "第一单元").unwrap();
+        let nested = memory.create_word_table("英语", Some(unit), "第一课").unwrap();
+        assert_eq!(name(&memory, nested), "第一课");
+        assert!(memory.find_word_table("英语", None, "第一课").unwrap().words.len() == 2);
+        assert_eq!(memory.create_word_table("英语", Some(Uuid::new_v4()), "第一课"), Err("文件夹不存在"));
+    }
+
+    #[test]
+    fn rename_group_moves_tables_and_settings() {
+        let mut memory = sample();
+        let settings = SchedulerSettings { algorithm: Algorithm::Fsrs, ..Default::default() };
+        memory.scheduler_settings.insert("英语".to_string(), settings.clone());
+
+        memory.rename_group("英语", "English").unwrap();
+        assert!(!memory.groups.contains_key("英语"));
+        let tables = memory.get_word_tables_in_group("English").unwrap();
+        assert_eq!(tables.len(), 2);
+        assert!(tables.iter().flat_map(|t| &t.words).all(|w| w.group == "English"));
+        assert_eq!(memory.scheduler_settings.get("English"), Some(&settings));
+        assert!(!memory.scheduler_settings.contains_key("英语"));
+    }
+
+    #[test]
+    fn reschedule_after_rename_keeps_earlier_reviews() {
+        use crate::review_log::{ReviewRecord, StudyMode};
+
+        let mut memory = sample();
+        let apple = memory.find_word_table("英语", None, "第一课").unwrap().words[0].clone();
+        let reviewed = |d: u32, grade| ReviewRecord {
+            word_id: Some(apple.id),
+            group: "英语".to_string(),
+            table: "第一课".to_string(),
+            english: apple.english.clone(),
+            chinese: apple.chinese.clone(),
+            mode: StudyMode::Review,
+            grade: Some(grade),
+            reveal_ms: None,
+            response_ms: None,
+            reviewed_at: day(d).and_hms_opt(9, 0, 0).unwrap().and_local_timezone(Local).unwrap(),
+        };
+        let log = ReviewLog::new(vec![reviewed(1, Grade::Good), reviewed(2, Grade::Good), reviewed(6, Grade::Hard)]);
+
+        // 记录仍是改名前的分组名
+        memory.rename_group("英语", "English").unwrap();
+        memory.reschedule_group("English", &log);
+        let expected = SchedulerSettings::default()
+            .scheduler()
+            .replay(&[(day(1), Grade::Good), (day(2), Grade::Good), (day(6), Grade::Hard)]);
+        let words = &memory.find_word_table("English", None, "第一课").unwrap().words;
+        assert_eq!(words[0].schedule, expected);
+        assert_eq!(words[0].schedule.repetitions, 3);
+        assert_eq!(words[1].schedule, Schedule::default());
+    }
+
+    #[test]
+    fn groups_keep_manual_order_through_renames() {
+        let mut memory = FlashMemory::new();
+        for name in ["Unit 2", "Unit 10", "Unit 1"] {
+            memory.create_group_if_absent(name);
+        }
+        assert_eq!(memory.get_groups(), vec!["Unit 2", "Unit 10", "Unit 1"]);
+        memory.move_group("Unit 1", 0).unwrap();
+        memory.rename_group("Unit 10", "Unit 3").unwrap();
+        memory.move_group("Unit 2", 9).unwrap();
+        assert_eq!(memory.get_groups(), vec!["Unit 1", "Unit 3", "Unit 2"]);
+        assert!(memory.group_created.contains_key("Unit 3"));
+        memory.delete_group("Unit 3").unwrap();
+        assert_eq!(memory.get_groups(), vec!["Unit 1", "Unit 2"]);
+        assert_eq!(memory.move_group("Unit 3", 0), Err("分组不存在"));
+    }
+
+    #[test]
+    fn move_word_table_within_group() {
+        let mut memory = sample();
+        let third = memory.create_word_table("英语", None, "第三课").unwrap();
+        memory.move_word_table("英语", third, None, 0).unwrap();
+        let names: Vec<_> = memory.get_word_tables_in_group("英语").unwrap().iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["第三课", "第一课", "第二课"]);
+        assert_eq!(memory.move_word_table("英语", Uuid::new_v4(), None, 0), Err("单词表不存在"));
+    }
+
+    #[test]
+    fn rename_group_conflicts() {
+        let mut memory = sample();
+        memory.create_group_if_absent("日语");
+        assert_eq!(memory.rename_group("英语", "日语"), Err("分组名已存在"));
+        assert_eq!(memory.rename_group("法语", "德语"), Err("原分组不存在"));
+        // 改成原名视为成功，不做任何改动
+        assert_eq!(memory.rename_group("英语", "英语"), Ok(()));
+        assert_eq!(memory.get_word_tables_in_group("英语").unwrap().len(), 2);
+        assert_eq!(memory.get_word_tables_in_group("日语").unwrap().len(), 0);
+    }
+
+    #[test]
+    fn rename_word_table_conflicts() {
+        let mut memory = sample();
+        let first = table(&memory, "英语", "第一课");
+        assert_eq!(memory.rename_word_table("英语", first, "第二课"), Err("单词表名已存在"));
+        assert_eq!(memory.rename_word_table("英语", Uuid::new_v4(), "第四课"), Err("单词表不存在"));
+        assert_eq!(memory.rename_word_table("日语", first, "第四课"), Err("分组不存在"));
+        assert_eq!(memory.rename_word_table("英语", first, "第一课"), Ok(()));
+
+        memory.rename_word_table("英语", first, "Lesson 1").unwrap();
+        assert!(memory.find_word_table("英语", None, "第一课").is_none());
+        assert_eq!(memory.find_word_table("英语", None, "Lesson 1").unwrap().words.len(), 2);
+    }
+
+    #[test]
+    fn delete_group_and_table() {
+        let mut memory = sample();
+        memory.scheduler_settings.insert("英语".to_string(), SchedulerSettings::default());
+
+        let second = table(&memory, "英语", "第二课");
+        assert_eq!(memory.delete_word_table("英语", Uuid::new_v4()), Err("单词表不存在"));
+        assert_eq!(memory.delete_word_table("日语", second), Err("分组不存在"));
+        memory.delete_word_table("英语", second).unwrap();
+        assert!(memory.find_word_table("英语", None, "第二课").is_none());
+        assert!(memory.find_word_table("英语", None, "第一课").is_some());
+
+        assert_eq!(memory.delete_group("日语"), Err("分组不存在"));
+        memory.delete_group("英语").unwrap();
+        assert!(memory.groups.is_empty());
+        assert!(memory.scheduler_settings.is_empty());
+    }
+
+    #[test]
+    fn add_words_to_table_replaces_content() {
+        let mut memory = sample();
+        let first = table(&memory, "英语", "第一课");
+        memory.add_words_to_table("英语", first, vec![Word::new("banana", "香蕉", "英语")]).unwrap();
+        let words = &memory.get_word_table("英语", first).unwrap().words;
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].english, "banana");
+        assert_eq!(memory.add_words_to_table("英语", Uuid::new_v4(), Vec::new()), Err("单词表不存在"));
+        assert_eq!(memory.add_words_to_table("日语", first, Vec::new()), Err("分组不存在"));
+    }
+
+    #[test]
+    fn save_words_to_table_appends_and_merges() {
+        let mut memory = sample();
+        let first = table(&memory, "英语", "第一课");
+        let pear = memory.get_word_table("英语", first).unwrap().words[1].clone();
+        memory.save_words_to_table("英语", first, vec![Word::new("banana", "香蕉", "英语")], SaveMode::Append).unwrap();
+        memory.save_words_to_table("英语", first, vec![Word::new("pear", "梨子", "英语")], SaveMode::Merge).unwrap();
+        let words = &memory.get_word_table("英语", first).unwrap().words;
+        let pairs: Vec<_> = words.iter().map(|w| (w.english.as_str(), w.chinese.as_str())).collect();
+        assert_eq!(pairs, vec![("apple", "苹果"), ("pear", "梨子"), ("banana", "香蕉")]);
+        assert_eq!(words[1].id, pear.id);
+    }
+
+    #[test]
+    fn graded_word_is_not_due_until_next_review() {
+        let mut memory = sample();
+        let first = table(&memory, "英语", "第一课");
+        assert_eq!(memory.get_due_words("英语", first, day(1)).len(), 2);
+
+        let apple = memory.get_word_table("英语", first).unwrap().words[0].clone();
+        memory.grade_word("英语", &apple, Grade::Good, day(1)).unwrap();
+        let due = memory.get_due_words("英语", first, day(1));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].english, "pear");
+        assert_eq!(memory.get_due_words("英语", first, day(2)).len(), 2);
+
+        let missing = Word::new("grape", "葡萄", "英语");
+        assert_eq!(memory.grade_word("英语", &missing, Grade::Good, day(1)), Err("单词不存在"));
+    }
+
+    #[test]
+    fn grading_finds_word_by_id_among_duplicates() {
+        let mut memory = sample();
+        let copies: Vec<Word> = (0..5).map(|_| Word::new("asdf", "重复", "英语")).collect();
+        memory.add_words_to_table("英语", table(&memory, "英语", "第二课"), copies.clone()).unwrap();
+        memory.grade_word("英语", &copies[3], Grade::Good, day(1)).unwrap();
+        let words = &memory.find_word_table("英语", None, "第二课").unwrap().words;
+        let graded: Vec<bool> = words.iter().map(|w| w.schedule.due.is_some()).collect();
+        assert_eq!(graded, vec![false, false, false, true, false]);
+    }
+
+    #[test]
+    fn ids_survive_renames() {
+        let mut memory = sample();
+        let group_id = memory.group_id("英语").unwrap();
+        let table = memory.find_word_table("英语", None, "第一课").unwrap().clone();
+        memory.rename_group("英语", "English").unwrap();
+        memory.rename_word_table("English", table.id, "Lesson 1").unwrap();
+        assert_eq!(memory.group_id("English"), Some(group_id));
+        assert_eq!(memory.group_id("英语"), None);
+        let renamed = memory.find_word_table("English", None, "Lesson 1").unwrap();
+        assert_eq!(renamed.id, table.id);
+        assert_eq!(renamed.words, table.words.iter().map(|w| Word { group: "English".to_string(), ..w.clone() }).collect::<Vec<_>>());
+
+        memory.delete_group("English").unwrap();
+        assert!(memory.group_ids.is_empty());
+    }
+
+    #[test]
+    fn edited_words_keep_ids_and_schedule() {
+        let mut memory = sample();
+        let first = table(&memory, "英语", "第一课");
+        let before = memory.get_word_table("英语", first).unwrap().words.clone();
+        memory.grade_word("英语", &before[1], Grade::Good, day(1)).unwrap();
+        let pear_schedule = memory.get_word_table("英语", first).unwrap().words[1].schedule.clone();
+
+        // 重新排序、修改释义、修正拼写并新增一个单词
+        let edited = vec![
+            Word::new("pear", "梨", "英语"),
+            Word::new("banana", "香蕉", "英语"),
+            Word::new("apple", "苹果；苹果树", "英语"),
+        ];
+        memory.add_words_to_table("英语", first, edited).unwrap();
+        let after = &memory.get_word_table("英语", first).unwrap().words;
+        assert_eq!(after[0].id, before[1].id);
+        assert_eq!(after[0].schedule, pear_schedule);
+        assert_eq!(after[2].id, before[0].id);
+        assert!(after[1].id != before[0].id && after[1].id != before[1].id);
+
+        // 按释义配对：拼写修正后仍是同一个单词
+        memory.add_words_to_table("英语", first, vec![Word::new("peer", "梨", "英语")]).unwrap();
+        assert_eq!(memory.get_word_table("英语", first).unwrap().words[0].id, before[1].id);
+    }
+
+    #[test]
+    fn set_word_table_timing() {
+        let mut memory = sample();
+        let timing = FlashTiming { front: 2.5, ..Default::default() };
+        let first = table(&memory, "英语", "第一课");
+        memory.set_word_table_timing("英语", first, timing.clone()).unwrap();
+        assert_eq!(memory.get_word_table("英语", first).unwrap().timing, timing);
+        assert_eq!(memory.set_word_table_timing("英语", Uuid::new_v4(), timing), Err("单词表不存在"));
+    }
+
+    #[test]
+    fn set_word_table_separator() {
+        let mut memory = sample();
+        let first = table(&memory, "英语", "第一课");
+        assert_eq!(memory.get_word_table("英语", first).unwrap().separator, Separator::Smart);
+        memory.set_word_table_separator("英语", first, Separator::Tab).unwrap();
+        assert_eq!(memory.get_word_table("英语", first).unwrap().separator, Separator::Tab);
+        assert_eq!(memory.set_word_table_separator("日语", first, Separator::Tab), Err("分组不存在"));
+    }
+
+    #[test]
+    fn group_words_follow_directory_order() {
+        let mut memory = sample();
+        assert_eq!(memory.get_words_in_group("日语"), None);
+        let unit = memory.create_folder("英语", None, "第一单元").unwrap();
+        let nested = memory.create_word_table("英语", Some(unit), "第三课").unwrap();
+        memory.add_words_to_table("英语", nested, vec![Word::new("cat", "猫", "英语")]).unwrap();
+        let words: Vec<String> = memory.get_words_in_group("英语").unwrap().into_iter().map(|w| w.english).collect();
+        assert_eq!(words, vec!["cat", "apple", "pear"]);
+    }
+
+    #[test]
+    fn add_word_goes_to_unsorted_table() {
+        let mut memory = sample();
+        let table = memory.add_word(Word::new("cat", "猫", "英语"));
+        assert_eq!(memory.add_word(Word::new("dog", "狗", "英语")), table);
+        let names: Vec<_> = memory.get_word_table("英语", table).unwrap().words.iter().map(|w| w.english.as_str()).collect();
+        assert_eq!(names, vec!["cat", "dog"]);
+        assert_eq!(memory.get_word_tables_in_group("英语").unwrap().len(), 3);
+        // 分组不存在时一并新建
+        let japanese = memory.add_word(Word::new("neko", "猫", "日语"));
+        assert_eq!(memory.get_word_table("日语", japanese).unwrap().name, UNSORTED_TABLE);
+    }
+
+    #[test]
+    fn folders_nest_and_move_with_their_tables() {
+        let mut memory = sample();
+        assert_eq!(memory.create_folder("日语", None, "第一单元"), Err("分组不存在"));
+        let unit = memory.create_folder("英语", None, "第一单元").unwrap();
+        let section = memory.create_folder("英语", Some(unit), "第一节").unwrap();
+        let other = memory.create_folder("英语", None, "第一单元").unwrap();
+        assert_eq!(memory.get_folder("英语", other).unwrap().name, "第一单元2");
+        assert_eq!(memory.rename_folder("英语", other, "第一单元"), Err("文件夹名已存在"));
+        memory.rename_folder("英语", other, "第二单元").unwrap();
+        memory.move_folder("英语", other, 0).unwrap();
+        let names: Vec<_> = memory.child_folders("英语", None).iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["第二单元", "第一单元"]);
+
+        let first = table(&memory, "英语", "第一课");
+        memory.move_word_table("英语", first, Some(section), 0).unwrap();
+        let lesson = memory.create_word_table("英语", Some(unit), "第三课").unwrap();
+        let under: Vec<_> = memory.tables_under("英语", Some(unit)).iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(under, vec!["第一课", "第三课"]);
+        assert_eq!(memory.tables_under("英语", None).len(), 3);
+        // 目标文件夹中已有同名单词表时不能移入
+        let copy = memory.create_word_table("英语", None, "第三课").unwrap();
+        assert_eq!(memory.move_word_table("英语", copy, Some(unit), 0), Err("目标位置已有同名单词表"));
+
+        // 删除文件夹时其中的子文件夹与单词表一并删除，单词表在分组中的顺序不受影响
+        memory.delete_folder("英语", unit).unwrap();
+        assert!(memory.get_folder("英语", section).is_none());
+        assert!(memory.get_word_table("英语", lesson).is_none());
+        let names: Vec<_> = memory.get_word_tables_in_group("英语").unwrap().iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["第二课", "第三课"]);
+        assert_eq!(memory.delete_folder("英语", unit), Err("文件夹不存在"));
+
+        memory.rename_group("英语", "English").unwrap();
+        assert_eq!(memory.get_folders_in_group("English").len(), 1);
+        memory.delete_group("English").unwrap();
+        assert!(memory.folders.is_empty());
+    }
+}
@@ -0,0 +1,185 @@
+use std::cmp::{Ordering, Reverse};
+use std::iter::Peekable;
+use std::str::Chars;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::model::{FlashMemory, Folder, WordTable};
+
+// 目录中分组、文件夹与单词表的排列方式；手动顺序保存在单词库中，其他方式只影响显示
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortMode {
+    #[default]
+    Manual,   // 手动顺序，可拖动调整
+    Natural,  // 按名称，名称中的数字按大小比较（Unit 2 在 Unit 10 之前）
+    Created,  // 按创建时间，早期没有记录的排在最前
+    DueCount, // 按今天待复习的单词数，多的在前
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 4] = [SortMode::Manual, SortMode::Natural, SortMode::Created, SortMode::DueCount];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::Manual => "手动",
+            SortMode::Natural => "名称",
+            SortMode::Created => "创建时间",
+            SortMode::DueCount => "待复习数",
+        }
+    }
+}
+
+// 按名称比较，连续的数字按数值比较，英文字母不区分大小写；完全相同时再按原文比较，保证顺序确定
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut x, mut y) = (a.chars().peekable(), b.chars().peekable());
+    loop {
+        let ord = match (x.peek(), y.peek()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(c), Some(d)) if c.is_ascii_digit() && d.is_ascii_digit() => {
+                let (m, n) = (take_number(&mut x), take_number(&mut y));
+                let (m_digits, n_digits) = (m.trim_start_matches('0'), n.trim_start_matches('0'));
+                m_digits.len().cmp(&n_digits.len()).then_with(|| m_digits.cmp(n_digits))
+            }
+            (Some(c), Some(d)) => {
+                let ord = c.to_ascii_lowercase().cmp(&d.to_ascii_lowercase());
+                x.next();
+                y.next();
+                ord
+            }
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+}
+
+fn take_number(chars: &mut Peekable<Chars>) -> String {
+    let mut number = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        number.push(c);
+    }
+    number
+}
+
+// 按排列方式返回分组名；并列时保持手动顺序
+pub fn sorted_groups(memory: &FlashMemory, mode: SortMode, today: NaiveDate) -> Vec<String> {
+    let mut groups: Vec<String> = memory.get_groups().into_iter().cloned().collect();
+    match mode {
+        SortMode::Manual => {}
+        SortMode::Natural => groups.sort_by(|a, b| natural_cmp(a, b)),
+        SortMode::Created => groups.sort_by_key(|g| memory.group_created.get(g).copied()),
+        SortMode::DueCount => groups.sort_by_key(|g| {
+            let tables = memory.get_word_tables_in_group(g).map(Vec::as_slice).unwrap_or(&[]);
+            Reverse(tables.iter().map(|t| t.due_count(today)).sum::<usize>())
+        }),
+    }
+    groups
+}
+
+// 按排列方式返回分组或文件夹中直接包含的子文件夹；并列时保持手动顺序
+pub fn sorted_folders<'a>(
+    memory: &'a FlashMemory,
+    group: &str,
+    parent: Option<Uuid>,
+    mode: SortMode,
+    today: NaiveDate,
+) -> Vec<&'a Folder> {
+    let mut folders = memory.child_folders(group, parent);
+    match mode {
+        SortMode::Manual => {}
+        SortMode::Natural => folders.sort_by(|a, b| natural_cmp(&a.name, &b.name)),
+        SortMode::Created => folders.sort_by_key(|f| f.created),
+        SortMode::DueCount => folders.sort_by_key(|f| {
+            Reverse(memory.tables_under(group, Some(f.id)).iter().map(|t| t.due_count(today)).sum::<usize>())
+        }),
+    }
+    folders
+}
+
+// 按排列方式返回分组或文件夹中直接包含的单词表；并列时保持手动顺序
+pub fn sorted_tables<'a>(
+    memory: &'a FlashMemory,
+    group: &str,
+    folder: Option<Uuid>,
+    mode: SortMode,
+    today: NaiveDate,
+) -> Vec<&'a WordTable> {
+    let mut tables = memory.child_tables(group, folder);
+    match mode {
+        SortMode::Manual => {}
+        SortMode::Natural => tables.sort_by(|a, b| natural_cmp(&a.name, &b.name)),
+        SortMode::Created => tables.sort_by_key(|t| t.created),
+        SortMode::DueCount => tables.sort_by_key(|t| Reverse(t.due_count(today))),
+    }
+    tables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Word;
+    use crate::scheduler::Schedule;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+    }
+
+    #[test]
+    fn numbers_compare_by_value() {
+        let mut names = vec!["Unit 10", "unit 2", "Unit 1", "Unit 02", "第3课", "第12课", "Unit", "Unit 1b"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, vec!["Unit", "Unit 1", "Unit 1b", "Unit 02", "unit 2", "Unit 10", "第3课", "第12课"]);
+    }
+
+    #[test]
+    fn sort_modes_order_groups_and_tables() {
+        let mut memory = FlashMemory::new();
+        for name in ["Unit 10", "Unit 2", "旧分组"] {
+            memory.create_word_table(name, None, "第一课").unwrap();
+        }
+        memory.group_created.remove("旧分组");
+        let mut learned = Word::new("pear", "梨", "Unit 2");
+        learned.schedule = Schedule { due: Some(day(20)), ..Schedule::default() };
+        let table = |memory: &FlashMemory, group| memory.find_word_table(group, None, "第一课").unwrap().id;
+        memory.add_words_to_table("Unit 2", table(&memory, "Unit 2"), vec![learned]).unwrap();
+        let first = table(&memory, "Unit 10");
+        memory.add_words_to_table("Unit 10", first, vec![Word::new("apple", "苹果", "Unit 10")]).unwrap();
+        let second = memory.create_word_table("Unit 10", None, "第二课").unwrap();
+        memory.add_words_to_table("Unit 10", second, vec![Word::new("a", "一", "Unit 10"), Word::new("b", "二", "Unit 10")]).unwrap();
+
+        let groups = |mode| sorted_groups(&memory, mode, day(1));
+        assert_eq!(groups(SortMode::Manual), vec!["Unit 10", "Unit 2", "旧分组"]);
+        assert_eq!(groups(SortMode::Natural), vec!["Unit 2", "Unit 10", "旧分组"]);
+        assert_eq!(groups(SortMode::Created), vec!["旧分组", "Unit 10", "Unit 2"]);
+        assert_eq!(groups(SortMode::DueCount), vec!["Unit 10", "Unit 2", "旧分组"]);
+
+        let tables = |mode| -> Vec<String> {
+            sorted_tables(&memory, "Unit 10", None, mode, day(1)).iter().map(|t| t.name.clone()).collect()
+        };
+        assert_eq!(tables(SortMode::Manual), vec!["第一课", "第二课"]);
+        assert_eq!(tables(SortMode::DueCount), vec!["第二课", "第一课"]);
+    }
+
+    #[test]
+    fn folders_sort_by_name_and_by_due_words_below() {
+        let mut memory = FlashMemory::new();
+        memory.create_group_if_absent("英语");
+        let unit10 = memory.create_folder("英语", None, "Unit 10").unwrap();
+        let unit2 = memory.create_folder("英语", None, "Unit 2").unwrap();
+        let lesson = memory.create_folder("英语", Some(unit2), "Lesson 1").unwrap();
+        let table = memory.create_word_table("英语", Some(lesson), "生词").unwrap();
+        memory.add_words_to_table("英语", table, vec![Word::new("apple", "苹果", "英语")]).unwrap();
+
+        let folders = |mode| -> Vec<Uuid> {
+            sorted_folders(&memory, "英语", None, mode, day(1)).iter().map(|f| f.id).collect()
+        };
+        assert_eq!(folders(SortMode::Manual), vec![unit10, unit2]);
+        assert_eq!(folders(SortMode::Natural), vec![unit2, unit10]);
+        // 子文件夹中的待复习单词也计入
+        assert_eq!(folders(SortMode::DueCount), vec![unit2, unit10]);
+    }
+}
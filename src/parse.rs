@@ -0,0 +1,482 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Example, Word};
+use crate::quiz;
+
+// 单词行中英文与释义之间的分隔符，按单词表记住上次使用的
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Separator {
+    #[default]
+    Smart,  // 自动：制表符，否则在英文与中日韩文字的交界处分开，都没有时按第一个空格
+    Space,  // 第一个空格（含全角空格）
+    Tab,
+    Equals, // = 或 ＝
+    Pipe,   // | 或 ｜
+    Comma,  // , 或 ，
+}
+
+impl Separator {
+    pub const ALL: [Separator; 6] =
+        [Separator::Smart, Separator::Space, Separator::Tab, Separator::Equals, Separator::Pipe, Separator::Comma];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Separator::Smart => "自动",
+            Separator::Space => "空格",
+            Separator::Tab => "制表符",
+            Separator::Equals => "等号 =",
+            Separator::Pipe => "竖线 |",
+            Separator::Comma => "逗号 ,",
+        }
+    }
+
+    // 写成文本时英文与释义之间的内容
+    fn joiner(self) -> &'static str {
+        match self {
+            Separator::Smart | Separator::Space => " ",
+            Separator::Tab => "\t",
+            Separator::Equals => " = ",
+            Separator::Pipe => " | ",
+            Separator::Comma => ", ",
+        }
+    }
+
+    fn matches(self, c: char) -> bool {
+        match self {
+            Separator::Smart => c == '\t',
+            Separator::Space => c == ' ' || c == '\u{3000}',
+            Separator::Tab => c == '\t',
+            Separator::Equals => c == '=' || c == '＝',
+            Separator::Pipe => c == '|' || c == '｜',
+            Separator::Comma => c == ',' || c == '，',
+        }
+    }
+
+    // 把一行（已去掉首尾空白）分成 (英文, 释义)；英文含分隔符时可以用双引号括起来，引号本身写两遍
+    pub fn split_line(self, line: &str) -> Result<(String, String), LineIssue> {
+        let (english, rest) = match line.strip_prefix('"') {
+            Some(quoted) => {
+                let (english, rest) = unquote(quoted).ok_or(LineIssue::UnclosedQuote)?;
+                let trimmed = rest.trim_start();
+                let rest = match trimmed.strip_prefix(|c| self.matches(c)) {
+                    Some(rest) => rest,
+                    // 空格与自动模式下，引号后的空白就是分隔
+                    None if matches!(self, Separator::Smart | Separator::Space) && trimmed.len() < rest.len() => trimmed,
+                    None => return Err(LineIssue::NoSeparator),
+                };
+                (english, rest.to_string())
+            }
+            None => {
+                let (english, rest) = match line.find(|c| self.matches(c)) {
+                    Some(pos) => {
+                        let sep_len = line[pos..].chars().next().map_or(0, char::len_utf8);
+                        (&line[..pos], &line[pos + sep_len..])
+                    }
+                    None if self == Separator::Smart => split_at_script_boundary(line).ok_or(LineIssue::NoSeparator)?,
+                    None => return Err(LineIssue::NoSeparator),
+                };
+                (english.trim().to_string(), rest.to_string())
+            }
+        };
+        let rest = rest.trim();
+        let chinese = match rest.strip_prefix('"').and_then(unquote) {
+            Some((chinese, tail)) if tail.trim().is_empty() => chinese,
+            _ => rest.to_string(),
+        };
+        if english.is_empty() || chinese.is_empty() {
+            return Err(LineIssue::MissingSide);
+        }
+        Ok((english, chinese))
+    }
+
+    // 把英文与释义写成一行；按该分隔符读回会出错时给英文加上引号
+    pub fn join(self, english: &str, chinese: &str) -> String {
+        let line = format!("{}{}{}", english, self.joiner(), chinese);
+        if self.split_line(&line) == Ok((english.to_string(), chinese.to_string())) {
+            line
+        } else {
+            format!("\"{}\"{}{}", english.replace('"', "\"\""), self.joiner(), chinese)
+        }
+    }
+}
+
+// 读取开头引号之后的内容，返回 (引号中的文本, 右引号之后的部分)；没有右引号时返回 None
+fn unquote(text: &str) -> Option<(String, &str)> {
+    let mut value = String::new();
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c != '"' {
+            value.push(c);
+        } else if text[i + 1..].starts_with('"') {
+            value.push('"');
+            chars.next();
+        } else {
+            return Some((value, &text[i + 1..]));
+        }
+    }
+    None
+}
+
+// 中日韩文字、假名、谚文与全角标点
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{2E80}'..='\u{9FFF}' | '\u{AC00}'..='\u{D7AF}' | '\u{F900}'..='\u{FAFF}' | '\u{FF00}'..='\u{FFEF}' | '\u{20000}'..='\u{2FA1F}')
+        && !c.is_whitespace()
+}
+
+// 英文与释义之间常见的分隔标点，自动模式下从两边去掉
+const SMART_PUNCTUATION: [char; 8] = [',', '，', '=', '＝', '|', '｜', ':', '：'];
+
+// 词性缩写（n. vt. adj. 等）属于释义
+fn is_part_of_speech(token: &str) -> bool {
+    token.len() <= 5
+        && token.ends_with('.')
+        && token.chars().all(|c| c.is_ascii_lowercase() || c == '.' || c == '/' || c == '&')
+}
+
+// 自动模式：在第一个含中日韩文字的词之前分开，例如 “ice cream 冰淇淋”“T-shirt T恤”“apple苹果”；
+// 行以中日韩文字开头或不含中日韩文字时按第一个空格分开
+fn split_at_script_boundary(line: &str) -> Option<(&str, &str)> {
+    let split = match line.find(is_cjk) {
+        Some(pos) if pos > 0 => line[..pos].rfind(char::is_whitespace).map_or(pos, |ws| ws + 1),
+        _ => line.find(char::is_whitespace)?,
+    };
+    let (mut english, mut chinese) = (line[..split].trim_end(), &line[split..]);
+    let stripped = english.trim_end_matches(SMART_PUNCTUATION).trim_end();
+    if stripped.len() < english.len() {
+        english = stripped;
+    } else if let Some((head, last)) = english.rsplit_once(char::is_whitespace) {
+        if is_part_of_speech(last) {
+            english = head.trim_end();
+            chinese = &line[english.len()..];
+        }
+    }
+    Some((english, chinese.trim_start().trim_start_matches(SMART_PUNCTUATION)))
+}
+
+// 详细信息行的标签，写在单词行之后并缩进，例如：
+//   apple 苹果
+//     音标 ˈæpl
+//     例句 I ate an apple. = 我吃了一个苹果。
+const PHONETIC: &str = "音标";
+const PART_OF_SPEECH: &str = "词性";
+const MEANING: &str = "释义";
+const EXAMPLE: &str = "例句";
+const NOTES: &str = "笔记";
+const TAGS: &str = "标签";
+// 例句与翻译之间的分隔
+const TRANSLATION_SEPARATOR: &str = " = ";
+
+// 缩进且以详细信息标签开头的行，返回 (标签, 内容)
+fn detail_line(raw: &str) -> Option<(&'static str, &str)> {
+    if !raw.starts_with([' ', '\t']) {
+        return None;
+    }
+    let line = raw.trim();
+    [PHONETIC, PART_OF_SPEECH, MEANING, EXAMPLE, NOTES, TAGS].into_iter().find_map(|label| {
+        let rest = line.strip_prefix(label)?;
+        (rest.is_empty() || rest.starts_with(char::is_whitespace)).then(|| (label, rest.trim()))
+    })
+}
+
+fn add_detail(word: &mut Word, label: &str, value: &str) {
+    if value.is_empty() {
+        return;
+    }
+    match label {
+        PHONETIC => word.phonetic = value.to_string(),
+        PART_OF_SPEECH => word.part_of_speech = value.to_string(),
+        MEANING => word.meanings.push(value.to_string()),
+        EXAMPLE => {
+            let (sentence, translation) = value.split_once(TRANSLATION_SEPARATOR).unwrap_or((value, ""));
+            word.examples.push(Example { sentence: sentence.trim().to_string(), translation: translation.trim().to_string() });
+        }
+        NOTES => {
+            if !word.notes.is_empty() {
+                word.notes.push('\n');
+            }
+            word.notes.push_str(value);
+        }
+        _ => word.tags.extend(value.split([' ', ',', '，']).filter(|t| !t.is_empty()).map(str::to_string)),
+    }
+}
+
+// 文本中某一行的问题
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineIssue {
+    NoSeparator,       // 找不到英文与释义之间的分隔符，该行被忽略
+    MissingSide,       // 分隔符一侧为空，该行被忽略
+    UnclosedQuote,     // 英文的引号没有闭合，该行被忽略
+    DetailWithoutWord, // 详细信息行之前没有单词，该行被忽略
+    Duplicate(usize),  // 与第几行的单词重复（英文与释义都相同），仍会保存
+}
+
+impl LineIssue {
+    pub fn message(&self) -> String {
+        match self {
+            LineIssue::NoSeparator => "找不到英文与释义之间的分隔符".to_string(),
+            LineIssue::MissingSide => "缺少英文或释义".to_string(),
+            LineIssue::UnclosedQuote => "引号没有闭合".to_string(),
+            LineIssue::DetailWithoutWord => "详细信息前面没有单词".to_string(),
+            LineIssue::Duplicate(first) => format!("与第 {} 行重复", first),
+        }
+    }
+
+    // 该行是否会在保存时被丢弃
+    pub fn is_rejected(&self) -> bool {
+        !matches!(self, LineIssue::Duplicate(_))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineDiagnostic {
+    pub line: usize, // 从 1 开始的行号
+    pub text: String,
+    pub issue: LineIssue,
+}
+
+// 解析结果与逐行的问题
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseReport {
+    pub words: Vec<Word>,
+    pub diagnostics: Vec<LineDiagnostic>,
+}
+
+impl ParseReport {
+    // 保存时会被丢弃的行数
+    pub fn rejected_count(&self) -> usize {
+        self.diagnostics.iter().filter(|d| d.issue.is_rejected()).count()
+    }
+}
+
+// 每行一个单词，按 separator 分为英文与中文；空行会被跳过，格式不对的行记入 diagnostics。
+// 单词行之后缩进的“音标 …”“例句 …”等行是该单词的详细信息
+pub fn parse_words_with_report(text: &str, group: &str, separator: Separator) -> ParseReport {
+    let mut report = ParseReport::default();
+    let mut first_lines: HashMap<(String, String), usize> = HashMap::new();
+
+    for (idx, raw) in text.lines().enumerate() {
+        let mut issue = |issue| report.diagnostics.push(LineDiagnostic { line: idx + 1, text: raw.trim().to_string(), issue });
+        if let Some((label, value)) = detail_line(raw) {
+            match report.words.last_mut() {
+                Some(word) => add_detail(word, label, value),
+                None => issue(LineIssue::DetailWithoutWord),
+            }
+            continue;
+        }
+        let line = raw.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let (english, chinese) = match separator.split_line(line) {
+            Ok(pair) => pair,
+            Err(e) => {
+                issue(e);
+                continue;
+            }
+        };
+        let key = (quiz::normalize(&english), quiz::normalize(&chinese));
+        if let Some(&first) = first_lines.get(&key) {
+            issue(LineIssue::Duplicate(first));
+        } else {
+            first_lines.insert(key, idx + 1);
+        }
+        report.words.push(Word::new(&english, &chinese, group));
+    }
+
+    report
+}
+
+pub fn parse_words_from_text(text: &str, group: &str, separator: Separator) -> Vec<Word> {
+    parse_words_with_report(text, group, separator).words
+}
+
+// 把单词写成可编辑的文本，按同一分隔符与 parse_words_from_text 互逆
+pub fn format_words_as_text(words: &[Word], separator: Separator) -> String {
+    let mut lines = Vec::new();
+    for word in words {
+        lines.push(separator.join(&word.english, &word.chinese));
+        let mut detail = |label: &str, value: &str| {
+            if !value.is_empty() {
+                lines.push(format!("  {} {}", label, value));
+            }
+        };
+        detail(PHONETIC, &word.phonetic);
+        detail(PART_OF_SPEECH, &word.part_of_speech);
+        for meaning in &word.meanings {
+            detail(MEANING, meaning);
+        }
+        for example in &word.examples {
+            if example.translation.is_empty() {
+                detail(EXAMPLE, &example.sentence);
+            } else {
+                detail(EXAMPLE, &format!("{}{}{}", example.sentence, TRANSLATION_SEPARATOR, example.translation));
+            }
+        }
+        for note in word.notes.lines() {
+            detail(NOTES, note);
+        }
+        detail(TAGS, &word.tags.join(" "));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(words: &[Word]) -> Vec<(&str, &str)> {
+        words.iter().map(|w| (w.english.as_str(), w.chinese.as_str())).collect()
+    }
+
+    #[test]
+    fn splits_on_first_space() {
+        let words = parse_words_from_text("apple 苹果\nbanana 香蕉", "英语", Separator::Space);
+        assert_eq!(pairs(&words), vec![("apple", "苹果"), ("banana", "香蕉")]);
+        assert!(words.iter().all(|w| w.group == "英语"));
+    }
+
+    #[test]
+    fn meaning_keeps_inner_spaces() {
+        let words = parse_words_from_text("run 跑 （动词）", "英语", Separator::Space);
+        assert_eq!(pairs(&words), vec![("run", "跑 （动词）")]);
+    }
+
+    #[test]
+    fn trims_surrounding_whitespace() {
+        let words = parse_words_from_text("   apple    苹果  \r\n\tpear 梨\t", "英语", Separator::Space);
+        assert_eq!(pairs(&words), vec![("apple", "苹果"), ("pear", "梨")]);
+    }
+
+    #[test]
+    fn skips_blank_and_malformed_lines() {
+        let text = "\n\napple\n苹果\napple \n   \npear 梨\n";
+        let words = parse_words_from_text(text, "英语", Separator::Space);
+        assert_eq!(pairs(&words), vec![("pear", "梨")]);
+    }
+
+    #[test]
+    fn indented_detail_lines_belong_to_previous_word() {
+        let text = "apple 苹果\n  音标 ˈæpl\n  词性 n.\n  释义 苹果树\n  例句 I ate an apple. = 我吃了一个苹果。\n\
+                    \t例句 An apple a day.\n  笔记 常见水果\n  笔记 可数名词\n  标签 水果，食物 初级\npear 梨";
+        let words = parse_words_from_text(text, "英语", Separator::Space);
+        assert_eq!(pairs(&words), vec![("apple", "苹果"), ("pear", "梨")]);
+        let apple = &words[0];
+        assert_eq!(apple.phonetic, "ˈæpl");
+        assert_eq!(apple.part_of_speech, "n.");
+        assert_eq!(apple.meanings, vec!["苹果树"]);
+        assert_eq!(
+            apple.examples,
+            vec![
+                Example { sentence: "I ate an apple.".to_string(), translation: "我吃了一个苹果。".to_string() },
+                Example { sentence: "An apple a day.".to_string(), translation: String::new() },
+            ]
+        );
+        assert_eq!(apple.notes, "常见水果\n可数名词");
+        assert_eq!(apple.tags, vec!["水果", "食物", "初级"]);
+        assert!(!words[1].has_details());
+    }
+
+    #[test]
+    fn detail_labels_need_indent_and_a_word() {
+        // 不缩进时按普通单词行处理；第一个单词之前的详细信息行被跳过
+        let words = parse_words_from_text("  音标 ˈæpl\n音标 phonetic\n  音标学 phonetics", "英语", Separator::Space);
+        assert_eq!(pairs(&words), vec![("音标", "phonetic"), ("音标学", "phonetics")]);
+        assert!(words[0].phonetic.is_empty());
+    }
+
+    #[test]
+    fn formatted_text_parses_back() {
+        let mut apple = Word::new("apple", "苹果", "英语");
+        apple.phonetic = "ˈæpl".to_string();
+        apple.meanings = vec!["苹果树".to_string(), "苹果公司".to_string()];
+        apple.examples = vec![Example { sentence: "I ate an apple.".to_string(), translation: "我吃了一个苹果。".to_string() }];
+        apple.notes = "第一行\n第二行".to_string();
+        apple.tags = vec!["水果".to_string(), "初级".to_string()];
+        let words = vec![apple, Word::new("run", "跑 （动词）", "英语")];
+        let mut parsed = parse_words_from_text(&format_words_as_text(&words, Separator::Space), "英语", Separator::Space);
+        // 文本中不含标识，保存时由单词表按内容沿用
+        for (p, w) in parsed.iter_mut().zip(&words) {
+            p.id = w.id;
+        }
+        assert_eq!(parsed, words);
+    }
+
+    #[test]
+    fn rejected_lines_and_duplicates_are_reported() {
+        let text = "  音标 ˈæpl\napple 苹果\n\napple\n苹果\nApple  苹果\npear 梨";
+        let report = parse_words_with_report(text, "英语", Separator::Space);
+        assert_eq!(pairs(&report.words), vec![("apple", "苹果"), ("Apple", "苹果"), ("pear", "梨")]);
+        let issues: Vec<_> = report.diagnostics.iter().map(|d| (d.line, d.issue.clone())).collect();
+        assert_eq!(
+            issues,
+            vec![
+                (1, LineIssue::DetailWithoutWord),
+                (4, LineIssue::NoSeparator),
+                (5, LineIssue::NoSeparator),
+                (6, LineIssue::Duplicate(2)),
+            ]
+        );
+        assert_eq!(report.diagnostics[1].text, "apple");
+        assert_eq!(report.rejected_count(), 3);
+        assert_eq!(report.diagnostics[3].issue.message(), "与第 2 行重复");
+    }
+
+    #[test]
+    fn smart_mode_splits_phrases_at_the_script_boundary() {
+        let text = "look up 查阅\nice cream 冰淇淋\nT-shirt T恤\napple苹果\nrun v. 跑\ngood-bye = 再见\nhello，你好\n\
+                    音标 phonetic\nwell done\t做得好\n\"a, b\" 甲，乙";
+        let words = parse_words_from_text(text, "英语", Separator::Smart);
+        assert_eq!(
+            pairs(&words),
+            vec![
+                ("look up", "查阅"),
+                ("ice cream", "冰淇淋"),
+                ("T-shirt", "T恤"),
+                ("apple", "苹果"),
+                ("run", "v. 跑"),
+                ("good-bye", "再见"),
+                ("hello", "你好"),
+                ("音标", "phonetic"),
+                ("well done", "做得好"),
+                ("a, b", "甲，乙"),
+            ]
+        );
+    }
+
+    #[test]
+    fn explicit_separators_accept_full_width_forms_and_quotes() {
+        let split = |sep: Separator, line: &str| sep.split_line(line);
+        let ok = |e: &str, c: &str| Ok((e.to_string(), c.to_string()));
+        assert_eq!(split(Separator::Tab, "look up\t查阅 查找"), ok("look up", "查阅 查找"));
+        assert_eq!(split(Separator::Equals, "look up ＝ 查阅"), ok("look up", "查阅"));
+        assert_eq!(split(Separator::Pipe, "a | b｜c"), ok("a", "b｜c"));
+        assert_eq!(split(Separator::Comma, "apple，苹果, 苹果树"), ok("apple", "苹果, 苹果树"));
+        assert_eq!(split(Separator::Comma, "\"1,000\", 一千"), ok("1,000", "一千"));
+        assert_eq!(split(Separator::Space, "\"say \"\"hi\"\"\" 打招呼"), ok("say \"hi\"", "打招呼"));
+        assert_eq!(split(Separator::Comma, "\"a, b\", \"甲\""), ok("a, b", "甲"));
+        assert_eq!(split(Separator::Comma, "\"a, b 甲"), Err(LineIssue::UnclosedQuote));
+        assert_eq!(split(Separator::Comma, "\"a\" 甲"), Err(LineIssue::NoSeparator));
+        assert_eq!(split(Separator::Equals, "apple ="), Err(LineIssue::MissingSide));
+        assert_eq!(split(Separator::Tab, "apple 苹果"), Err(LineIssue::NoSeparator));
+    }
+
+    #[test]
+    fn joined_lines_split_back_with_every_separator() {
+        let cases = [("apple", "苹果"), ("look up", "查阅"), ("1,000", "一千"), ("a = b", "甲"), ("say \"hi\"", "打招呼"), ("X光", "X-ray")];
+        for sep in Separator::ALL {
+            for (english, chinese) in cases {
+                assert_eq!(sep.split_line(&sep.join(english, chinese)), Ok((english.to_string(), chinese.to_string())), "{:?}", sep);
+            }
+        }
+        assert_eq!(Separator::Comma.join("look up", "查阅"), "look up, 查阅");
+        assert_eq!(Separator::Space.join("look up", "查阅"), "\"look up\" 查阅");
+    }
+
+    #[test]
+    fn empty_input() {
+        assert!(parse_words_from_text("", "英语", Separator::Space).is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+// 测验相关的答案检查与选项生成
+
+// 拼写测验的判定结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnswerCheck {
+    Exact,        // 完全正确（忽略大小写与多余空白）
+    Close(usize), // 在容错范围内，附编辑距离
+    Wrong,
+}
+
+// 差异视图中的一段字符
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffChar {
+    Same(char),    // 输入与答案一致
+    Extra(char),   // 输入中多出或写错的字符
+    Missing(char), // 答案中缺少的字符
+}
+
+// 忽略大小写、首尾空白，并把连续空白视为一个空格
+pub fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+// 答案可能包含多个释义，如 “颜色；色彩” 或 “color/colour”，任意一个都算对
+pub fn alternatives(expected: &str) -> Vec<String> {
+    let mut alts: Vec<String> = expected
+        .split(['，', ',', '；', ';', '、', '/'])
+        .map(normalize)
+        .filter(|a| !a.is_empty())
+        .collect();
+    let whole = normalize(expected);
+    if !alts.contains(&whole) {
+        alts.push(whole);
+    }
+    alts
+}
+
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+// 返回判定结果与最接近的答案（用于差异视图）
+pub fn check_answer(input: &str, expected: &str, tolerance: usize) -> (AnswerCheck, String) {
+    let input = normalize(input);
+    let (distance, closest) = alternatives(expected)
+        .into_iter()
+        .map(|alt| (edit_distance(&input, &alt), alt))
+        .min_by_key(|(d, _)| *d)
+        .unwrap_or((usize::MAX, String::new()));
+    let check = if distance == 0 {
+        AnswerCheck::Exact
+    } else if distance <= tolerance && closest.chars().count() > tolerance {
+        // 答案本身不长于容错值时不放宽，否则任何输入都会被判为接近
+        AnswerCheck::Close(distance)
+    } else {
+        AnswerCheck::Wrong
+    };
+    (check, closest)
+}
+
+// 基于最长公共子序列的逐字符差异
+pub fn diff_chars(input: &str, expected: &str) -> Vec<DiffChar> {
+    let a: Vec<char> = normalize(input).chars().collect();
+    let b: Vec<char> = expected.chars().collect();
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::new();
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            diff.push(DiffChar::Same(b[j]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(DiffChar::Extra(a[i]));
+            i += 1;
+        } else {
+            diff.push(DiffChar::Missing(b[j]));
+            j += 1;
+        }
+    }
+    diff.extend(a[i..].iter().map(|&c| DiffChar::Extra(c)));
+    diff.extend(b[j..].iter().map(|&c| DiffChar::Missing(c)));
+    diff
+}
+
+// 为选择题挑选干扰项：优先取同一单词表的释义，不足时再从同一分组补充；
+// 干扰项之间以及与正确答案都不重复（忽略大小写与空白）
+pub fn pick_distractors(correct: &str, table_pool: &[String], group_pool: &[String], count: usize) -> Vec<String> {
+    use rand::seq::SliceRandom;
+    let mut rng = rand::thread_rng();
+    let mut seen = vec![normalize(correct)];
+    let mut picked = Vec::new();
+    for pool in [table_pool, group_pool] {
+        let mut candidates: Vec<&String> = pool.iter().collect();
+        candidates.shuffle(&mut rng);
+        for candidate in candidates {
+            if picked.len() == count {
+                return picked;
+            }
+            let key = normalize(candidate);
+            if key.is_empty() || seen.contains(&key) {
+                continue;
+            }
+            seen.push(key);
+            picked.push(candidate.clone());
+        }
+    }
+    picked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn any_alternative_is_accepted() {
+        assert_eq!(check_answer("色彩", "颜色；色彩", 1).0, AnswerCheck::Exact);
+        assert_eq!(check_answer("  Colour ", "color/colour", 0).0, AnswerCheck::Exact);
+    }
+
+    #[test]
+    fn tolerance_allows_small_typos() {
+        assert_eq!(check_answer("aple", "apple", 1), (AnswerCheck::Close(1), "apple".to_string()));
+        assert_eq!(check_answer("aple", "apple", 0).0, AnswerCheck::Wrong);
+        // 答案不长于容错值时不放宽
+        assert_eq!(check_answer("b", "a", 1).0, AnswerCheck::Wrong);
+    }
+
+    #[test]
+    fn diff_marks_extra_and_missing() {
+        let diff = diff_chars("aplle", "apple");
+        let rebuilt: String = diff
+            .iter()
+            .filter_map(|d| match d {
+                DiffChar::Same(c) | DiffChar::Missing(c) => Some(*c),
+                DiffChar::Extra(_) => None,
+            })
+            .collect();
+        assert_eq!(rebuilt, "apple");
+        assert!(diff.contains(&DiffChar::Missing('p')));
+        assert!(diff.contains(&DiffChar::Extra('l')));
+    }
+
+    #[test]
+    fn distractors_skip_duplicates_of_answer() {
+        let table = vec!["苹果".to_string(), " 苹果 ".to_string(), "梨".to_string()];
+        let group = vec!["梨".to_string(), "香蕉".to_string()];
+        let mut picked = pick_distractors("苹果", &table, &group, 3);
+        picked.sort();
+        assert_eq!(picked, vec!["梨".to_string(), "香蕉".to_string()]);
+    }
+}
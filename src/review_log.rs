@@ -0,0 +1,189 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use uuid::Uuid;
+
+use crate::model::{FlashMemory, WordTable};
+use crate::scheduler::Grade;
+
+// JSON 存储下的复习记录文件，与 words.json 放在同一目录，每行一条 JSON
+pub const REVIEW_LOG_FILE: &str = "reviews.jsonl";
+
+// 复习历史对应的单词：有单词标识时按标识，早期没有标识的记录按 (单词表, 英文, 中文)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WordKey {
+    Id(Uuid),
+    Name(String, String, String),
+}
+
+// 产生记录的学习方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum StudyMode {
+    Flash,  // 普通闪记，只展示不评分
+    #[default]
+    Review, // 复习模式，显示释义后评分
+    Typing, // 拼写测验，按答案正误评分
+    Choice, // 选择题，按答案正误评分
+}
+
+// 一次卡片展示
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewRecord {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word_id: Option<Uuid>, // 早期记录没有，读取时按名称补上
+    pub group: String,
+    pub table: String,
+    pub english: String,
+    pub chinese: String,
+    #[serde(default)]
+    pub mode: StudyMode,
+    pub grade: Option<Grade>,     // 未评分的展示为 None
+    #[serde(default)]
+    pub reveal_ms: Option<u64>,   // 卡片出现到显示释义的时间
+    #[serde(default)]
+    pub response_ms: Option<u64>, // 卡片出现到评分的时间
+    pub reviewed_at: DateTime<Local>,
+}
+
+// 已读取的复习记录，统计、排期与导出都以它为准；写入由存储后端负责
+#[derive(Default)]
+pub struct ReviewLog {
+    records: Vec<ReviewRecord>,
+}
+
+// 当天的学习概况
+pub struct DailySummary {
+    pub cards: usize,   // 展示的卡片数
+    pub graded: usize,  // 评分次数
+    pub correct: usize, // 评分中非“重来”的次数
+}
+
+impl ReviewLog {
+    pub fn new(records: Vec<ReviewRecord>) -> Self {
+        ReviewLog { records }
+    }
+
+    pub fn records(&self) -> &[ReviewRecord] {
+        &self.records
+    }
+
+    // 记录已写入存储后加入内存
+    pub fn push(&mut self, record: ReviewRecord) {
+        self.records.push(record);
+    }
+
+    // 为没有单词标识的早期记录按 (分组, 单词表, 英文, 中文) 补上标识；返回补上的条数。
+    // 名称相同的重复单词取第一个，已删除的单词保持为空
+    pub fn link_word_ids(&mut self, memory: &FlashMemory) -> usize {
+        let mut linked = 0;
+        for record in self.records.iter_mut().filter(|r| r.word_id.is_none()) {
+            // 早期的单词表都直接位于分组下，名称不重复
+            let word = memory
+                .find_word_table(&record.group, None, &record.table)
+                .and_then(|t| t.words.iter().find(|w| w.english == record.english && w.chinese == record.chinese));
+            if let Some(word) = word {
+                record.word_id = Some(word.id);
+                linked += 1;
+            }
+        }
+        linked
+    }
+
+    // 若干单词表中的单词当天的学习概况
+    pub fn daily_summary(&self, tables: &[&WordTable], day: NaiveDate) -> DailySummary {
+        let ids: HashSet<Uuid> = tables.iter().flat_map(|t| t.words.iter().map(|w| w.id)).collect();
+        let mut summary = DailySummary { cards: 0, graded: 0, correct: 0 };
+        for r in self.records.iter().filter(|r| {
+            r.word_id.is_some_and(|id| ids.contains(&id)) && r.reviewed_at.date_naive() == day
+        }) {
+            summary.cards += 1;
+            if let Some(grade) = r.grade {
+                summary.graded += 1;
+                if grade != Grade::Again {
+                    summary.correct += 1;
+                }
+            }
+        }
+        summary
+    }
+
+    // 分组内每个单词按时间排序的复习历史，用于重算复习状态与参数优化。
+    // 按分组中现有单词的标识选取记录，分组改名后仍能找到；没有标识的早期记录（单词已删除）只能按记录的分组名
+    pub fn histories_in_group(&self, memory: &FlashMemory, group: &str) -> HashMap<WordKey, Vec<(NaiveDate, Grade)>> {
+        let ids: HashSet<Uuid> = memory
+            .get_word_tables_in_group(group)
+            .into_iter()
+            .flatten()
+            .flat_map(|t| t.words.iter().map(|w| w.id))
+            .collect();
+        let mut records: Vec<&ReviewRecord> = self.records
+            .iter()
+            .filter(|r| r.grade.is_some())
+            .filter(|r| match r.word_id {
+                Some(id) => ids.contains(&id),
+                None => r.group == group,
+            })
+            .collect();
+        records.sort_by_key(|r| r.reviewed_at);
+        let mut histories: HashMap<WordKey, Vec<(NaiveDate, Grade)>> = HashMap::new();
+        for r in records {
+            let Some(grade) = r.grade else { continue };
+            let key = match r.word_id {
+                Some(id) => WordKey::Id(id),
+                None => WordKey::Name(r.table.clone(), r.english.clone(), r.chinese.clone()),
+            };
+            histories.entry(key).or_default().push((r.reviewed_at.date_naive(), grade));
+        }
+        histories
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Word;
+
+    fn record(table: &str, english: &str, chinese: &str) -> ReviewRecord {
+        ReviewRecord {
+            word_id: None,
+            group: "英语".to_string(),
+            table: table.to_string(),
+            english: english.to_string(),
+            chinese: chinese.to_string(),
+            mode: StudyMode::Review,
+            grade: Some(Grade::Good),
+            reveal_ms: None,
+            response_ms: None,
+            reviewed_at: Local::now(),
+        }
+    }
+
+    #[test]
+    fn early_records_are_linked_by_name() {
+        let mut memory = FlashMemory::new();
+        let lesson = memory.create_word_table("英语", None, "第一课").unwrap();
+        memory
+            .add_words_to_table("英语", lesson, vec![Word::new("apple", "苹果", "英语"), Word::new("apple", "苹果", "英语")])
+            .unwrap();
+        let first = memory.get_word_table("英语", lesson).unwrap().words[0].id;
+        let known = Uuid::new_v4();
+
+        let mut log = ReviewLog::new(vec![
+            record("第一课", "apple", "苹果"),
+            record("第一课", "grape", "葡萄"),
+            ReviewRecord { word_id: Some(known), ..record("第二课", "pear", "梨") },
+        ]);
+        assert_eq!(log.link_word_ids(&memory), 1);
+        let ids: Vec<_> = log.records().iter().map(|r| r.word_id).collect();
+        assert_eq!(ids, vec![Some(first), None, Some(known)]);
+        assert_eq!(log.link_word_ids(&memory), 0);
+
+        // 第二课的 pear 不在分组中，不计入
+        let histories = log.histories_in_group(&memory, "英语");
+        assert_eq!(histories.len(), 2);
+        assert!(histories.contains_key(&WordKey::Id(first)));
+        assert!(histories.contains_key(&WordKey::Name("第一课".to_string(), "grape".to_string(), "葡萄".to_string())));
+    }
+}
@@ -0,0 +1,283 @@
+use chrono::{Duration, NaiveDate};
+
+use super::{Grade, Schedule, Scheduler};
+
+// FSRS-4.5 默认参数
+pub const DEFAULT_WEIGHTS: [f64; 17] = [
+    0.4872, 1.4003, 3.7145, 13.8206, 5.1618, 1.2298, 0.8975, 0.031, 1.6474, 0.1367, 1.0461,
+    2.1072, 0.0793, 0.3246, 1.587, 0.2272, 2.8755,
+];
+
+// 参数取值范围，优化时超出范围的值会被截断
+const WEIGHT_BOUNDS: [(f64, f64); 17] = [
+    (0.1, 100.0), (0.1, 100.0), (0.1, 100.0), (0.1, 100.0),
+    (1.0, 10.0), (0.1, 5.0), (0.1, 5.0), (0.0, 0.5),
+    (0.0, 3.0), (0.1, 0.8), (0.01, 2.5), (0.5, 5.0),
+    (0.01, 0.2), (0.01, 0.9), (0.01, 2.0), (0.0, 1.0), (1.0, 10.0),
+];
+
+const DECAY: f64 = -0.5;
+const FACTOR: f64 = 19.0 / 81.0;
+
+// 参与优化所需的最少有效复习次数
+const MIN_SAMPLES: usize = 50;
+
+// FSRS（稳定性 / 难度 / 可提取性）记忆模型
+pub struct Fsrs {
+    pub weights: [f64; 17],
+    pub target_retention: f64,
+}
+
+fn rating(grade: Grade) -> f64 {
+    match grade {
+        Grade::Again => 1.0,
+        Grade::Hard => 2.0,
+        Grade::Good => 3.0,
+        Grade::Easy => 4.0,
+    }
+}
+
+// 距上次复习 elapsed 天后的回忆概率
+fn retrievability(elapsed: f64, stability: f64) -> f64 {
+    (1.0 + FACTOR * elapsed / stability).powf(DECAY)
+}
+
+fn init_stability(w: &[f64; 17], g: f64) -> f64 {
+    w[g as usize - 1].max(0.1)
+}
+
+fn init_difficulty(w: &[f64; 17], g: f64) -> f64 {
+    (w[4] - (g - 3.0) * w[5]).clamp(1.0, 10.0)
+}
+
+fn next_difficulty(w: &[f64; 17], d: f64, g: f64) -> f64 {
+    let next = d - w[6] * (g - 3.0);
+    // 向初始难度均值回归
+    (w[7] * init_difficulty(w, 3.0) + (1.0 - w[7]) * next).clamp(1.0, 10.0)
+}
+
+fn next_stability(w: &[f64; 17], d: f64, s: f64, r: f64, g: f64) -> f64 {
+    if g == 1.0 {
+        let forget = w[11] * d.powf(-w[12]) * ((s + 1.0).powf(w[13]) - 1.0) * (w[14] * (1.0 - r)).exp();
+        forget.min(s).max(0.1)
+    } else {
+        let hard_penalty = if g == 2.0 { w[15] } else { 1.0 };
+        let easy_bonus = if g == 4.0 { w[16] } else { 1.0 };
+        s * (w[8].exp() * (11.0 - d) * s.powf(-w[9]) * ((w[10] * (1.0 - r)).exp() - 1.0) * hard_penalty * easy_bonus + 1.0)
+    }
+}
+
+// 记忆状态单步更新，返回 (稳定性, 难度)
+fn step(w: &[f64; 17], state: Option<(f64, f64)>, elapsed: f64, g: f64) -> (f64, f64) {
+    match state {
+        None => (init_stability(w, g), init_difficulty(w, g)),
+        Some((s, d)) => {
+            let r = retrievability(elapsed, s);
+            (next_stability(w, d, s, r, g), next_difficulty(w, d, g))
+        }
+    }
+}
+
+impl Fsrs {
+    // 达到目标保持率对应的间隔天数
+    pub fn next_interval(&self, stability: f64) -> u32 {
+        let interval = stability / FACTOR * (self.target_retention.powf(1.0 / DECAY) - 1.0);
+        interval.round().clamp(1.0, 36500.0) as u32
+    }
+}
+
+impl Scheduler for Fsrs {
+    fn review(&self, schedule: &mut Schedule, grade: Grade, today: NaiveDate) {
+        // 没有 FSRS 状态（新词或由 SM-2 切换而来）时按首次复习处理
+        let state = schedule.last_review
+            .filter(|_| schedule.stability > 0.0)
+            .map(|_| (schedule.stability, schedule.difficulty));
+        let elapsed = schedule.last_review.map_or(0.0, |last| (today - last).num_days().max(0) as f64);
+        let (stability, difficulty) = step(&self.weights, state, elapsed, rating(grade));
+        schedule.stability = stability;
+        schedule.difficulty = difficulty;
+        schedule.repetitions = if grade == Grade::Again { 0 } else { schedule.repetitions + 1 };
+        schedule.interval = self.next_interval(stability);
+        schedule.last_review = Some(today);
+        schedule.due = Some(today + Duration::days(schedule.interval as i64));
+    }
+}
+
+pub struct OptimizeResult {
+    pub weights: [f64; 17],
+    pub loss_before: f64, // 优化前平均对数损失
+    pub loss_after: f64,  // 优化后平均对数损失
+    pub samples: usize,   // 参与拟合的复习次数
+}
+
+// 用给定参数重放复习历史，返回 (对数损失之和, 样本数)
+fn log_loss(w: &[f64; 17], histories: &[Vec<(NaiveDate, Grade)>]) -> (f64, usize) {
+    let mut total = 0.0;
+    let mut count = 0;
+    for history in histories {
+        let mut state: Option<(f64, f64)> = None;
+        let mut last: Option<NaiveDate> = None;
+        for &(date, grade) in history {
+            let elapsed = last.map_or(0.0, |l| (date - l).num_days().max(0) as f64);
+            if let Some((s, _)) = state {
+                // 同一天内的重复复习不参与预测
+                if elapsed > 0.0 {
+                    let r = retrievability(elapsed, s).clamp(1e-4, 1.0 - 1e-4);
+                    let recalled = grade != Grade::Again;
+                    total -= if recalled { r.ln() } else { (1.0 - r).ln() };
+                    count += 1;
+                }
+            }
+            state = Some(step(w, state, elapsed, rating(grade)));
+            last = Some(date);
+        }
+    }
+    (total, count)
+}
+
+fn clamp_weights(w: &mut [f64; 17]) {
+    for (value, (lo, hi)) in w.iter_mut().zip(WEIGHT_BOUNDS) {
+        *value = value.clamp(lo, hi);
+    }
+}
+
+// 根据用户自己的复习历史拟合 FSRS 参数（数值梯度 + Adam）
+pub fn optimize(histories: &[Vec<(NaiveDate, Grade)>], initial: [f64; 17]) -> Result<OptimizeResult, &'static str> {
+    // 先把初始参数限制在范围内，优化前的损失与返回的参数都以限制后的参数为准
+    let mut w = initial;
+    clamp_weights(&mut w);
+    let (loss, samples) = log_loss(&w, histories);
+    if samples < MIN_SAMPLES {
+        return Err("复习记录不足，至少需要 50 次隔天复习");
+    }
+    let n = samples as f64;
+    let loss_before = loss / n;
+    let mut best = (loss_before, w);
+    let (lr, beta1, beta2, eps) = (0.02, 0.9, 0.999, 1e-8);
+    let mut m = [0.0; 17];
+    let mut v = [0.0; 17];
+    for t in 1..=150 {
+        let mut grad = [0.0; 17];
+        for i in 0..17 {
+            let h = 1e-4 * w[i].abs().max(1.0);
+            let mut plus = w;
+            plus[i] += h;
+            let mut minus = w;
+            minus[i] -= h;
+            grad[i] = (log_loss(&plus, histories).0 - log_loss(&minus, histories).0) / (2.0 * h * n);
+        }
+        for i in 0..17 {
+            m[i] = beta1 * m[i] + (1.0 - beta1) * grad[i];
+            v[i] = beta2 * v[i] + (1.0 - beta2) * grad[i] * grad[i];
+            let m_hat = m[i] / (1.0 - beta1.powi(t));
+            let v_hat = v[i] / (1.0 - beta2.powi(t));
+            w[i] -= lr * m_hat / (v_hat.sqrt() + eps);
+        }
+        clamp_weights(&mut w);
+        let current = log_loss(&w, histories).0 / n;
+        if current < best.0 {
+            best = (current, w);
+        }
+    }
+
+    Ok(OptimizeResult {
+        weights: best.1,
+        loss_before,
+        loss_after: best.0,
+        samples,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: i64) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + Duration::days(d)
+    }
+
+    fn fsrs() -> Fsrs {
+        Fsrs { weights: DEFAULT_WEIGHTS, target_retention: 0.9 }
+    }
+
+    #[test]
+    fn first_review_uses_initial_stability_and_difficulty() {
+        for (i, grade) in Grade::ALL.into_iter().enumerate() {
+            let mut schedule = Schedule::default();
+            fsrs().review(&mut schedule, grade, day(0));
+            assert_eq!(schedule.stability, DEFAULT_WEIGHTS[i]);
+            let expected = (DEFAULT_WEIGHTS[4] - (i as f64 - 2.0) * DEFAULT_WEIGHTS[5]).clamp(1.0, 10.0);
+            assert!((schedule.difficulty - expected).abs() < 1e-9);
+            // 目标保持率 0.9 时间隔约等于稳定性
+            assert_eq!(schedule.interval, DEFAULT_WEIGHTS[i].round().max(1.0) as u32);
+            assert_eq!(schedule.due, Some(day(schedule.interval as i64)));
+        }
+    }
+
+    #[test]
+    fn later_grades_move_stability_and_difficulty() {
+        let mut first = Schedule::default();
+        fsrs().review(&mut first, Grade::Good, day(0));
+        let after = |grade| {
+            let mut schedule = first.clone();
+            fsrs().review(&mut schedule, grade, day(4));
+            schedule
+        };
+        let [again, hard, good, easy] = Grade::ALL.map(after);
+        // 忘记后稳定性下降、难度上升，连续答对次数归零
+        assert!(again.stability < first.stability);
+        assert!(again.difficulty > first.difficulty);
+        assert_eq!(again.repetitions, 0);
+        // 回忆成功时稳定性上升，评分越高上升越多
+        assert!(first.stability < hard.stability && hard.stability < good.stability && good.stability < easy.stability);
+        assert!(hard.difficulty > good.difficulty && good.difficulty > easy.difficulty);
+        assert!((good.difficulty - first.difficulty).abs() < 1e-9);
+        assert_eq!(good.repetitions, 2);
+        // 目标保持率越高，间隔越短
+        let mut strict = first.clone();
+        Fsrs { weights: DEFAULT_WEIGHTS, target_retention: 0.97 }.review(&mut strict, Grade::Good, day(4));
+        assert!(strict.interval < good.interval);
+    }
+
+    // 20 个单词、每个 4 次隔天复习：大多记得很牢，少数在第三次复习时忘记
+    fn synthetic_histories() -> Vec<Vec<(NaiveDate, Grade)>> {
+        (0..20)
+            .map(|i| {
+                let mut date = 0;
+                let mut history = vec![(day(date), Grade::Good)];
+                for (k, gap) in [3, 10, 30, 60].into_iter().enumerate() {
+                    date += gap + i % 3;
+                    let grade = if k == 2 && i % 5 == 0 { Grade::Again } else { Grade::Good };
+                    history.push((day(date), grade));
+                }
+                history
+            })
+            .collect()
+    }
+
+    #[test]
+    fn optimize_lowers_the_loss() {
+        let histories = synthetic_histories();
+        let result = optimize(&histories, DEFAULT_WEIGHTS).unwrap();
+        assert_eq!(result.samples, 80);
+        assert!(result.loss_after < result.loss_before);
+        assert!((log_loss(&result.weights, &histories).0 / 80.0 - result.loss_after).abs() < 1e-9);
+        assert!(result.weights.iter().zip(WEIGHT_BOUNDS).all(|(w, (lo, hi))| (lo..=hi).contains(w)));
+
+        assert!(optimize(&histories[..10], DEFAULT_WEIGHTS).is_err());
+    }
+
+    #[test]
+    fn loss_before_uses_clamped_weights() {
+        let histories = synthetic_histories();
+        let mut initial = DEFAULT_WEIGHTS;
+        initial[4] = 50.0;
+        initial[9] = 0.0;
+        let result = optimize(&histories, initial).unwrap();
+        let mut clamped = initial;
+        clamp_weights(&mut clamped);
+        assert_eq!(clamped[4], 10.0);
+        assert!((result.loss_before - log_loss(&clamped, &histories).0 / 80.0).abs() < 1e-12);
+        assert!(result.loss_after <= result.loss_before);
+    }
+}
@@ -0,0 +1,180 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+mod fsrs;
+mod sm2;
+
+pub use fsrs::{optimize, Fsrs, OptimizeResult, DEFAULT_WEIGHTS};
+pub use sm2::Sm2;
+
+// 复习评分：重来 / 困难 / 良好 / 简单
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Grade {
+    Again,
+    Hard,
+    Good,
+    Easy,
+}
+
+impl Grade {
+    pub const ALL: [Grade; 4] = [Grade::Again, Grade::Hard, Grade::Good, Grade::Easy];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Grade::Again => "重来",
+            Grade::Hard => "困难",
+            Grade::Good => "良好",
+            Grade::Easy => "简单",
+        }
+    }
+}
+
+// 每个单词的复习状态，SM-2 与 FSRS 共用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Schedule {
+    pub ease: f32,                      // SM-2 难度系数（不低于 1.3）
+    pub interval: u32,                  // 当前间隔天数
+    pub repetitions: u32,               // 连续答对次数
+    pub due: Option<NaiveDate>,         // 下次复习日期，None 表示新词
+    pub stability: f64,                 // FSRS 记忆稳定性（天）
+    pub difficulty: f64,                // FSRS 难度（1-10）
+    pub last_review: Option<NaiveDate>, // 上次复习日期
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Schedule {
+            ease: 2.5,
+            interval: 0,
+            repetitions: 0,
+            due: None,
+            stability: 0.0,
+            difficulty: 0.0,
+            last_review: None,
+        }
+    }
+}
+
+impl Schedule {
+    // 新词或到期（含逾期）的单词需要今天复习
+    pub fn is_due(&self, today: NaiveDate) -> bool {
+        self.due.is_none_or(|due| due <= today)
+    }
+
+    // 上一次复习评为“重来”
+    pub fn last_failed(&self) -> bool {
+        self.last_review.is_some() && self.repetitions == 0
+    }
+}
+
+// 调度算法：根据评分更新单词的复习状态
+pub trait Scheduler {
+    fn review(&self, schedule: &mut Schedule, grade: Grade, today: NaiveDate);
+
+    // 从复习记录（按时间排序）重新计算复习状态
+    fn replay(&self, reviews: &[(NaiveDate, Grade)]) -> Schedule {
+        let mut schedule = Schedule::default();
+        for &(date, grade) in reviews {
+            self.review(&mut schedule, grade, date);
+        }
+        schedule
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Algorithm {
+    #[default]
+    Sm2,
+    Fsrs,
+}
+
+impl Algorithm {
+    pub fn label(self) -> &'static str {
+        match self {
+            Algorithm::Sm2 => "SM-2",
+            Algorithm::Fsrs => "FSRS",
+        }
+    }
+}
+
+// 分组的复习设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SchedulerSettings {
+    pub algorithm: Algorithm,
+    pub target_retention: f64,        // FSRS 目标记忆保持率
+    pub fsrs_weights: Option<Vec<f64>>, // 优化后的 FSRS 参数，None 表示使用默认参数
+}
+
+impl Default for SchedulerSettings {
+    fn default() -> Self {
+        SchedulerSettings {
+            algorithm: Algorithm::Sm2,
+            target_retention: 0.9,
+            fsrs_weights: None,
+        }
+    }
+}
+
+impl SchedulerSettings {
+    pub fn scheduler(&self) -> Box<dyn Scheduler> {
+        match self.algorithm {
+            Algorithm::Sm2 => Box::new(Sm2),
+            Algorithm::Fsrs => {
+                let weights = self.fsrs_weights
+                    .as_deref()
+                    .and_then(|w| <[f64; 17]>::try_from(w).ok())
+                    .unwrap_or(DEFAULT_WEIGHTS);
+                Box::new(Fsrs { weights, target_retention: self.target_retention })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+    }
+
+    #[test]
+    fn new_and_overdue_words_are_due() {
+        let mut schedule = Schedule::default();
+        assert!(schedule.is_due(day(1)));
+        assert!(!schedule.last_failed());
+        schedule.due = Some(day(5));
+        assert!(!schedule.is_due(day(4)));
+        assert!(schedule.is_due(day(5)) && schedule.is_due(day(9)));
+    }
+
+    #[test]
+    fn replay_matches_reviewing_in_order() {
+        let reviews = [(day(1), Grade::Good), (day(2), Grade::Hard), (day(8), Grade::Again)];
+        let mut schedule = Schedule::default();
+        for &(date, grade) in &reviews {
+            Sm2.review(&mut schedule, grade, date);
+        }
+        assert_eq!(Sm2.replay(&reviews), schedule);
+        assert_eq!(Sm2.replay(&[]), Schedule::default());
+    }
+
+    #[test]
+    fn settings_choose_algorithm_and_weights() {
+        let reviews = [(day(1), Grade::Good)];
+        let sm2 = SchedulerSettings::default().scheduler().replay(&reviews);
+        assert_eq!((sm2.interval, sm2.stability), (1, 0.0));
+
+        let mut settings = SchedulerSettings { algorithm: Algorithm::Fsrs, ..Default::default() };
+        assert_eq!(settings.scheduler().replay(&reviews).stability, DEFAULT_WEIGHTS[2]);
+        let mut weights = DEFAULT_WEIGHTS.to_vec();
+        weights[2] = 7.0;
+        settings.fsrs_weights = Some(weights);
+        assert_eq!(settings.scheduler().replay(&reviews).stability, 7.0);
+        // 参数个数不对时使用默认参数
+        settings.fsrs_weights = Some(vec![1.0; 3]);
+        assert_eq!(settings.scheduler().replay(&reviews).stability, DEFAULT_WEIGHTS[2]);
+    }
+}
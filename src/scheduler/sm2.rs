@@ -0,0 +1,84 @@
+use chrono::{Duration, NaiveDate};
+
+use super::{Grade, Schedule, Scheduler};
+
+// 经典 SM-2 算法
+pub struct Sm2;
+
+// 映射到 SM-2 的回忆质量（0-5）
+fn quality(grade: Grade) -> u8 {
+    match grade {
+        Grade::Again => 1,
+        Grade::Hard => 3,
+        Grade::Good => 4,
+        Grade::Easy => 5,
+    }
+}
+
+impl Scheduler for Sm2 {
+    fn review(&self, schedule: &mut Schedule, grade: Grade, today: NaiveDate) {
+        let q = quality(grade);
+        if q < 3 {
+            // 回忆失败：重新开始，明天再复习
+            schedule.repetitions = 0;
+            schedule.interval = 1;
+        } else {
+            schedule.interval = match schedule.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => ((schedule.interval as f32) * schedule.ease).round().max(1.0) as u32,
+            };
+            schedule.repetitions += 1;
+        }
+        let diff = (5 - q) as f32;
+        schedule.ease = (schedule.ease + 0.1 - diff * (0.08 + diff * 0.02)).max(1.3);
+        schedule.last_review = Some(today);
+        schedule.due = Some(today + Duration::days(schedule.interval as i64));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+    }
+
+    #[test]
+    fn intervals_grow_with_ease() {
+        let mut schedule = Schedule::default();
+        Sm2.review(&mut schedule, Grade::Good, day(1));
+        assert_eq!((schedule.interval, schedule.repetitions, schedule.due), (1, 1, Some(day(2))));
+        assert_eq!(schedule.ease, 2.5);
+        Sm2.review(&mut schedule, Grade::Good, day(2));
+        assert_eq!((schedule.interval, schedule.repetitions), (6, 2));
+        // 第三次起按上次间隔乘以难度系数
+        Sm2.review(&mut schedule, Grade::Easy, day(8));
+        assert_eq!((schedule.interval, schedule.repetitions), (15, 3));
+        assert!((schedule.ease - 2.6).abs() < 1e-6);
+        Sm2.review(&mut schedule, Grade::Hard, day(23));
+        assert_eq!(schedule.interval, 39);
+        assert!((schedule.ease - 2.46).abs() < 1e-6);
+        assert_eq!(schedule.last_review, Some(day(23)));
+    }
+
+    #[test]
+    fn again_restarts_and_ease_has_a_floor() {
+        let mut schedule = Schedule::default();
+        Sm2.review(&mut schedule, Grade::Good, day(1));
+        Sm2.review(&mut schedule, Grade::Good, day(2));
+        Sm2.review(&mut schedule, Grade::Again, day(8));
+        assert_eq!((schedule.interval, schedule.repetitions, schedule.due), (1, 0, Some(day(9))));
+        assert!((schedule.ease - 1.96).abs() < 1e-6);
+        assert!(schedule.last_failed());
+        for d in 9..12 {
+            Sm2.review(&mut schedule, Grade::Again, day(d));
+        }
+        assert_eq!(schedule.ease, 1.3);
+        // 重新开始后间隔从 1 天、6 天重新增长
+        Sm2.review(&mut schedule, Grade::Good, day(12));
+        Sm2.review(&mut schedule, Grade::Good, day(13));
+        assert_eq!(schedule.interval, 6);
+    }
+}
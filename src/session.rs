@@ -0,0 +1,639 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use uuid::Uuid;
+
+use crate::clock::Clock;
+use crate::model::{FlashMemory, Word};
+use crate::quiz;
+use crate::review_log::StudyMode;
+use crate::scheduler::Grade;
+use crate::timing::FlashTiming;
+
+// 一轮学习中界面发给会话的事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionEvent {
+    Tick,         // 按时钟推进计时
+    Pause,
+    Resume,
+    Reveal,       // 立即显示释义
+    Grade(Grade), // 评分；测验中表示作答结果
+    Skip,         // 下一张；测验作答后进入下一题
+    Back,         // 上一张
+    End,          // 中途结束
+}
+
+// 处理事件时产生的状态变化，界面据此写复习记录、出题或显示结束提示
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transition {
+    CardShown(usize), // 切换到第几张卡片
+    Revealed,
+    Presented(Box<Presentation>),
+    Paused,
+    Resumed,
+    Finished,         // 学完最后一张自动结束（中途结束不产生）
+}
+
+// 一张卡片展示完毕，对应一条复习记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct Presentation {
+    pub word: Word,
+    pub mode: StudyMode,
+    pub grade: Option<Grade>,     // 未评分的展示为 None
+    pub reveal_ms: Option<u64>,   // 卡片出现到显示释义的时间
+    pub response_ms: Option<u64>, // 卡片出现到评分的时间
+}
+
+// 一轮学习的状态机：倒计时、卡片计时、暂停、评分与重排，不依赖界面
+pub struct Session {
+    mode: StudyMode,
+    words: Vec<Word>,
+    index: usize,
+    timing: FlashTiming,
+    clock: Box<dyn Clock>,
+    last_tick: Instant,
+    paused: bool,
+    finished: bool,
+    countdown_remaining: u32, // 倒计时剩余秒数（0 表示已结束）
+    countdown_timer: f32,
+    card_timer: f32,            // 当前卡片的节奏计时，显示释义后可被跳过
+    card_elapsed: f32,          // 当前卡片已展示的时间（不含暂停）
+    revealed_at: Option<f32>,   // 当前卡片显示释义的时刻
+    answer: Option<Grade>,      // 测验中当前题的作答结果
+    correct: usize,
+    answered: usize,
+    missed: Vec<String>,        // 测验中答错过的单词
+}
+
+impl Session {
+    pub fn new(mode: StudyMode, words: Vec<Word>, timing: FlashTiming, clock: Box<dyn Clock>) -> Self {
+        // 测验不计时，直接出题
+        let countdown = if Self::is_timed_mode(mode) { timing.countdown } else { 0 };
+        let last_tick = clock.now();
+        Session {
+            mode,
+            finished: words.is_empty(),
+            words,
+            index: 0,
+            timing,
+            clock,
+            last_tick,
+            paused: false,
+            countdown_remaining: countdown,
+            countdown_timer: 0.0,
+            card_timer: 0.0,
+            card_elapsed: 0.0,
+            revealed_at: None,
+            answer: None,
+            correct: 0,
+            answered: 0,
+            missed: Vec::new(),
+        }
+    }
+
+    fn is_timed_mode(mode: StudyMode) -> bool {
+        matches!(mode, StudyMode::Flash | StudyMode::Review)
+    }
+
+    pub fn mode(&self) -> StudyMode { self.mode }
+    pub fn words(&self) -> &[Word] { &self.words }
+    pub fn index(&self) -> usize { self.index }
+    pub fn current_word(&self) -> Option<&Word> { self.words.get(self.index) }
+    pub fn is_paused(&self) -> bool { self.paused }
+    pub fn is_finished(&self) -> bool { self.finished }
+    pub fn countdown(&self) -> u32 { self.countdown_remaining }
+    pub fn answer(&self) -> Option<Grade> { self.answer }
+    pub fn correct(&self) -> usize { self.correct }
+    pub fn answered(&self) -> usize { self.answered }
+    pub fn missed(&self) -> &[String] { &self.missed }
+
+    // 倒计时结束、正在展示卡片
+    pub fn on_card(&self) -> bool {
+        !self.finished && self.countdown_remaining == 0
+    }
+
+    // 当前卡片的 (正面时长, 背面时长)
+    pub fn durations(&self) -> (f32, f32) {
+        self.current_word()
+            .map(|w| self.timing.durations(&w.english, w.schedule.last_failed()))
+            .unwrap_or((self.timing.front, self.timing.back))
+    }
+
+    // 是否已显示释义：闪记按正面时长，测验在作答后
+    pub fn show_meaning(&self) -> bool {
+        if !self.on_card() {
+            return false;
+        }
+        if Self::is_timed_mode(self.mode) {
+            self.card_timer >= self.durations().0
+        } else {
+            self.answer.is_some()
+        }
+    }
+
+    // 释义展示结束后的空白间隔
+    pub fn in_gap(&self) -> bool {
+        let (front, back) = self.durations();
+        self.mode == StudyMode::Flash && self.on_card() && self.card_timer >= front + back
+    }
+
+    pub fn handle(&mut self, event: SessionEvent) -> Vec<Transition> {
+        let mut out = Vec::new();
+        if self.finished {
+            return out;
+        }
+        self.advance_time(&mut out);
+        if self.finished {
+            return out;
+        }
+        let timed = Self::is_timed_mode(self.mode);
+        match event {
+            SessionEvent::Tick => {}
+            SessionEvent::Pause => {
+                if timed && !self.paused {
+                    self.paused = true;
+                    out.push(Transition::Paused);
+                }
+            }
+            SessionEvent::Resume => {
+                if self.paused {
+                    self.paused = false;
+                    self.last_tick = self.clock.now();
+                    out.push(Transition::Resumed);
+                }
+            }
+            SessionEvent::Reveal => {
+                if timed && self.on_card() {
+                    self.card_timer = self.card_timer.max(self.durations().0);
+                    self.mark_revealed(&mut out);
+                }
+            }
+            SessionEvent::Grade(grade) => {
+                if !self.on_card() {
+                    return out;
+                }
+                if timed {
+                    self.present(Some(grade), &mut out);
+                    // 回忆失败的单词放回队尾，本轮稍后再出现
+                    if grade == Grade::Again {
+                        self.words.push(self.words[self.index].clone());
+                    }
+                    self.advance(&mut out);
+                } else if self.answer.is_none() {
+                    // 测验作答即揭晓答案，停在本题等待进入下一题
+                    self.mark_revealed(&mut out);
+                    self.present(Some(grade), &mut out);
+                    self.answer = Some(grade);
+                    self.answered += 1;
+                    let english = &self.words[self.index].english;
+                    if grade != Grade::Again {
+                        self.correct += 1;
+                    } else if !self.missed.contains(english) {
+                        self.missed.push(english.clone());
+                    }
+                }
+            }
+            SessionEvent::Skip => {
+                if !self.on_card() {
+                    return out;
+                }
+                match self.answer {
+                    Some(Grade::Again) => {
+                        let word = self.words[self.index].clone();
+                        if self.mode == StudyMode::Choice {
+                            // 选择题答错的单词插回几题之后再考一次
+                            let pos = (self.index + 4).min(self.words.len());
+                            self.words.insert(pos, word);
+                        } else {
+                            // 拼错的单词放回队尾
+                            self.words.push(word);
+                        }
+                    }
+                    Some(_) => {}
+                    None => self.present(None, &mut out),
+                }
+                self.advance(&mut out);
+            }
+            SessionEvent::Back => {
+                if timed && self.on_card() {
+                    self.present(None, &mut out);
+                    self.index = self.index.saturating_sub(1);
+                    self.card_timer = 0.0;
+                    out.push(Transition::CardShown(self.index));
+                }
+            }
+            SessionEvent::End => {
+                // 中途结束时当前卡片也算一次展示（已作答的测验题已经记录过）
+                if self.on_card() && self.answer.is_none() {
+                    self.present(None, &mut out);
+                }
+                self.finished = true;
+            }
+        }
+        out
+    }
+
+    // 按时钟推进倒计时与卡片计时；一次推进的时间较长时可连续切换多张卡片
+    fn advance_time(&mut self, out: &mut Vec<Transition>) {
+        let now = self.clock.now();
+        let mut dt = now.saturating_duration_since(self.last_tick).as_secs_f32();
+        self.last_tick = now;
+        if self.paused {
+            return;
+        }
+        while self.countdown_remaining > 0 {
+            let need = 1.0 - self.countdown_timer;
+            if dt < need {
+                self.countdown_timer += dt;
+                return;
+            }
+            dt -= need;
+            self.countdown_timer = 0.0;
+            self.countdown_remaining -= 1;
+            if self.countdown_remaining == 0 {
+                // 倒计时刚结束时，词计时器从 0 开始，避免释义提前出现
+                self.card_timer = 0.0;
+                out.push(Transition::CardShown(self.index));
+            }
+        }
+        while !self.finished {
+            let (front, back) = self.durations();
+            match self.mode {
+                StudyMode::Typing | StudyMode::Choice => {
+                    self.card_elapsed += dt;
+                    return;
+                }
+                StudyMode::Review => {
+                    // 复习模式：显示释义后停住，等待评分再切换
+                    self.reveal_within(dt, front, out);
+                    self.card_timer = (self.card_timer + dt).min(front);
+                    self.card_elapsed += dt;
+                    return;
+                }
+                StudyMode::Flash => {
+                    let total = front + back + self.timing.gap;
+                    let need = (total - self.card_timer).max(0.0);
+                    if dt < need {
+                        self.reveal_within(dt, front, out);
+                        self.card_timer += dt;
+                        self.card_elapsed += dt;
+                        return;
+                    }
+                    self.reveal_within(need, front, out);
+                    self.card_elapsed += need;
+                    dt -= need;
+                    self.present(None, out);
+                    // 播放到最后一个词后自动结束，不循环
+                    self.advance(out);
+                }
+            }
+        }
+    }
+
+    // 接下来的 dt 秒内到达正面时长时，按到达的时刻记录显示释义
+    fn reveal_within(&mut self, dt: f32, front: f32, out: &mut Vec<Transition>) {
+        let to_front = (front - self.card_timer).max(0.0);
+        if dt >= to_front && self.revealed_at.is_none() {
+            self.revealed_at = Some(self.card_elapsed + to_front);
+            out.push(Transition::Revealed);
+        }
+    }
+
+    fn mark_revealed(&mut self, out: &mut Vec<Transition>) {
+        if self.revealed_at.is_none() {
+            self.revealed_at = Some(self.card_elapsed);
+            out.push(Transition::Revealed);
+        }
+    }
+
+    // 记录当前卡片的展示，并重置卡片计时
+    fn present(&mut self, grade: Option<Grade>, out: &mut Vec<Transition>) {
+        let to_ms = |secs: f32| (secs * 1000.0).round() as u64;
+        out.push(Transition::Presented(Box::new(Presentation {
+            word: self.words[self.index].clone(),
+            mode: self.mode,
+            grade,
+            reveal_ms: self.revealed_at.map(to_ms),
+            response_ms: grade.map(|_| to_ms(self.card_elapsed)),
+        })));
+        self.card_elapsed = 0.0;
+        self.revealed_at = None;
+    }
+
+    // 切换到下一张卡片，已是最后一张时结束本轮
+    fn advance(&mut self, out: &mut Vec<Transition>) {
+        self.card_timer = 0.0;
+        self.card_elapsed = 0.0;
+        self.revealed_at = None;
+        self.answer = None;
+        if self.index + 1 < self.words.len() {
+            self.index += 1;
+            out.push(Transition::CardShown(self.index));
+        } else {
+            self.finished = true;
+            out.push(Transition::Finished);
+        }
+    }
+}
+
+// 同时学习多个单词表（整个分组、文件夹或多选）时的一张卡片
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergedCard {
+    pub word: Word,
+    pub tables: Vec<String>, // 出现该单词的单词表名称，按学习顺序
+    pub copies: Vec<Uuid>,   // 与之相同的其他单词的标识，评分时一并更新
+}
+
+// 按顺序合并各单词表中满足 keep 的单词；英文与释义都相同（忽略大小写与多余空白）的单词只保留第一个
+pub fn merge_tables(memory: &FlashMemory, group: &str, tables: &[Uuid], keep: impl Fn(&Word) -> bool) -> Vec<MergedCard> {
+    let mut cards: Vec<MergedCard> = Vec::new();
+    let mut seen: HashMap<(String, String), usize> = HashMap::new();
+    for table in tables.iter().filter_map(|&id| memory.get_word_table(group, id)) {
+        for word in table.words.iter().filter(|w| keep(w)) {
+            let key = (quiz::normalize(&word.english), quiz::normalize(&word.chinese));
+            match seen.get(&key) {
+                Some(&idx) => {
+                    let card = &mut cards[idx];
+                    card.copies.push(word.id);
+                    if !card.tables.contains(&table.name) {
+                        card.tables.push(table.name.clone());
+                    }
+                }
+                None => {
+                    seen.insert(key, cards.len());
+                    cards.push(MergedCard { word: word.clone(), tables: vec![table.name.clone()], copies: Vec::new() });
+                }
+            }
+        }
+    }
+    cards
+}
+
+// 干扰项来源：本轮学习的单词表的释义，以及同一分组其他单词表的释义
+pub fn choice_pools(memory: &FlashMemory, group: &str, tables: &[Uuid]) -> (Vec<String>, Vec<String>) {
+    let mut table_pool = Vec::new();
+    let mut group_pool = Vec::new();
+    if let Some(group_tables) = memory.get_word_tables_in_group(group) {
+        for table in group_tables {
+            let pool = if tables.contains(&table.id) { &mut table_pool } else { &mut group_pool };
+            pool.extend(table.words.iter().map(|w| w.chinese.clone()));
+        }
+    }
+    (table_pool, group_pool)
+}
+
+// 单词表所在分组中至少有两个不同的释义才能出选择题
+pub fn has_enough_choices(memory: &FlashMemory, group: &str, tables: &[Uuid]) -> bool {
+    let (table_pool, group_pool) = choice_pools(memory, group, tables);
+    let mut distinct: Vec<String> = table_pool.iter().chain(group_pool.iter()).map(|c| quiz::normalize(c)).collect();
+    distinct.sort();
+    distinct.dedup();
+    distinct.len() >= 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    fn words(n: usize) -> Vec<Word> {
+        (0..n).map(|i| Word::new(&format!("word{}", i), &format!("词{}", i), "英语")).collect()
+    }
+
+    fn session(mode: StudyMode, n: usize, timing: FlashTiming) -> (Session, ManualClock) {
+        let clock = ManualClock::new();
+        (Session::new(mode, words(n), timing, Box::new(clock.clone())), clock)
+    }
+
+    fn tick(session: &mut Session, clock: &ManualClock, secs: f32) -> Vec<Transition> {
+        clock.advance(secs);
+        session.handle(SessionEvent::Tick)
+    }
+
+    fn presented(transitions: &[Transition]) -> Vec<&Presentation> {
+        transitions
+            .iter()
+            .filter_map(|t| match t {
+                Transition::Presented(p) => Some(p.as_ref()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // 倒计时 1 秒，正面 1 秒，背面 1 秒，间隔 0.5 秒
+    fn timing() -> FlashTiming {
+        FlashTiming { countdown: 1, front: 1.0, back: 1.0, gap: 0.5, adaptive: false }
+    }
+
+    #[test]
+    fn countdown_then_front_back_gap() {
+        let (mut s, clock) = session(StudyMode::Flash, 2, timing());
+        assert_eq!(s.countdown(), 1);
+        assert!(!s.on_card());
+
+        assert_eq!(tick(&mut s, &clock, 1.0), vec![Transition::CardShown(0)]);
+        assert!(s.on_card() && !s.show_meaning());
+
+        assert_eq!(tick(&mut s, &clock, 1.0), vec![Transition::Revealed]);
+        assert!(s.show_meaning() && !s.in_gap());
+
+        assert!(tick(&mut s, &clock, 1.0).is_empty());
+        assert!(s.in_gap());
+
+        let out = tick(&mut s, &clock, 0.5);
+        let shown = presented(&out);
+        assert_eq!(shown.len(), 1);
+        assert_eq!(shown[0].word.english, "word0");
+        assert_eq!(shown[0].grade, None);
+        assert_eq!(shown[0].reveal_ms, Some(1000));
+        assert_eq!(out.last(), Some(&Transition::CardShown(1)));
+        assert_eq!(s.index(), 1);
+    }
+
+    #[test]
+    fn finishes_after_last_card_without_looping() {
+        let (mut s, clock) = session(StudyMode::Flash, 3, timing());
+        // 一次推进足够长的时间，连续走完全部卡片
+        let out = tick(&mut s, &clock, 60.0);
+        assert_eq!(presented(&out).len(), 3);
+        assert_eq!(out.last(), Some(&Transition::Finished));
+        assert!(s.is_finished());
+        assert!(tick(&mut s, &clock, 10.0).is_empty());
+        assert!(s.handle(SessionEvent::End).is_empty());
+    }
+
+    #[test]
+    fn pause_stops_the_clock() {
+        let (mut s, clock) = session(StudyMode::Flash, 2, timing());
+        tick(&mut s, &clock, 1.5); // 倒计时结束后过了 0.5 秒
+        assert_eq!(s.handle(SessionEvent::Pause), vec![Transition::Paused]);
+        assert!(s.is_paused());
+        assert!(tick(&mut s, &clock, 100.0).is_empty());
+        assert!(!s.show_meaning());
+
+        assert_eq!(s.handle(SessionEvent::Resume), vec![Transition::Resumed]);
+        assert!(tick(&mut s, &clock, 0.25).is_empty());
+        assert_eq!(tick(&mut s, &clock, 0.25), vec![Transition::Revealed]);
+        assert_eq!(s.index(), 0);
+    }
+
+    #[test]
+    fn pause_during_countdown() {
+        let (mut s, clock) = session(StudyMode::Flash, 1, FlashTiming { countdown: 3, ..timing() });
+        tick(&mut s, &clock, 1.5);
+        assert_eq!(s.countdown(), 2);
+        s.handle(SessionEvent::Pause);
+        tick(&mut s, &clock, 5.0);
+        assert_eq!(s.countdown(), 2);
+        s.handle(SessionEvent::Resume);
+        tick(&mut s, &clock, 0.5);
+        assert_eq!(s.countdown(), 1);
+        // 暂停时仍可中途结束，倒计时中结束不记录展示
+        assert!(s.handle(SessionEvent::End).is_empty());
+        assert!(s.is_finished());
+    }
+
+    #[test]
+    fn review_waits_for_grade_and_requeues_again() {
+        let (mut s, clock) = session(StudyMode::Review, 2, FlashTiming { countdown: 0, ..timing() });
+        assert!(s.on_card());
+        tick(&mut s, &clock, 30.0);
+        assert!(s.show_meaning());
+        assert_eq!(s.index(), 0);
+
+        clock.advance(1.0);
+        let out = s.handle(SessionEvent::Grade(Grade::Again));
+        let shown = presented(&out);
+        assert_eq!(shown[0].grade, Some(Grade::Again));
+        assert_eq!(shown[0].reveal_ms, Some(1000));
+        assert_eq!(shown[0].response_ms, Some(31000));
+        assert_eq!(s.words().len(), 3);
+        assert_eq!(s.words()[2].english, "word0");
+
+        s.handle(SessionEvent::Grade(Grade::Good));
+        let out = s.handle(SessionEvent::Grade(Grade::Good));
+        assert_eq!(out.last(), Some(&Transition::Finished));
+    }
+
+    #[test]
+    fn reveal_skips_the_front() {
+        let (mut s, clock) = session(StudyMode::Flash, 2, FlashTiming { countdown: 0, ..timing() });
+        tick(&mut s, &clock, 0.25);
+        assert_eq!(s.handle(SessionEvent::Reveal), vec![Transition::Revealed]);
+        assert!(s.show_meaning());
+        // 跳过正面后背面仍完整展示
+        assert!(tick(&mut s, &clock, 1.0).is_empty());
+        let out = tick(&mut s, &clock, 0.5);
+        assert_eq!(presented(&out)[0].reveal_ms, Some(250));
+    }
+
+    #[test]
+    fn skip_and_back() {
+        let (mut s, clock) = session(StudyMode::Flash, 3, FlashTiming { countdown: 0, ..timing() });
+        tick(&mut s, &clock, 0.5);
+        let out = s.handle(SessionEvent::Skip);
+        assert_eq!(presented(&out).len(), 1);
+        assert_eq!(s.index(), 1);
+        let out = s.handle(SessionEvent::Back);
+        assert_eq!(out.last(), Some(&Transition::CardShown(0)));
+        assert_eq!(s.index(), 0);
+        // 第一张再往前仍停在第一张
+        s.handle(SessionEvent::Back);
+        assert_eq!(s.index(), 0);
+    }
+
+    #[test]
+    fn end_logs_the_current_card_once() {
+        let (mut s, clock) = session(StudyMode::Flash, 2, FlashTiming { countdown: 0, ..timing() });
+        tick(&mut s, &clock, 0.5);
+        let out = s.handle(SessionEvent::End);
+        assert_eq!(presented(&out).len(), 1);
+        assert!(!out.contains(&Transition::Finished));
+        assert!(s.is_finished());
+    }
+
+    #[test]
+    fn quiz_answers_then_waits_for_next() {
+        let (mut s, clock) = session(StudyMode::Typing, 2, timing());
+        assert!(s.on_card(), "测验不倒计时");
+        clock.advance(2.0);
+        let out = s.handle(SessionEvent::Grade(Grade::Again));
+        assert_eq!(presented(&out)[0].response_ms, Some(2000));
+        assert!(s.show_meaning());
+        assert_eq!(s.index(), 0);
+        // 已作答的题不能再次作答，结束时也不重复记录
+        assert!(presented(&s.handle(SessionEvent::Grade(Grade::Good))).is_empty());
+
+        s.handle(SessionEvent::Skip);
+        assert_eq!(s.index(), 1);
+        assert_eq!(s.words().len(), 3);
+        s.handle(SessionEvent::Grade(Grade::Hard));
+        assert!(presented(&s.handle(SessionEvent::End)).is_empty());
+        assert_eq!((s.correct(), s.answered()), (1, 2));
+        assert_eq!(s.missed(), ["word0".to_string()]);
+    }
+
+    #[test]
+    fn wrong_choice_comes_back_a_few_questions_later() {
+        let (mut s, _clock) = session(StudyMode::Choice, 6, timing());
+        s.handle(SessionEvent::Grade(Grade::Again));
+        s.handle(SessionEvent::Skip);
+        assert_eq!(s.words().len(), 7);
+        assert_eq!(s.words()[4].english, "word0");
+        // 不能暂停或跳回
+        assert!(s.handle(SessionEvent::Pause).is_empty());
+        assert!(s.handle(SessionEvent::Back).is_empty());
+    }
+
+    #[test]
+    fn choices_come_from_table_then_group() {
+        let mut memory = FlashMemory::new();
+        let first = memory.create_word_table("英语", None, "第一课").unwrap();
+        let second = memory.create_word_table("英语", None, "第二课").unwrap();
+        memory.add_words_to_table("英语", first, vec![Word::new("apple", "苹果", "英语")]).unwrap();
+        assert!(!has_enough_choices(&memory, "英语", &[first]));
+
+        memory.add_words_to_table("英语", second, vec![Word::new("pear", "梨", "英语")]).unwrap();
+        let (table_pool, group_pool) = choice_pools(&memory, "英语", &[first]);
+        assert_eq!(table_pool, vec!["苹果"]);
+        assert_eq!(group_pool, vec!["梨"]);
+        assert!(has_enough_choices(&memory, "英语", &[first]));
+        // 同时学习两个单词表时都算作本轮的释义
+        assert_eq!(choice_pools(&memory, "英语", &[first, second]).0, vec!["苹果", "梨"]);
+    }
+
+    #[test]
+    fn merged_tables_drop_duplicates_and_keep_sources() {
+        let mut memory = FlashMemory::new();
+        let first = memory.create_word_table("英语", None, "第一课").unwrap();
+        let unit = memory.create_folder("英语", None, "第一单元").unwrap();
+        let second = memory.create_word_table("英语", Some(unit), "第二课").unwrap();
+        let mut learned = Word::new("pear", "梨", "英语");
+        learned.schedule.due = Some(chrono::NaiveDate::from_ymd_opt(2099, 1, 1).unwrap());
+        memory.add_words_to_table("英语", first, vec![Word::new("apple", "苹果", "英语"), learned]).unwrap();
+        let copies = vec![Word::new("Apple ", "苹果", "英语"), Word::new("pear", "梨", "英语"), Word::new("apple", "苹果树", "英语")];
+        memory.add_words_to_table("英语", second, copies).unwrap();
+
+        let cards = merge_tables(&memory, "英语", &[first, second], |_| true);
+        let summary: Vec<_> = cards.iter().map(|c| (c.word.chinese.as_str(), c.tables.len(), c.copies.len())).collect();
+        assert_eq!(summary, vec![("苹果", 2, 1), ("梨", 2, 1), ("苹果树", 1, 0)]);
+        assert_eq!(cards[0].tables, vec!["第一课", "第二课"]);
+        assert_eq!(cards[0].copies, vec![memory.get_word_table("英语", second).unwrap().words[0].id]);
+
+        // 先筛选再去重：第一课的 pear 未到期时由第二课的 pear 代替
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let due = merge_tables(&memory, "英语", &[first, second], |w| w.schedule.is_due(today));
+        assert_eq!(due[1].tables, vec!["第二课"]);
+        assert_eq!(due.len(), 3);
+    }
+
+    #[test]
+    fn duplicate_meanings_do_not_count() {
+        let mut memory = FlashMemory::new();
+        let first = memory.create_word_table("英语", None, "第一课").unwrap();
+        let words = vec![Word::new("big", "大", "英语"), Word::new("large", " 大 ", "英语")];
+        memory.add_words_to_table("英语", first, words).unwrap();
+        assert!(!has_enough_choices(&memory, "英语", &[first]));
+        assert!(!has_enough_choices(&memory, "日语", &[first]));
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::{HashMap, HashSet};
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use std::path::{Path, PathBuf};
+
+use flash_memory::merge::SaveMode;
+use flash_memory::ordering::SortMode;
+use uuid::Uuid;
+
+// 应用偏好设置文件，放在应用数据目录中
+pub const SETTINGS_FILE: &str = "settings.json";
+// 最多记住的最近单词库数量
+const RECENT_LIBRARY_COUNT: usize = 8;
+
+// 闪记过程中可绑定快捷键的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StudyAction {
+    PauseResume,
+    Previous,
+    Next,
+    Reveal,
+    MarkKnown,
+    MarkUnknown,
+    End,
+    ToggleHelp,
+}
+
+impl StudyAction {
+    pub const ALL: [StudyAction; 8] = [
+        StudyAction::PauseResume,
+        StudyAction::Previous,
+        StudyAction::Next,
+        StudyAction::Reveal,
+        StudyAction::MarkKnown,
+        StudyAction::MarkUnknown,
+        StudyAction::End,
+        StudyAction::ToggleHelp,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StudyAction::PauseResume => "暂停/继续",
+            StudyAction::Previous => "上一个",
+            StudyAction::Next => "下一个",
+            StudyAction::Reveal => "立即显示释义",
+            StudyAction::MarkKnown => "标记认识",
+            StudyAction::MarkUnknown => "标记不认识",
+            StudyAction::End => "结束",
+            StudyAction::ToggleHelp => "显示/隐藏快捷键",
+        }
+    }
+
+    fn default_key(self) -> egui::Key {
+        match self {
+            StudyAction::PauseResume => egui::Key::Space,
+            StudyAction::Previous => egui::Key::ArrowLeft,
+            StudyAction::Next => egui::Key::ArrowRight,
+            StudyAction::Reveal => egui::Key::Enter,
+            StudyAction::MarkKnown => egui::Key::K,
+            StudyAction::MarkUnknown => egui::Key::U,
+            StudyAction::End => egui::Key::Escape,
+            StudyAction::ToggleHelp => egui::Key::F1,
+        }
+    }
+}
+
+// 快捷键绑定，以按键名保存（如 "Space"、"ArrowLeft"）；未设置或无法识别时使用默认按键
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct KeyBindings(HashMap<StudyAction, String>);
+
+impl KeyBindings {
+    pub fn key(&self, action: StudyAction) -> egui::Key {
+        self.0
+            .get(&action)
+            .and_then(|name| egui::Key::from_name(name))
+            .unwrap_or_else(|| action.default_key())
+    }
+
+    // 绑定新按键；若该按键已被其他操作占用，则与其交换，返回被交换的操作
+    pub fn bind(&mut self, action: StudyAction, key: egui::Key) -> Option<StudyAction> {
+        let old = self.key(action);
+        let other = StudyAction::ALL
+            .into_iter()
+            .find(|&a| a != action && self.key(a) == key);
+        if let Some(other) = other {
+            self.0.insert(other, old.name().to_string());
+        }
+        self.0.insert(action, key.name().to_string());
+        other
+    }
+
+    // 取出当前帧按下的可用操作，并消耗这些按键，避免同时触发获得焦点的按钮；
+    // 不可用的操作不消耗按键，留给界面其他部分（如选择题的回车）
+    pub fn take_pressed(&self, input: &mut egui::InputState, enabled: impl Fn(StudyAction) -> bool) -> Vec<StudyAction> {
+        StudyAction::ALL
+            .into_iter()
+            .filter(|&a| enabled(a) && input.consume_key(egui::Modifiers::NONE, self.key(a)))
+            .collect()
+    }
+}
+
+// 单词表编辑器的视图
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EditorView {
+    #[default]
+    Grid, // 表格：每个单词一行，每个字段一列
+    Text, // 文本：每行一个单词，详细信息缩进写在其后
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub key_bindings: KeyBindings,
+    // 最近打开的单词库，最近的在前；启动时默认打开第一个
+    pub recent_libraries: Vec<PathBuf>,
+    // 目录中分组、文件夹与单词表的排列方式
+    pub sort_mode: SortMode,
+    // 目录中展开的文件夹
+    pub expanded_folders: HashSet<Uuid>,
+    // 单词表编辑器使用表格还是文本视图
+    pub editor_view: EditorView,
+    // 编辑器保存时上次选择的方式（替换、追加或按英文合并）
+    pub save_mode: SaveMode,
+}
+
+impl AppSettings {
+    pub fn load_from_file(filename: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(filename)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save_to_file(&self, filename: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        flash_memory::storage::write_atomic(filename, serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+
+    // 把单词库移到最近列表的最前面
+    pub fn remember_library(&mut self, path: &Path) {
+        let path = std::fs::canonicalize(path)
+            .or_else(|_| std::path::absolute(path))
+            .unwrap_or_else(|_| path.to_path_buf());
+        self.recent_libraries.retain(|p| *p != path);
+        self.recent_libraries.insert(0, path);
+        self.recent_libraries.truncate(RECENT_LIBRARY_COUNT);
+    }
+}
@@ -0,0 +1,159 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::{Storage, StorageKind, DATA_FILE};
+use crate::model::FlashMemory;
+use crate::review_log::{ReviewRecord, REVIEW_LOG_FILE};
+
+// JSON 后端：单词库整体写入一个 JSON 文件，复习记录逐行追加到同目录的 reviews.jsonl
+// （其他名称的单词库如 course.json 对应 course.reviews.jsonl，同一目录的多个单词库互不干扰）
+pub struct JsonStorage {
+    path: PathBuf,
+    reviews_path: PathBuf,
+    needs_newline: bool, // 复习记录文件末尾是被中断写入的半行
+    backup_error: Option<String>,
+}
+
+impl JsonStorage {
+    pub fn new(path: &Path) -> Self {
+        JsonStorage {
+            path: path.to_path_buf(),
+            reviews_path: reviews_file(path),
+            needs_newline: false,
+            backup_error: None,
+        }
+    }
+}
+
+fn reviews_file(path: &Path) -> PathBuf {
+    match path.file_stem().and_then(|s| s.to_str()) {
+        Some(stem) if Path::new(DATA_FILE).file_stem() != Some(stem.as_ref()) => {
+            path.with_file_name(format!("{}.{}", stem, REVIEW_LOG_FILE))
+        }
+        _ => path.with_file_name(REVIEW_LOG_FILE),
+    }
+}
+
+impl Storage for JsonStorage {
+    fn kind(&self) -> StorageKind {
+        StorageKind::Json
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn load(&mut self) -> Result<FlashMemory, Box<dyn std::error::Error>> {
+        if !self.path.exists() {
+            return Ok(FlashMemory::new());
+        }
+        FlashMemory::load_from_file(&self.path.to_string_lossy())
+    }
+
+    fn save(&mut self, memory: &FlashMemory) -> Result<(), Box<dyn std::error::Error>> {
+        let backup_error = memory.save_to_file(&self.path.to_string_lossy())?;
+        self.backup_error = backup_error.map(|e| e.to_string());
+        Ok(())
+    }
+
+    fn take_backup_error(&mut self) -> Option<String> {
+        self.backup_error.take()
+    }
+
+    // 文件不存在时视为空记录，无法解析的行（如崩溃时写了一半）会被跳过
+    fn load_reviews(&mut self) -> Result<Vec<ReviewRecord>, Box<dyn std::error::Error>> {
+        let content = match std::fs::read_to_string(&self.reviews_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        self.needs_newline = !content.is_empty() && !content.ends_with('\n');
+        Ok(content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+    }
+
+    // 每条记录写入后立即落盘，学习中途崩溃也不会丢失已展示的卡片
+    fn append_review(&mut self, record: &ReviewRecord) -> Result<(), Box<dyn std::error::Error>> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        if self.needs_newline {
+            line.insert(0, '\n');
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.reviews_path)?;
+        file.write_all(line.as_bytes())?;
+        file.sync_data()?;
+        self.needs_newline = false;
+        Ok(())
+    }
+
+    fn replace_reviews(&mut self, records: &[ReviewRecord]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut content = String::new();
+        for record in records {
+            content.push_str(&serde_json::to_string(record)?);
+            content.push('\n');
+        }
+        super::write_atomic(&self.reviews_path, content.as_bytes())?;
+        self.needs_newline = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review_log::StudyMode;
+    use crate::scheduler::Grade;
+
+    fn record(english: &str) -> ReviewRecord {
+        ReviewRecord {
+            word_id: Some(uuid::Uuid::new_v4()),
+            group: "英语".to_string(),
+            table: "第一课".to_string(),
+            english: english.to_string(),
+            chinese: "词".to_string(),
+            mode: StudyMode::Review,
+            grade: Some(Grade::Good),
+            reveal_ms: Some(1000),
+            response_ms: Some(1500),
+            reviewed_at: chrono::Local::now(),
+        }
+    }
+
+    #[test]
+    fn missing_files_load_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(&dir.path().join("words.json"));
+        assert_eq!(storage.load().unwrap(), FlashMemory::new());
+        assert!(storage.load_reviews().unwrap().is_empty());
+    }
+
+    #[test]
+    fn libraries_in_one_directory_keep_separate_reviews() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut personal = JsonStorage::new(&dir.path().join("words.json"));
+        let mut course = JsonStorage::new(&dir.path().join("course.json"));
+        personal.append_review(&record("apple")).unwrap();
+        course.append_review(&record("pear")).unwrap();
+        assert!(dir.path().join(REVIEW_LOG_FILE).exists());
+        assert!(dir.path().join("course.reviews.jsonl").exists());
+        assert_eq!(personal.load_reviews().unwrap()[0].english, "apple");
+        assert_eq!(course.load_reviews().unwrap()[0].english, "pear");
+    }
+
+    #[test]
+    fn torn_review_line_is_skipped_and_not_glued() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = JsonStorage::new(&dir.path().join("words.json"));
+        storage.append_review(&record("apple")).unwrap();
+        // 模拟崩溃时写了一半的行
+        let mut file = OpenOptions::new().append(true).open(dir.path().join(REVIEW_LOG_FILE)).unwrap();
+        file.write_all("{\"group\":\"英".as_bytes()).unwrap();
+
+        let mut storage = JsonStorage::new(&dir.path().join("words.json"));
+        assert_eq!(storage.load_reviews().unwrap().len(), 1);
+        storage.append_review(&record("pear")).unwrap();
+        let reviews = storage.load_reviews().unwrap();
+        let words: Vec<_> = reviews.iter().map(|r| r.english.as_str()).collect();
+        assert_eq!(words, vec!["apple", "pear"]);
+    }
+}
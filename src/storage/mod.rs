@@ -0,0 +1,432 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDateTime, TimeDelta};
+use serde::Serialize;
+
+use crate::migrate::{self, CURRENT_VERSION};
+use crate::model::FlashMemory;
+use crate::review_log::{ReviewRecord, REVIEW_LOG_FILE};
+
+mod json;
+mod sqlite;
+
+pub use json::JsonStorage;
+pub use sqlite::{migrate_to_sqlite, SqliteStorage};
+
+// 单词库目录中的单词数据文件
+pub const DATA_FILE: &str = "words.json";
+// 单词库目录中的 SQLite 单词库文件；存在时优先使用
+pub const DB_FILE: &str = "words.db";
+// 应用数据目录下的文件夹名
+const APP_DIR_NAME: &str = "flash_memory";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageKind {
+    Json,
+    Sqlite,
+}
+
+impl StorageKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StorageKind::Json => "JSON",
+            StorageKind::Sqlite => "SQLite",
+        }
+    }
+}
+
+// 单词库的存储后端：单词数据与复习记录的读写
+pub trait Storage {
+    fn kind(&self) -> StorageKind;
+
+    fn path(&self) -> &Path;
+
+    // 读取单词库；文件不存在时返回空单词库
+    fn load(&mut self) -> Result<FlashMemory, Box<dyn std::error::Error>>;
+
+    // 保存单词库当前的状态
+    fn save(&mut self, memory: &FlashMemory) -> Result<(), Box<dyn std::error::Error>>;
+
+    // 最近一次保存时备份失败的原因（单词库本身已写入），取出后清空
+    fn take_backup_error(&mut self) -> Option<String> {
+        None
+    }
+
+    fn load_reviews(&mut self) -> Result<Vec<ReviewRecord>, Box<dyn std::error::Error>>;
+
+    // 追加一条复习记录，写入后立即落盘
+    fn append_review(&mut self, record: &ReviewRecord) -> Result<(), Box<dyn std::error::Error>>;
+
+    // 用给定的记录整体替换复习记录（如为早期记录补上单词标识后写回）
+    fn replace_reviews(&mut self, records: &[ReviewRecord]) -> Result<(), Box<dyn std::error::Error>>;
+}
+
+// 按扩展名选择后端：.db 使用 SQLite，其余使用 JSON
+pub fn open(path: &Path) -> Box<dyn Storage> {
+    if path.extension().is_some_and(|ext| ext == "db") {
+        Box::new(SqliteStorage::new(path))
+    } else {
+        Box::new(JsonStorage::new(path))
+    }
+}
+
+// 应用数据目录（如 Linux 上的 ~/.local/share/flash_memory），默认单词库放在这里
+pub fn app_data_dir() -> PathBuf {
+    dirs::data_dir().map(|dir| dir.join(APP_DIR_NAME)).unwrap_or_else(|| PathBuf::from("."))
+}
+
+// 把用户给出的单词库位置解析为文件：目录（或没有扩展名的路径）中已迁移到 SQLite 时
+// 使用 words.db，否则使用 words.json；文件路径原样使用
+pub fn resolve_library(path: &Path) -> PathBuf {
+    if path.is_dir() || path.extension().is_none() {
+        let db = path.join(DB_FILE);
+        if db.exists() { db } else { path.join(DATA_FILE) }
+    } else {
+        path.to_path_buf()
+    }
+}
+
+// 默认单词库：应用数据目录中的 words.db 或 words.json
+pub fn default_library() -> PathBuf {
+    resolve_library(&app_data_dir())
+}
+
+// 早期版本把单词库放在程序的工作目录中。应用数据目录中还没有单词库时，把 legacy_dir 中的
+// words.json（或 words.db）与 reviews.jsonl 复制过去，原文件保留；返回复制后的单词库路径
+pub fn adopt_legacy_library(legacy_dir: &Path, data_dir: &Path) -> std::io::Result<Option<PathBuf>> {
+    let target = resolve_library(data_dir);
+    let legacy = resolve_library(legacy_dir);
+    if target.exists() || !legacy.exists() || legacy_dir.canonicalize()? == data_dir.canonicalize()? {
+        return Ok(None);
+    }
+    let target = data_dir.join(legacy.file_name().unwrap_or_default());
+    std::fs::copy(&legacy, &target)?;
+    let reviews = legacy_dir.join(REVIEW_LOG_FILE);
+    if reviews.exists() {
+        std::fs::copy(&reviews, data_dir.join(REVIEW_LOG_FILE))?;
+    }
+    Ok(Some(target))
+}
+
+// 保留的备份数量，超出时删除最旧的
+pub const BACKUP_COUNT: usize = 10;
+// 两次备份之间至少间隔的分钟数，避免频繁自动保存把较早的备份挤掉
+pub const BACKUP_INTERVAL_MINUTES: i64 = 10;
+const BACKUP_TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+// 先写入同目录下的临时文件并落盘，再替换目标文件；中途崩溃或磁盘写满时原文件保持完整
+pub fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let result = (|| {
+        let mut file = std::fs::File::create(&tmp)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+// 备份目录：数据文件旁的 backups 文件夹
+pub fn backup_dir(data_file: &Path) -> PathBuf {
+    data_file.parent().unwrap_or(Path::new("")).join("backups")
+}
+
+fn backup_stem(data_file: &Path) -> String {
+    data_file.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+// 数据文件的备份及其时间，按时间从新到旧排列
+pub fn list_backups(data_file: &Path) -> Vec<(NaiveDateTime, PathBuf)> {
+    let prefix = format!("{}-", backup_stem(data_file));
+    let Ok(entries) = std::fs::read_dir(backup_dir(data_file)) else { return Vec::new() };
+    let mut backups: Vec<(NaiveDateTime, PathBuf)> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_stem()?.to_str()?;
+            let time = NaiveDateTime::parse_from_str(name.strip_prefix(&prefix)?, BACKUP_TIME_FORMAT).ok()?;
+            Some((time, path))
+        })
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.0));
+    backups
+}
+
+// 距上次备份已超过间隔时，把当前数据文件复制一份带时间的备份，并只保留最近 BACKUP_COUNT 份；
+// 返回新建的备份路径
+pub fn backup_if_due(data_file: &Path, now: NaiveDateTime) -> std::io::Result<Option<PathBuf>> {
+    if !data_file.exists() {
+        return Ok(None);
+    }
+    let backups = list_backups(data_file);
+    if let Some((latest, _)) = backups.first() {
+        if now - *latest < TimeDelta::minutes(BACKUP_INTERVAL_MINUTES) {
+            return Ok(None);
+        }
+    }
+    let dir = backup_dir(data_file);
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}-{}.json", backup_stem(data_file), now.format(BACKUP_TIME_FORMAT)));
+    std::fs::copy(data_file, &path)?;
+    for (_, old) in backups.iter().skip(BACKUP_COUNT - 1) {
+        std::fs::remove_file(old)?;
+    }
+    Ok(Some(path))
+}
+
+// 把无法读取的数据文件改名保留（如 words.json.broken-20240101-120000），返回新路径
+pub fn set_aside_broken(data_file: &Path, now: NaiveDateTime) -> std::io::Result<PathBuf> {
+    let mut name = data_file.as_os_str().to_owned();
+    name.push(format!(".broken-{}", now.format(BACKUP_TIME_FORMAT)));
+    let path = PathBuf::from(name);
+    std::fs::rename(data_file, &path)?;
+    Ok(path)
+}
+
+// 写入文件的顶层结构：格式版本加上单词库内容
+#[derive(Serialize)]
+struct Persisted<'a> {
+    version: u64,
+    #[serde(flatten)]
+    memory: &'a FlashMemory,
+}
+
+impl FlashMemory {
+    // 保存前按需备份原文件，再以临时文件替换的方式写入；备份失败时照常写入，并返回备份失败的原因
+    pub fn save_to_file(&self, filename: &str) -> Result<Option<std::io::Error>, Box<dyn std::error::Error>> {
+        let path = Path::new(filename);
+        let json = serde_json::to_string_pretty(&Persisted { version: CURRENT_VERSION, memory: self })?;
+        let backup_error = backup_if_due(path, Local::now().naive_local()).err();
+        write_atomic(path, json.as_bytes())?;
+        Ok(backup_error)
+    }
+
+    // 用备份覆盖数据文件，备份无法读取时不做改动
+    pub fn restore_backup(backup: &Path, filename: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let restored = Self::load_from_file(&backup.to_string_lossy())?;
+        write_atomic(Path::new(filename), &std::fs::read(backup)?)?;
+        Ok(restored)
+    }
+
+    // 读取时先把旧版本的文件逐步升级到当前格式；来自更新版本的文件会报错
+    pub fn load_from_file(filename: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(filename)?;
+        let doc = migrate::migrate(serde_json::from_str(&content)?)?;
+        let flash_memory: FlashMemory = serde_json::from_value(doc)?;
+        Ok(flash_memory)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::model::Word;
+    use crate::scheduler::{Algorithm, Grade, SchedulerSettings};
+    use crate::timing::FlashTiming;
+
+    fn path_in(dir: &tempfile::TempDir) -> String {
+        dir.path().join("words.json").to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = path_in(&dir);
+
+        let mut memory = FlashMemory::new();
+        let lesson = memory.create_word_table("英语", None, "第一课").unwrap();
+        memory.create_group_if_absent("空分组");
+        memory
+            .add_words_to_table("英语", lesson, vec![Word::new("apple", "苹果", "英语"), Word::new("pear", "梨", "英语")])
+            .unwrap();
+        let unit = memory.create_folder("英语", None, "第一单元").unwrap();
+        memory.create_word_table("英语", Some(unit), "第一课").unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let apple = memory.find_word_table("英语", None, "第一课").unwrap().words[0].clone();
+        memory.grade_word("英语", &apple, Grade::Easy, today).unwrap();
+        memory.set_word_table_timing("英语", lesson, FlashTiming { gap: 0.5, adaptive: true, ..Default::default() }).unwrap();
+        memory.scheduler_settings.insert(
+            "英语".to_string(),
+            SchedulerSettings { algorithm: Algorithm::Fsrs, target_retention: 0.85, fsrs_weights: None },
+        );
+
+        memory.save_to_file(&path).unwrap();
+        let loaded = FlashMemory::load_from_file(&path).unwrap();
+        assert_eq!(loaded, memory);
+    }
+
+    #[test]
+    fn loads_file_without_newer_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = path_in(&dir);
+        let legacy = r#"{
+            "groups": {
+                "英语": [
+                    { "name": "第一课", "words": [ { "english": "apple", "chinese": "苹果", "group": "英语" } ] }
+                ]
+            }
+        }"#;
+        std::fs::write(&path, legacy).unwrap();
+
+        let loaded = FlashMemory::load_from_file(&path).unwrap();
+        let table = loaded.find_word_table("英语", None, "第一课").unwrap();
+        assert_eq!(table.timing, FlashTiming::default());
+        assert_eq!(table.words.len(), 1);
+        assert_eq!((table.words[0].english.as_str(), table.words[0].chinese.as_str()), ("apple", "苹果"));
+        assert!(!table.words[0].has_details());
+        assert!(loaded.scheduler_settings.is_empty());
+    }
+
+    #[test]
+    fn saved_file_records_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = path_in(&dir);
+        FlashMemory::new().save_to_file(&path).unwrap();
+        let doc: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(doc["version"], CURRENT_VERSION);
+    }
+
+    #[test]
+    fn loads_fixtures_of_every_version() {
+        let fixtures = format!("{}/tests/fixtures", env!("CARGO_MANIFEST_DIR"));
+        for name in ["v1_baseline.json", "v1_scheduled.json", "v2.json", "v3.json", "v4.json", "v5.json", "v6.json", "v7.json"] {
+            let memory = FlashMemory::load_from_file(&format!("{}/{}", fixtures, name)).unwrap();
+            assert!(memory.find_word_table("英语", None, "第一课").is_some(), "{}", name);
+        }
+        let err = FlashMemory::load_from_file(&format!("{}/future.json", fixtures)).unwrap_err();
+        assert!(err.to_string().contains("更新版本"));
+    }
+
+    #[test]
+    fn missing_or_corrupt_file_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = path_in(&dir);
+        assert!(FlashMemory::load_from_file(&path).is_err());
+        std::fs::write(&path, "{ \"groups\": ").unwrap();
+        assert!(FlashMemory::load_from_file(&path).is_err());
+    }
+
+    #[test]
+    fn library_location_resolves_to_a_file() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(resolve_library(dir.path()), dir.path().join(DATA_FILE));
+        // 尚未创建的目录同样指向其中的 words.json
+        assert_eq!(resolve_library(&dir.path().join("课程")), dir.path().join("课程").join(DATA_FILE));
+        std::fs::write(dir.path().join(DB_FILE), "").unwrap();
+        assert_eq!(resolve_library(dir.path()), dir.path().join(DB_FILE));
+        let file = dir.path().join("course.json");
+        assert_eq!(resolve_library(&file), file);
+    }
+
+    #[test]
+    fn legacy_library_is_copied_once() {
+        let legacy = tempfile::tempdir().unwrap();
+        let data = tempfile::tempdir().unwrap();
+        // 程序目录中没有早期的单词库
+        assert_eq!(adopt_legacy_library(legacy.path(), data.path()).unwrap(), None);
+
+        std::fs::write(legacy.path().join(DATA_FILE), "{}").unwrap();
+        std::fs::write(legacy.path().join(REVIEW_LOG_FILE), "记录\n").unwrap();
+        let adopted = adopt_legacy_library(legacy.path(), data.path()).unwrap();
+        assert_eq!(adopted, Some(data.path().join(DATA_FILE)));
+        assert_eq!(std::fs::read_to_string(data.path().join(REVIEW_LOG_FILE)).unwrap(), "记录\n");
+        assert!(legacy.path().join(DATA_FILE).exists());
+
+        // 应用数据目录中已有单词库时不再覆盖
+        std::fs::write(data.path().join(DATA_FILE), "新的").unwrap();
+        assert_eq!(adopt_legacy_library(legacy.path(), data.path()).unwrap(), None);
+        assert_eq!(std::fs::read_to_string(data.path().join(DATA_FILE)).unwrap(), "新的");
+        // 两者是同一目录时不做任何事
+        assert_eq!(adopt_legacy_library(legacy.path(), legacy.path()).unwrap(), None);
+    }
+
+    fn at(minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 3, 1).unwrap().and_hms_opt(8, 0, 0).unwrap() + TimeDelta::minutes(minute as i64)
+    }
+
+    #[test]
+    fn save_replaces_file_without_leaving_temp() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = path_in(&dir);
+        std::fs::write(&path, "旧内容").unwrap();
+        write_atomic(Path::new(&path), "新内容".as_bytes()).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "新内容");
+        let names: Vec<_> = std::fs::read_dir(dir.path()).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(names, vec![std::ffi::OsString::from("words.json")]);
+    }
+
+    #[test]
+    fn backups_respect_interval_and_rotate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = path_in(&dir);
+        let data_file = Path::new(&path);
+        // 还没有数据文件时无需备份
+        assert_eq!(backup_if_due(data_file, at(0)).unwrap(), None);
+
+        std::fs::write(data_file, "第 0 版").unwrap();
+        assert!(backup_if_due(data_file, at(0)).unwrap().is_some());
+        assert_eq!(backup_if_due(data_file, at(5)).unwrap(), None);
+
+        for i in 1..=BACKUP_COUNT as u32 + 2 {
+            std::fs::write(data_file, format!("第 {} 版", i)).unwrap();
+            assert!(backup_if_due(data_file, at(i * 10)).unwrap().is_some());
+        }
+        let backups = list_backups(data_file);
+        assert_eq!(backups.len(), BACKUP_COUNT);
+        assert_eq!(backups[0].0, at((BACKUP_COUNT as u32 + 2) * 10));
+        assert_eq!(std::fs::read_to_string(&backups[0].1).unwrap(), format!("第 {} 版", BACKUP_COUNT + 2));
+        assert_eq!(backups.last().unwrap().0, at(30));
+    }
+
+    #[test]
+    fn failed_backup_does_not_block_saving() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = path_in(&dir);
+        FlashMemory::new().save_to_file(&path).unwrap();
+        // backups 被同名文件占用，无法创建备份目录
+        std::fs::write(backup_dir(Path::new(&path)), "").unwrap();
+
+        let mut memory = FlashMemory::new();
+        memory.create_word_table("英语", None, "第一课").unwrap();
+        assert!(memory.save_to_file(&path).unwrap().is_some());
+        assert_eq!(FlashMemory::load_from_file(&path).unwrap(), memory);
+    }
+
+    #[test]
+    fn restore_backup_over_broken_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = path_in(&dir);
+        let mut memory = FlashMemory::new();
+        memory.create_word_table("英语", None, "第一课").unwrap();
+        memory.save_to_file(&path).unwrap();
+        let backup = backup_if_due(Path::new(&path), at(0)).unwrap().unwrap();
+
+        std::fs::write(&path, "{ \"groups\": ").unwrap();
+        assert!(FlashMemory::load_from_file(&path).is_err());
+        let restored = FlashMemory::restore_backup(&backup, &path).unwrap();
+        assert_eq!(restored, memory);
+        assert_eq!(FlashMemory::load_from_file(&path).unwrap(), memory);
+
+        // 损坏的备份不会覆盖数据文件
+        std::fs::write(&backup, "损坏").unwrap();
+        assert!(FlashMemory::restore_backup(&backup, &path).is_err());
+        assert_eq!(FlashMemory::load_from_file(&path).unwrap(), memory);
+    }
+
+    #[test]
+    fn broken_file_is_set_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = path_in(&dir);
+        std::fs::write(&path, "损坏").unwrap();
+        let aside = set_aside_broken(Path::new(&path), at(0)).unwrap();
+        assert!(!Path::new(&path).exists());
+        assert_eq!(std::fs::read_to_string(aside).unwrap(), "损坏");
+    }
+}
@@ -0,0 +1,923 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, Transaction};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{Storage, StorageKind};
+use crate::model::{FlashMemory, Folder, Word, WordTable};
+use crate::review_log::ReviewRecord;
+use crate::scheduler::{Schedule, SchedulerSettings};
+use crate::timing::FlashTiming;
+
+// 数据库结构版本，记录在 PRAGMA user_version 中
+pub const SCHEMA_VERSION: i64 = 6;
+
+const SCHEMA: &str = "
+CREATE TABLE word_groups (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    algorithm TEXT,            -- 复习算法，NULL 表示使用默认设置
+    target_retention REAL,
+    fsrs_weights TEXT          -- 优化后的 FSRS 参数（JSON 数组）
+);
+CREATE TABLE word_tables (
+    id INTEGER PRIMARY KEY,
+    group_id INTEGER NOT NULL REFERENCES word_groups(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    countdown INTEGER NOT NULL,
+    front REAL NOT NULL,
+    back REAL NOT NULL,
+    gap REAL NOT NULL,
+    adaptive INTEGER NOT NULL
+);
+CREATE INDEX word_tables_group ON word_tables(group_id, position);
+CREATE TABLE words (
+    id INTEGER PRIMARY KEY,
+    table_id INTEGER NOT NULL REFERENCES word_tables(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    english TEXT NOT NULL,
+    chinese TEXT NOT NULL,
+    ease REAL NOT NULL,
+    interval INTEGER NOT NULL,
+    repetitions INTEGER NOT NULL,
+    due TEXT,
+    stability REAL NOT NULL,
+    difficulty REAL NOT NULL,
+    last_review TEXT,
+    UNIQUE (table_id, position)
+);
+CREATE INDEX words_due ON words(due);
+CREATE TABLE reviews (
+    id INTEGER PRIMARY KEY,
+    group_name TEXT NOT NULL,
+    table_name TEXT NOT NULL,
+    english TEXT NOT NULL,
+    chinese TEXT NOT NULL,
+    mode TEXT NOT NULL,
+    grade TEXT,
+    reveal_ms INTEGER,
+    response_ms INTEGER,
+    reviewed_at TEXT NOT NULL
+);
+CREATE INDEX reviews_word ON reviews(group_name, table_name, english, chinese);
+CREATE INDEX reviews_time ON reviews(reviewed_at);
+";
+
+// 一个升级步骤：把数据库升级到下一个版本
+type Upgrade = fn(&Transaction) -> rusqlite::Result<()>;
+
+// 第 i 项把版本 i+1 的数据库升级到版本 i+2；新建的数据库先建立版本 1 的结构再逐步升级
+const UPGRADES: [Upgrade; (SCHEMA_VERSION - 1) as usize] = [upgrade_v2, upgrade_v3, upgrade_v4, upgrade_v5, upgrade_v6];
+
+// 版本 2：单词的音标、词性、其他释义、例句、笔记与标签（列表以 JSON 数组保存）
+fn upgrade_v2(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+ALTER TABLE words ADD COLUMN phonetic TEXT NOT NULL DEFAULT '';
+ALTER TABLE words ADD COLUMN part_of_speech TEXT NOT NULL DEFAULT '';
+ALTER TABLE words ADD COLUMN meanings TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE words ADD COLUMN examples TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE words ADD COLUMN notes TEXT NOT NULL DEFAULT '';
+ALTER TABLE words ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';
+",
+    )
+}
+
+// 版本 3：分组、单词表与单词的标识（UUID 文本），复习记录按名称指向单词的标识
+fn upgrade_v3(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+ALTER TABLE word_groups ADD COLUMN uuid TEXT;
+ALTER TABLE word_tables ADD COLUMN uuid TEXT;
+ALTER TABLE words ADD COLUMN uuid TEXT;
+ALTER TABLE reviews ADD COLUMN word_id TEXT;
+",
+    )?;
+    for table in ["word_groups", "word_tables", "words"] {
+        let rows: Vec<i64> = tx
+            .prepare(&format!("SELECT id FROM {}", table))?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        for row in rows {
+            tx.execute(&format!("UPDATE {} SET uuid = ?1 WHERE id = ?2", table), params![Uuid::new_v4().to_string(), row])?;
+        }
+    }
+    // 名称相同的重复单词取位置最靠前的
+    tx.execute_batch(
+        "
+UPDATE reviews SET word_id = (
+    SELECT w.uuid FROM words w
+    JOIN word_tables t ON w.table_id = t.id
+    JOIN word_groups g ON t.group_id = g.id
+    WHERE g.name = reviews.group_name AND t.name = reviews.table_name
+      AND w.english = reviews.english AND w.chinese = reviews.chinese
+    ORDER BY t.position, w.position LIMIT 1
+);
+CREATE UNIQUE INDEX word_groups_uuid ON word_groups(uuid);
+CREATE UNIQUE INDEX word_tables_uuid ON word_tables(uuid);
+CREATE UNIQUE INDEX words_uuid ON words(uuid);
+CREATE INDEX reviews_word_id ON reviews(word_id);
+",
+    )
+}
+
+// 版本 4：分组的手动顺序（初始按名称排列）与分组、单词表的创建时间（早期的为 NULL）
+fn upgrade_v4(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+ALTER TABLE word_groups ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE word_groups ADD COLUMN created_at TEXT;
+ALTER TABLE word_tables ADD COLUMN created_at TEXT;
+UPDATE word_groups SET position = (SELECT COUNT(*) FROM word_groups g WHERE g.name < word_groups.name);
+",
+    )
+}
+
+// 版本 5：分组中的文件夹；上级文件夹与单词表所在的文件夹以 UUID 文本指向，原有单词表都直接位于分组下
+fn upgrade_v5(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "
+CREATE TABLE folders (
+    id INTEGER PRIMARY KEY,
+    group_id INTEGER NOT NULL REFERENCES word_groups(id) ON DELETE CASCADE,
+    uuid TEXT NOT NULL UNIQUE,
+    parent TEXT,               -- 上级文件夹，NULL 表示直接位于分组下
+    name TEXT NOT NULL,
+    position INTEGER NOT NULL, -- 在分组的全部文件夹中的顺序
+    created_at TEXT
+);
+CREATE INDEX folders_group ON folders(group_id, position);
+ALTER TABLE word_tables ADD COLUMN folder TEXT;
+",
+    )
+}
+
+// 版本 6：单词表在文本编辑器中使用的分隔符，原有单词表为自动
+fn upgrade_v6(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch("ALTER TABLE word_tables ADD COLUMN separator TEXT NOT NULL DEFAULT 'Smart';")
+}
+
+// 单词的各列，读取、插入与更新使用同样的顺序
+const WORD_COLUMNS: &str = "uuid, english, chinese, ease, interval, repetitions, due, stability, difficulty, last_review,
+    phonetic, part_of_speech, meanings, examples, notes, tags";
+
+// SQLite 后端：分组、单词表、单词与复习记录各占一张表；保存时与上次保存的内容比较，只写入变化的行
+pub struct SqliteStorage {
+    path: PathBuf,
+    conn: Option<Connection>, // 第一次读写时打开
+    saved: FlashMemory,       // 数据库中当前的内容
+}
+
+impl SqliteStorage {
+    pub fn new(path: &Path) -> Self {
+        SqliteStorage { path: path.to_path_buf(), conn: None, saved: FlashMemory::new() }
+    }
+
+    fn connect(path: &Path) -> Result<Connection, Box<dyn std::error::Error>> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+        let version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            return Err(format!(
+                "单词库来自更新版本的应用（数据库版本 {}，当前最高支持 {}），请升级应用后再打开",
+                version, SCHEMA_VERSION
+            )
+            .into());
+        }
+        if version < SCHEMA_VERSION {
+            let tx = conn.unchecked_transaction()?;
+            if version == 0 {
+                tx.execute_batch(SCHEMA)?;
+            }
+            for upgrade in &UPGRADES[version.max(1) as usize - 1..] {
+                upgrade(&tx)?;
+            }
+            tx.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+            tx.commit()?;
+        }
+        Ok(conn)
+    }
+
+    fn conn(&mut self) -> Result<&mut Connection, Box<dyn std::error::Error>> {
+        if self.conn.is_none() {
+            self.conn = Some(Self::connect(&self.path)?);
+        }
+        Ok(self.conn.as_mut().expect("连接已打开"))
+    }
+
+    // 批量写入复习记录（迁移时使用），全部成功或全部不写
+    pub fn import_reviews(&mut self, records: &[ReviewRecord]) -> Result<(), Box<dyn std::error::Error>> {
+        let tx = self.conn()?.transaction()?;
+        for record in records {
+            insert_review(&tx, record)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    #[cfg(test)]
+    fn total_changes(&mut self) -> u64 {
+        self.conn().unwrap().total_changes()
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn kind(&self) -> StorageKind {
+        StorageKind::Sqlite
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn load(&mut self) -> Result<FlashMemory, Box<dyn std::error::Error>> {
+        let memory = read_memory(self.conn()?)?;
+        self.saved = memory.clone();
+        Ok(memory)
+    }
+
+    fn save(&mut self, memory: &FlashMemory) -> Result<(), Box<dyn std::error::Error>> {
+        self.conn()?;
+        let conn = self.conn.as_mut().expect("连接已打开");
+        let tx = conn.transaction()?;
+        write_changes(&tx, &self.saved, memory)?;
+        tx.commit()?;
+        self.saved = memory.clone();
+        Ok(())
+    }
+
+    fn load_reviews(&mut self) -> Result<Vec<ReviewRecord>, Box<dyn std::error::Error>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT group_name, table_name, english, chinese, mode, grade, reveal_ms, response_ms, reviewed_at, word_id
+             FROM reviews ORDER BY id",
+        )?;
+        let records = stmt
+            .query_map([], |row| {
+                Ok(ReviewRecord {
+                    word_id: row.get::<_, Option<String>>(9)?.map(uuid_from).transpose()?,
+                    group: row.get(0)?,
+                    table: row.get(1)?,
+                    english: row.get(2)?,
+                    chinese: row.get(3)?,
+                    mode: from_text(row.get(4)?)?,
+                    grade: row.get::<_, Option<String>>(5)?.map(from_text).transpose()?,
+                    reveal_ms: row.get(6)?,
+                    response_ms: row.get(7)?,
+                    reviewed_at: row.get(8)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(records)
+    }
+
+    fn append_review(&mut self, record: &ReviewRecord) -> Result<(), Box<dyn std::error::Error>> {
+        insert_review(self.conn()?, record)?;
+        Ok(())
+    }
+
+    fn replace_reviews(&mut self, records: &[ReviewRecord]) -> Result<(), Box<dyn std::error::Error>> {
+        let tx = self.conn()?.transaction()?;
+        tx.execute("DELETE FROM reviews", [])?;
+        for record in records {
+            insert_review(&tx, record)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+// 把 JSON 单词库与复习记录一次性写入新的 SQLite 文件；失败时删除写了一半的文件
+pub fn migrate_to_sqlite(
+    memory: &FlashMemory,
+    reviews: &[ReviewRecord],
+    db_path: &Path,
+) -> Result<SqliteStorage, Box<dyn std::error::Error>> {
+    if db_path.exists() {
+        return Err(format!("{} 已存在", db_path.display()).into());
+    }
+    let mut storage = SqliteStorage::new(db_path);
+    let result = storage.save(memory).and_then(|_| storage.import_reviews(reviews));
+    if let Err(e) = result {
+        drop(storage);
+        let _ = std::fs::remove_file(db_path);
+        return Err(e);
+    }
+    Ok(storage)
+}
+
+// 枚举以名称保存（如 "Good"、"Review"），与 JSON 中的写法一致
+fn to_text<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn from_text<T: DeserializeOwned>(text: String) -> rusqlite::Result<T> {
+    serde_json::from_value(serde_json::Value::String(text))
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, Box::new(e)))
+}
+
+// 标识以 UUID 文本保存
+fn uuid_from(text: String) -> rusqlite::Result<Uuid> {
+    Uuid::parse_str(&text).map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, Box::new(e)))
+}
+
+// 列表类字段以 JSON 数组保存
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn from_json<T: DeserializeOwned>(text: String) -> rusqlite::Result<T> {
+    serde_json::from_str(&text).map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, Box::new(e)))
+}
+
+fn insert_review(conn: &Connection, record: &ReviewRecord) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO reviews (group_name, table_name, english, chinese, mode, grade, reveal_ms, response_ms, reviewed_at, word_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            record.group,
+            record.table,
+            record.english,
+            record.chinese,
+            to_text(&record.mode),
+            record.grade.map(|g| to_text(&g)),
+            record.reveal_ms,
+            record.response_ms,
+            record.reviewed_at,
+            record.word_id.map(|id| id.to_string()),
+        ],
+    )?;
+    Ok(())
+}
+
+fn read_memory(conn: &Connection) -> rusqlite::Result<FlashMemory> {
+    let mut memory = FlashMemory::new();
+
+    let mut group_names = HashMap::new();
+    let mut stmt = conn.prepare(
+        "SELECT id, uuid, name, algorithm, target_retention, fsrs_weights, created_at FROM word_groups ORDER BY position, name",
+    )?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let id: i64 = row.get(0)?;
+        let name: String = row.get(2)?;
+        if let Some(algorithm) = row.get::<_, Option<String>>(3)? {
+            let weights: Option<String> = row.get(5)?;
+            let settings = SchedulerSettings {
+                algorithm: from_text(algorithm)?,
+                target_retention: row.get::<_, Option<f64>>(4)?.unwrap_or(0.9),
+                fsrs_weights: weights.and_then(|w| serde_json::from_str(&w).ok()),
+            };
+            memory.scheduler_settings.insert(name.clone(), settings);
+        }
+        memory.groups.insert(name.clone(), Vec::new());
+        memory.group_ids.insert(name.clone(), uuid_from(row.get(1)?)?);
+        memory.group_order.push(name.clone());
+        if let Some(created) = row.get(6)? {
+            memory.group_created.insert(name.clone(), created);
+        }
+        group_names.insert(id, name);
+    }
+
+    let mut stmt = conn.prepare("SELECT group_id, uuid, parent, name, created_at FROM folders ORDER BY group_id, position")?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let Some(group) = group_names.get(&row.get::<_, i64>(0)?) else { continue };
+        memory.folders.entry(group.clone()).or_default().push(Folder {
+            id: uuid_from(row.get(1)?)?,
+            parent: row.get::<_, Option<String>>(2)?.map(uuid_from).transpose()?,
+            name: row.get(3)?,
+            created: row.get(4)?,
+        });
+    }
+
+    let mut table_index = HashMap::new();
+    let mut stmt = conn.prepare(
+        "SELECT id, group_id, uuid, name, countdown, front, back, gap, adaptive, created_at, folder, separator FROM word_tables
+         ORDER BY group_id, position",
+    )?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let group_id: i64 = row.get(1)?;
+        let Some(group) = group_names.get(&group_id) else { continue };
+        let tables = memory.groups.get_mut(group).expect("分组已读取");
+        tables.push(WordTable {
+            id: uuid_from(row.get(2)?)?,
+            name: row.get(3)?,
+            words: Vec::new(),
+            timing: FlashTiming {
+                countdown: row.get(4)?,
+                front: row.get::<_, f64>(5)? as f32,
+                back: row.get::<_, f64>(6)? as f32,
+                gap: row.get::<_, f64>(7)? as f32,
+                adaptive: row.get(8)?,
+            },
+            separator: from_text(row.get(11)?)?,
+            created: row.get(9)?,
+            folder: row.get::<_, Option<String>>(10)?.map(uuid_from).transpose()?,
+        });
+        table_index.insert(row.get::<_, i64>(0)?, (group.clone(), tables.len() - 1));
+    }
+
+    let mut stmt = conn.prepare(&format!("SELECT table_id, {} FROM words ORDER BY table_id, position", WORD_COLUMNS))?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let Some((group, idx)) = table_index.get(&row.get::<_, i64>(0)?) else { continue };
+        let word = Word {
+            id: uuid_from(row.get(1)?)?,
+            english: row.get(2)?,
+            chinese: row.get(3)?,
+            group: group.clone(),
+            schedule: Schedule {
+                ease: row.get::<_, f64>(4)? as f32,
+                interval: row.get(5)?,
+                repetitions: row.get(6)?,
+                due: row.get(7)?,
+                stability: row.get(8)?,
+                difficulty: row.get(9)?,
+                last_review: row.get(10)?,
+            },
+            phonetic: row.get(11)?,
+            part_of_speech: row.get(12)?,
+            meanings: from_json(row.get(13)?)?,
+            examples: from_json(row.get(14)?)?,
+            notes: row.get(15)?,
+            tags: from_json(row.get(16)?)?,
+        };
+        memory.groups.get_mut(group).expect("分组已读取")[*idx].words.push(word);
+    }
+    Ok(memory)
+}
+
+// 比较上次保存的内容与当前内容，只写入变化的分组、单词表与单词；三者都按标识对应，
+// 重命名与移动位置只更新对应的列
+fn write_changes(tx: &Transaction, old: &FlashMemory, new: &FlashMemory) -> rusqlite::Result<()> {
+    let old_names: HashMap<Uuid, &String> = old.group_ids.iter().map(|(name, id)| (*id, name)).collect();
+    for id in old.group_ids.values() {
+        if !new.group_ids.values().any(|v| v == id) {
+            tx.execute("DELETE FROM word_groups WHERE uuid = ?1", params![id.to_string()])?;
+        }
+    }
+    // 改名后的名称仍被其他分组占用，或原名称被其他分组使用（如两个分组互换名称、改名后新建同名分组）时，
+    // 先改成以标识命名的临时名称，再按下面的顺序写入就不会违反名称的唯一约束
+    for (name, id) in &new.group_ids {
+        let Some(old_name) = old_names.get(id) else { continue };
+        if *old_name != name && (old.group_ids.contains_key(name) || new.group_ids.contains_key(*old_name)) {
+            tx.execute("UPDATE word_groups SET name = ?1 WHERE uuid = ?1", params![id.to_string()])?;
+        }
+    }
+
+    for (name, tables) in &new.groups {
+        let id = new
+            .group_id(name)
+            .ok_or_else(|| rusqlite::Error::ToSqlConversionFailure(format!("分组 {} 缺少标识", name).into()))?;
+        let position = new.group_order.iter().position(|n| n == name).unwrap_or(new.group_order.len());
+        let old_name = old_names.get(&id).copied();
+        match old_name {
+            None => {
+                tx.execute(
+                    "INSERT INTO word_groups (uuid, name, position, created_at) VALUES (?1, ?2, ?3, ?4)",
+                    params![id.to_string(), name, position, new.group_created.get(name)],
+                )?;
+            }
+            Some(old_name) if old_name != name || old.group_order.iter().position(|n| n == old_name) != Some(position) => {
+                tx.execute(
+                    "UPDATE word_groups SET name = ?1, position = ?2 WHERE uuid = ?3",
+                    params![name, position, id.to_string()],
+                )?;
+            }
+            Some(_) => {}
+        }
+        let group_row: i64 =
+            tx.query_row("SELECT id FROM word_groups WHERE uuid = ?1", params![id.to_string()], |row| row.get(0))?;
+
+        let settings = new.scheduler_settings.get(name);
+        if old_name.is_none() || old.scheduler_settings.get(old_name.unwrap_or(name)) != settings {
+            tx.execute(
+                "UPDATE word_groups SET algorithm = ?1, target_retention = ?2, fsrs_weights = ?3 WHERE id = ?4",
+                params![
+                    settings.map(|s| to_text(&s.algorithm)),
+                    settings.map(|s| s.target_retention),
+                    settings.and_then(|s| s.fsrs_weights.as_ref()).map(|w| serde_json::to_string(w).unwrap_or_default()),
+                    group_row,
+                ],
+            )?;
+        }
+
+        // 文件夹数量少，有变化时整组重写
+        let folders = new.get_folders_in_group(name);
+        if old_name.is_none_or(|n| old.get_folders_in_group(n) != folders) {
+            tx.execute("DELETE FROM folders WHERE group_id = ?1", params![group_row])?;
+            for (position, folder) in folders.iter().enumerate() {
+                tx.execute(
+                    "INSERT INTO folders (group_id, uuid, parent, name, position, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        group_row,
+                        folder.id.to_string(),
+                        folder.parent.map(|p| p.to_string()),
+                        folder.name,
+                        position,
+                        folder.created,
+                    ],
+                )?;
+            }
+        }
+
+        let old_tables = old_name.and_then(|n| old.groups.get(n)).map(Vec::as_slice).unwrap_or(&[]);
+        write_tables(tx, group_row, old_tables, tables)?;
+    }
+    Ok(())
+}
+
+fn write_tables(tx: &Transaction, group_row: i64, old: &[WordTable], new: &[WordTable]) -> rusqlite::Result<()> {
+    for table in old.iter().filter(|t| !new.iter().any(|n| n.id == t.id)) {
+        tx.execute("DELETE FROM word_tables WHERE uuid = ?1", params![table.id.to_string()])?;
+    }
+    for (position, table) in new.iter().enumerate() {
+        let t = &table.timing;
+        match old.iter().position(|o| o.id == table.id) {
+            Some(old_position) => {
+                let previous = &old[old_position];
+                if old_position != position
+                    || previous.name != table.name
+                    || previous.timing != table.timing
+                    || previous.folder != table.folder
+                    || previous.separator != table.separator
+                {
+                    tx.execute(
+                        "UPDATE word_tables SET name = ?1, position = ?2, countdown = ?3, front = ?4, back = ?5, gap = ?6,
+                         adaptive = ?7, folder = ?8, separator = ?9 WHERE uuid = ?10",
+                        params![
+                            table.name, position, t.countdown, t.front, t.back, t.gap, t.adaptive,
+                            table.folder.map(|f| f.to_string()), to_text(&table.separator), table.id.to_string(),
+                        ],
+                    )?;
+                }
+                let table_row: i64 = tx.query_row(
+                    "SELECT id FROM word_tables WHERE uuid = ?1",
+                    params![table.id.to_string()],
+                    |row| row.get(0),
+                )?;
+                write_words(tx, table_row, &previous.words, &table.words)?;
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO word_tables (group_id, uuid, name, position, countdown, front, back, gap, adaptive, created_at,
+                     folder, separator) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                    params![
+                        group_row, table.id.to_string(), table.name, position, t.countdown, t.front, t.back, t.gap,
+                        t.adaptive, table.created, table.folder.map(|f| f.to_string()), to_text(&table.separator),
+                    ],
+                )?;
+                write_words(tx, tx.last_insert_rowid(), &[], &table.words)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// 比较时忽略单词的 group 字段：它由所在分组决定，数据库中不单独保存
+fn same_word(a: &Word, b: &Word) -> bool {
+    a.id == b.id
+        && a.english == b.english
+        && a.chinese == b.chinese
+        && a.schedule == b.schedule
+        && a.phonetic == b.phonetic
+        && a.part_of_speech == b.part_of_speech
+        && a.meanings == b.meanings
+        && a.examples == b.examples
+        && a.notes == b.notes
+        && a.tags == b.tags
+}
+
+// 单词按标识对应：消失的删除，新出现的插入，内容或位置变化的更新
+fn write_words(tx: &Transaction, table_row: i64, old: &[Word], new: &[Word]) -> rusqlite::Result<()> {
+    let old_positions: HashMap<Uuid, usize> = old.iter().enumerate().map(|(i, w)| (w.id, i)).collect();
+    for word in old.iter().filter(|w| !new.iter().any(|n| n.id == w.id)) {
+        tx.execute("DELETE FROM words WHERE uuid = ?1", params![word.id.to_string()])?;
+    }
+    // 移动的单词先放到负数位置，避免逐行更新时与 (table_id, position) 唯一约束冲突
+    for (position, word) in new.iter().enumerate() {
+        if old_positions.get(&word.id).is_some_and(|&p| p != position) {
+            tx.execute(
+                "UPDATE words SET position = ?1 WHERE uuid = ?2",
+                params![-1 - position as i64, word.id.to_string()],
+            )?;
+        }
+    }
+
+    // ?1、?2 为单词表与位置，?3 起按 WORD_COLUMNS 的顺序
+    let values = "?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18";
+    let update = format!("UPDATE words SET (position, {}) = (?2, {}) WHERE table_id = ?1 AND uuid = ?3", WORD_COLUMNS, values);
+    let insert = format!("INSERT INTO words (table_id, position, {}) VALUES (?1, ?2, {})", WORD_COLUMNS, values);
+    for (position, word) in new.iter().enumerate() {
+        let sql = match old_positions.get(&word.id) {
+            Some(&p) if p == position && same_word(&old[p], word) => continue,
+            Some(_) => &update,
+            None => &insert,
+        };
+        let s = &word.schedule;
+        tx.execute(
+            sql,
+            params![
+                table_row, position, word.id.to_string(), word.english, word.chinese, s.ease, s.interval,
+                s.repetitions, s.due, s.stability, s.difficulty, s.last_review, word.phonetic, word.part_of_speech,
+                to_json(&word.meanings), to_json(&word.examples), word.notes, to_json(&word.tags),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::model::Example;
+    use crate::parse::Separator;
+    use crate::review_log::StudyMode;
+    use crate::scheduler::{Algorithm, Grade};
+
+    fn table(memory: &FlashMemory, group: &str, name: &str) -> Uuid {
+        memory.find_word_table(group, None, name).unwrap().id
+    }
+
+    fn sample() -> FlashMemory {
+        let mut memory = FlashMemory::new();
+        let first = memory.create_word_table("英语", None, "第一课").unwrap();
+        let second = memory.create_word_table("英语", None, "第二课").unwrap();
+        memory.create_group_if_absent("空分组");
+        memory
+            .add_words_to_table("英语", first, vec![Word::new("apple", "苹果", "英语"), Word::new("pear", "梨", "英语")])
+            .unwrap();
+        let mut book = Word::new("book", "书", "英语");
+        book.phonetic = "bʊk".to_string();
+        book.part_of_speech = "n.".to_string();
+        book.meanings = vec!["书籍".to_string(), "预订".to_string()];
+        book.examples = vec![Example { sentence: "Read a book.".to_string(), translation: "读一本书。".to_string() }];
+        book.notes = "也可作动词".to_string();
+        book.tags = vec!["初级".to_string()];
+        memory.add_words_to_table("英语", second, vec![book]).unwrap();
+        // 文件夹中的单词表可以与分组下的单词表同名
+        let unit = memory.create_folder("英语", None, "第一单元").unwrap();
+        let section = memory.create_folder("英语", Some(unit), "第一节").unwrap();
+        let nested = memory.create_word_table("英语", Some(section), "第一课").unwrap();
+        memory.add_words_to_table("英语", nested, vec![Word::new("cat", "猫", "英语")]).unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let apple = memory.get_word_table("英语", first).unwrap().words[0].clone();
+        memory.grade_word("英语", &apple, Grade::Good, today).unwrap();
+        memory.set_word_table_timing("英语", second, FlashTiming { gap: 0.5, adaptive: true, ..Default::default() }).unwrap();
+        memory.set_word_table_separator("英语", second, Separator::Comma).unwrap();
+        memory.scheduler_settings.insert(
+            "英语".to_string(),
+            SchedulerSettings { algorithm: Algorithm::Fsrs, target_retention: 0.85, fsrs_weights: Some(vec![0.5; 17]) },
+        );
+        memory
+    }
+
+    fn reopen(path: &Path) -> FlashMemory {
+        SqliteStorage::new(path).load().unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.db");
+        let memory = sample();
+        let mut storage = SqliteStorage::new(&path);
+        assert_eq!(storage.load().unwrap(), FlashMemory::new());
+        storage.save(&memory).unwrap();
+        assert_eq!(reopen(&path), memory);
+    }
+
+    #[test]
+    fn edits_write_only_changed_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.db");
+        let mut memory = sample();
+        let mut storage = SqliteStorage::new(&path);
+        storage.save(&memory).unwrap();
+
+        let changes = |storage: &mut SqliteStorage, memory: &FlashMemory| {
+            let before = storage.total_changes();
+            storage.save(memory).unwrap();
+            storage.total_changes() - before
+        };
+        assert_eq!(changes(&mut storage, &memory), 0);
+
+        let today = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        let pear = memory.find_word_table("英语", None, "第一课").unwrap().words[1].clone();
+        memory.grade_word("英语", &pear, Grade::Easy, today).unwrap();
+        assert_eq!(changes(&mut storage, &memory), 1);
+
+        memory.rename_group("英语", "English").unwrap();
+        assert_eq!(changes(&mut storage, &memory), 1);
+
+        let lesson2 = table(&memory, "English", "第二课");
+        memory.rename_word_table("English", lesson2, "Lesson 2").unwrap();
+        assert_eq!(changes(&mut storage, &memory), 1);
+        memory.set_word_table_separator("English", lesson2, Separator::Tab).unwrap();
+        assert_eq!(changes(&mut storage, &memory), 1);
+
+        // 调整顺序只更新交换位置的两行
+        memory.move_group("空分组", 0).unwrap();
+        assert_eq!(changes(&mut storage, &memory), 2);
+        memory.move_word_table("English", lesson2, None, 0).unwrap();
+        assert_eq!(changes(&mut storage, &memory), 2);
+
+        // 把单词表移入文件夹只更新该单词表一行
+        let unit = memory.child_folders("English", None)[0].id;
+        memory.move_word_table("English", lesson2, Some(unit), 0).unwrap();
+        assert_eq!(changes(&mut storage, &memory), 1);
+
+        assert_eq!(reopen(&path), memory);
+    }
+
+    #[test]
+    fn swapped_and_reused_group_names_are_saved_at_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.db");
+        let mut memory = sample();
+        let mut storage = SqliteStorage::new(&path);
+        storage.save(&memory).unwrap();
+
+        // 两个分组互换名称后一次保存
+        memory.rename_group("英语", "临时").unwrap();
+        memory.rename_group("空分组", "英语").unwrap();
+        memory.rename_group("临时", "空分组").unwrap();
+        storage.save(&memory).unwrap();
+        assert_eq!(reopen(&path), memory);
+
+        // 改名后新建原名称的分组，一次保存
+        memory.rename_group("空分组", "English").unwrap();
+        memory.create_word_table("空分组", None, "第一课").unwrap();
+        storage.save(&memory).unwrap();
+        let loaded = reopen(&path);
+        assert_eq!(loaded, memory);
+        assert_eq!(loaded.find_word_table("English", None, "第一课").unwrap().words.len(), 2);
+    }
+
+    #[test]
+    fn reordered_words_keep_their_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.db");
+        let mut memory = sample();
+        let mut storage = SqliteStorage::new(&path);
+        storage.save(&memory).unwrap();
+
+        let mut words = memory.find_word_table("英语", None, "第一课").unwrap().words.clone();
+        words.reverse();
+        words.insert(1, Word::new("banana", "香蕉", "英语"));
+        memory.add_words_to_table("英语", table(&memory, "英语", "第一课"), words).unwrap();
+        storage.save(&memory).unwrap();
+        let loaded = reopen(&path);
+        assert_eq!(loaded, memory);
+        let english: Vec<_> = loaded.find_word_table("英语", None, "第一课").unwrap().words.iter().map(|w| w.english.clone()).collect();
+        assert_eq!(english, vec!["pear", "banana", "apple"]);
+    }
+
+    #[test]
+    fn deletes_and_replacements_are_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.db");
+        let mut memory = sample();
+        let mut storage = SqliteStorage::new(&path);
+        storage.save(&memory).unwrap();
+
+        memory.add_words_to_table("英语", table(&memory, "英语", "第一课"), vec![Word::new("banana", "香蕉", "英语")]).unwrap();
+        memory.delete_word_table("英语", table(&memory, "英语", "第二课")).unwrap();
+        memory.create_word_table("英语", None, "第三课").unwrap();
+        memory.delete_group("空分组").unwrap();
+        storage.save(&memory).unwrap();
+        assert_eq!(reopen(&path), memory);
+
+        // 删除文件夹时其中的子文件夹与单词表一并删除
+        let unit = memory.child_folders("英语", None)[0].id;
+        memory.create_folder("英语", None, "第二单元").unwrap();
+        memory.delete_folder("英语", unit).unwrap();
+        storage.save(&memory).unwrap();
+        let loaded = reopen(&path);
+        assert_eq!(loaded, memory);
+        assert_eq!(loaded.get_folders_in_group("英语").len(), 1);
+        assert_eq!(loaded.get_word_tables_in_group("英语").unwrap().len(), 2);
+
+        memory.delete_group("英语").unwrap();
+        storage.save(&memory).unwrap();
+        assert_eq!(reopen(&path), FlashMemory::new());
+    }
+
+    #[test]
+    fn reviews_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = SqliteStorage::new(&dir.path().join("words.db"));
+        let record = ReviewRecord {
+            word_id: Some(Uuid::new_v4()),
+            group: "英语".to_string(),
+            table: "第一课".to_string(),
+            english: "apple".to_string(),
+            chinese: "苹果".to_string(),
+            mode: StudyMode::Typing,
+            grade: Some(Grade::Hard),
+            reveal_ms: Some(1200),
+            response_ms: None,
+            reviewed_at: chrono::Local::now(),
+        };
+        storage.append_review(&record).unwrap();
+        storage.append_review(&ReviewRecord { grade: None, mode: StudyMode::Flash, ..record.clone() }).unwrap();
+        let loaded = storage.load_reviews().unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].mode, StudyMode::Typing);
+        assert_eq!(loaded[0].grade, Some(Grade::Hard));
+        assert_eq!(loaded[0].reveal_ms, Some(1200));
+        assert_eq!(loaded[0].reviewed_at, record.reviewed_at);
+        assert_eq!(loaded[1].grade, None);
+    }
+
+    #[test]
+    fn migrate_copies_words_and_reviews() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.db");
+        let memory = sample();
+        let review = ReviewRecord {
+            word_id: None,
+            group: "英语".to_string(),
+            table: "第一课".to_string(),
+            english: "apple".to_string(),
+            chinese: "苹果".to_string(),
+            mode: StudyMode::Review,
+            grade: Some(Grade::Good),
+            reveal_ms: None,
+            response_ms: None,
+            reviewed_at: chrono::Local::now(),
+        };
+        let mut storage = migrate_to_sqlite(&memory, std::slice::from_ref(&review), &path).unwrap();
+        assert_eq!(storage.load_reviews().unwrap().len(), 1);
+        assert_eq!(reopen(&path), memory);
+        // 不会覆盖已有的数据库
+        assert!(migrate_to_sqlite(&memory, &[], &path).is_err());
+        assert_eq!(reopen(&path), memory);
+    }
+
+    #[test]
+    fn version_1_database_is_upgraded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.db");
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(SCHEMA).unwrap();
+            conn.pragma_update(None, "user_version", 1).unwrap();
+            conn.execute("INSERT INTO word_groups (name) VALUES ('英语')", []).unwrap();
+            conn.execute("INSERT INTO word_groups (name) VALUES ('日语')", []).unwrap();
+            conn.execute(
+                "INSERT INTO word_tables (group_id, name, position, countdown, front, back, gap, adaptive)
+                 VALUES (1, '第一课', 0, 3, 1.0, 1.0, 0.0, 0)",
+                [],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO words (table_id, position, english, chinese, ease, interval, repetitions, stability, difficulty)
+                 VALUES (1, 0, 'apple', '苹果', 2.5, 0, 0, 0.0, 0.0)",
+                [],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO reviews (group_name, table_name, english, chinese, mode, grade, reviewed_at)
+                 VALUES ('英语', '第一课', 'apple', '苹果', 'Review', 'Good', '2024-03-01T08:00:00+08:00')",
+                [],
+            )
+            .unwrap();
+        }
+        let mut storage = SqliteStorage::new(&path);
+        let memory = storage.load().unwrap();
+        let words = &memory.find_word_table("英语", None, "第一课").unwrap().words;
+        assert_eq!(words.len(), 1);
+        assert_eq!((words[0].english.as_str(), words[0].chinese.as_str()), ("apple", "苹果"));
+        assert!(!words[0].has_details());
+        assert_eq!(memory.find_word_table("英语", None, "第一课").unwrap().separator, Separator::Smart);
+        // 分组按名称排出初始的手动顺序
+        assert_eq!(memory.get_groups(), vec!["日语", "英语"]);
+        let version: i64 = storage.conn().unwrap().pragma_query_value(None, "user_version", |row| row.get(0)).unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+        // 升级时分配的标识指向同一个单词
+        assert_eq!(storage.load_reviews().unwrap()[0].word_id, Some(words[0].id));
+        assert_eq!(reopen(&path), memory);
+    }
+
+    #[test]
+    fn newer_schema_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.db");
+        Connection::open(&path).unwrap().pragma_update(None, "user_version", SCHEMA_VERSION + 1).unwrap();
+        let err = SqliteStorage::new(&path).load().unwrap_err();
+        assert!(err.to_string().contains("更新版本"));
+    }
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+// 闪记节奏设置，按单词表保存（时长单位为秒）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlashTiming {
+    pub countdown: u32, // 开始前倒计时秒数，0 表示直接开始
+    pub front: f32,     // 只显示英文的时长
+    pub back: f32,      // 显示释义的时长
+    pub gap: f32,       // 两张卡片之间的空白时长
+    pub adaptive: bool, // 长单词与上次答错的单词延长展示时间
+}
+
+impl Default for FlashTiming {
+    fn default() -> Self {
+        FlashTiming {
+            countdown: 3,
+            front: 1.0,
+            back: 1.0,
+            gap: 0.0,
+            adaptive: false,
+        }
+    }
+}
+
+impl FlashTiming {
+    // 返回某个单词的 (正面时长, 背面时长)
+    pub fn durations(&self, english: &str, missed: bool) -> (f32, f32) {
+        if !self.adaptive {
+            return (self.front, self.back);
+        }
+        // 超过 6 个字母后每个字母多 0.1 秒，最多多 1 秒
+        let extra = (english.chars().count().saturating_sub(6) as f32 * 0.1).min(1.0);
+        let factor = if missed { 1.5 } else { 1.0 };
+        ((self.front + extra) * factor, self.back * factor)
+    }
+}